@@ -0,0 +1,87 @@
+use crate::audit::read::read as read_audit;
+use crate::swagger::SecurityAddon;
+use actix_web::web::{scope, ServiceConfig};
+use actix_web::HttpRequest;
+use chrono::{DateTime, Utc};
+use common::audit::AuditEvent as Event;
+use serde::{Deserialize, Serialize};
+use utoipa::{OpenApi, ToSchema};
+use uuid::Uuid;
+
+pub(crate) mod read;
+
+/// Header a client identifies its requests with, recorded with the changes they make
+const REQUEST_ID: &str = "X-Request-Id";
+
+/// Longest request identifier recorded, longer ones are ignored
+const REQUEST_ID_MAX_LENGTH: usize = 128;
+
+pub(crate) fn init(cfg: &mut ServiceConfig) {
+    cfg.service(scope("").service(read_audit));
+}
+
+/// Value of the `X-Request-Id` header of `request`, if any.
+pub(crate) fn request_id(request: &HttpRequest) -> Option<String> {
+    request
+        .headers()
+        .get(REQUEST_ID)
+        .and_then(|value| value.to_str().ok())
+        .filter(|value| !value.is_empty() && value.len() <= REQUEST_ID_MAX_LENGTH)
+        .map(String::from)
+}
+
+/// Change of a record and who made it
+#[derive(Serialize, Deserialize, ToSchema, Debug, Clone)]
+pub struct AuditEvent {
+    pub id: Uuid,
+    /// Id of the user who made the change
+    pub actor: String,
+    /// `create`, `update`, `patch`, `delete`, `restore` or `purge`
+    pub action: String,
+    pub resource: String,
+    pub entity_id: String,
+    /// Changed fields, each with its value `before` and `after` the change,
+    /// e.g. `{"email": {"before": "jane@example.com", "after": "jane.doe@example.com"}}`
+    #[schema(value_type = Object)]
+    pub changes: serde_json::Value,
+    /// `X-Request-Id` header of the request which made the change
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub request_id: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<Box<dyn Event>> for AuditEvent {
+    fn from(value: Box<dyn Event>) -> Self {
+        Self {
+            id: value.get_id().parse().unwrap_or_default(),
+            actor: value.get_actor(),
+            action: value.get_action(),
+            resource: value.get_resource(),
+            entity_id: value.get_entity_id(),
+            changes: serde_json::from_str(value.get_changes().as_str()).unwrap_or_default(),
+            request_id: value.get_request_id(),
+            created_at: value.get_created_at(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, ToSchema, Debug)]
+pub struct AuditSearchResult {
+    pub num_pages: usize,
+    pub total_items: usize,
+    pub page: usize,
+    pub per_page: usize,
+    /// Latest events first
+    pub result: Vec<AuditEvent>,
+}
+
+pub fn api_docs() -> utoipa::openapi::OpenApi {
+    #[derive(OpenApi)]
+    #[openapi(
+    paths(crate::audit::read::read),
+    components(schemas(crate::audit::AuditEvent,crate::audit::AuditSearchResult)),
+    modifiers(&SecurityAddon)
+    )]
+    struct ApiDocs;
+    ApiDocs::openapi()
+}
@@ -0,0 +1,253 @@
+use crate::audit::{AuditEvent, AuditSearchResult};
+use crate::auth::Claims;
+use crate::error::AsHttpError;
+use crate::{AppState, Audit, Authorization};
+use actix_web::web::Data;
+use actix_web::{get, web, HttpResponse, Responder};
+use chrono::{DateTime, Utc};
+use common::audit::AuditSearch;
+use common::error::CoreError;
+use common::management::{DateField, DateRange, Pagination};
+use serde::{Deserialize, Serialize};
+use services_local::audit::AuditManagement;
+use services_local::authorization::RoleManagement;
+use std::fmt::Display;
+use std::sync::Arc;
+use uuid::Uuid;
+
+#[derive(Default, Serialize, Deserialize)]
+pub(crate) struct QueryAudit {
+    /// Id of the user who made the changes
+    pub actor: Option<String>,
+    pub action: Option<String>,
+    pub resource: Option<String>,
+    pub entity_id: Option<String>,
+    pub start_date: Option<DateTime<Utc>>,
+    pub end_date: Option<DateTime<Utc>>,
+    pub page: Option<usize>,
+    pub per_page: Option<usize>,
+    /// Bounds of `per_page`, read from the settings
+    #[serde(skip)]
+    pub default_per_page: usize,
+    #[serde(skip)]
+    pub max_per_page: usize,
+}
+
+impl AuditSearch for QueryAudit {
+    fn get_actor(&self) -> Option<String> {
+        self.actor.to_owned()
+    }
+
+    fn get_action(&self) -> Option<String> {
+        self.action.to_owned()
+    }
+
+    fn get_resource(&self) -> Option<String> {
+        self.resource.to_owned()
+    }
+
+    fn get_entity_id(&self) -> Option<String> {
+        self.entity_id.to_owned()
+    }
+
+    fn get_date_range(&self) -> Result<Option<DateRange>, CoreError> {
+        match (self.start_date, self.end_date) {
+            (Some(start_date), Some(end_date)) => {
+                DateRange::new(DateField::CreatedAt, start_date, end_date).map(Some)
+            }
+            (None, None) => Ok(None),
+            _ => Err(CoreError::DataError(String::from(
+                "start_date and end_date must be given together",
+            ))),
+        }
+    }
+
+    fn get_pagination(&self) -> Result<Pagination, CoreError> {
+        Pagination::new(
+            self.page,
+            self.per_page,
+            self.default_per_page,
+            self.max_per_page,
+        )
+    }
+}
+
+/// Events of the organization `tenant` matching `query` once the `audit:read` permission of `actor` is checked,
+/// `owner` is the user the events are about when there is only one.
+pub(crate) async fn search(
+    actor: String,
+    tenant: Uuid,
+    owner: Option<Box<dyn Display + Sync + Send>>,
+    query: QueryAudit,
+    app_data: &AppState,
+) -> Result<AuditSearchResult, AsHttpError> {
+    let authorization: Authorization<_, _> = Authorization(Box::new(RoleManagement::new(tenant)));
+    authorization
+        .0
+        .authorize(
+            Box::new(actor),
+            String::from("audit:read"),
+            owner,
+            &app_data.db_connection,
+        )
+        .await
+        .map_err(|e| AsHttpError::from(e.get_core_error()))?;
+    let audit: Audit<_, _, _> = Audit(Box::new(AuditManagement::new(tenant)));
+    let events = audit
+        .0
+        .search(
+            Box::new(QueryAudit {
+                default_per_page: app_data.settings.pagination.default_page_size,
+                max_per_page: app_data.settings.pagination.max_page_size,
+                ..query
+            }),
+            &app_data.db_connection,
+        )
+        .await
+        .map_err(|e| AsHttpError::from(e.get_core_error()))?;
+    Ok(AuditSearchResult {
+        num_pages: events.get_num_pages().unwrap_or_default(),
+        total_items: events.get_total_items().unwrap_or_default(),
+        page: events.get_page().unwrap_or(1),
+        per_page: events.get_per_page(),
+        result: events.get_result().map(AuditEvent::from).collect(),
+    })
+}
+
+#[utoipa::path(
+tag = "Audit",
+context_path = "/api/audit",
+params(
+("actor" = Option<String>, Query, description = "Id of the user who made the changes"),
+("action" = Option<String>, Query, description = "`create`, `update`, `patch`, `delete`, `restore` or `purge`"),
+("resource" = Option<String>, Query, description = "Kind of record changed, e.g. `user`"),
+("entity_id" = Option<String>, Query, description = "Id of the record changed"),
+("start_date" = Option<String>, Query, description = "RFC 3339 date of the first event"),
+("end_date" = Option<String>, Query, description = "RFC 3339 date of the last event"),
+("page" = Option<usize>, Query, description = "Page number, starting from 1"),
+("per_page" = Option<usize>, Query, description = "Number of events per page")
+),
+responses((status=200, description = "Audit events list succeed", body = AuditSearchResult),
+(status=400, description = "Filters not correct"),
+(status=401, description = "Authentication required"),
+(status=403, description = "Permission audit:read required")
+),
+security(("api_jwt_token" = []))
+)]
+#[get("")]
+pub(super) async fn read(
+    app_data: Data<Arc<AppState>>,
+    query: web::Query<QueryAudit>,
+    claims: Claims,
+) -> actix_web::Result<impl Responder> {
+    let response = search(
+        claims.sub,
+        claims.tenant,
+        None,
+        query.into_inner(),
+        &app_data,
+    )
+    .await?;
+    Ok(HttpResponse::Ok().json(response))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::audit::read::read;
+    use crate::audit::AuditSearchResult;
+    use crate::tests::{access_token, administrator_id, app_state, test_settings};
+    use crate::user::{NewUser, User};
+    use actix_web::http::header::AUTHORIZATION;
+    use actix_web::http::StatusCode;
+    use actix_web::web::Data;
+    use actix_web::{test, web, App};
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn test_audit_read() {
+        let state = app_state(test_settings()).await;
+        let administrator = administrator_id(&state).await;
+        let authorization = format!(
+            "Bearer {}",
+            access_token(&state.settings, &administrator, 60)
+        );
+
+        let app = test::init_service(
+            App::new()
+                .app_data(Data::new(Arc::clone(&state)))
+                .service(web::scope("/api/user").configure(crate::user::init))
+                .service(web::scope("/api/audit").service(read)),
+        )
+        .await;
+
+        let new_user = NewUser {
+            first_name: "Audrey".to_string(),
+            last_name: "Tor".to_string(),
+            email: "audreytor@example.com".to_string(),
+            password: None,
+        };
+        let req = test::TestRequest::post()
+            .uri("/api/user")
+            .insert_header((AUTHORIZATION, authorization.as_str()))
+            .set_json(&new_user)
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        let user: User = test::read_body_json(resp).await;
+
+        let req = test::TestRequest::delete()
+            .uri(format!("/api/user/{}", user.id).as_str())
+            .insert_header((AUTHORIZATION, authorization.as_str()))
+            .to_request();
+        assert_eq!(
+            test::call_service(&app, req).await.status(),
+            StatusCode::NO_CONTENT
+        );
+
+        // test 200, filtered on the record and the action
+        {
+            let req = test::TestRequest::get()
+                .uri(
+                    format!(
+                        "/api/audit?resource=user&entity_id={}&action=delete&actor={}",
+                        user.id, administrator
+                    )
+                    .as_str(),
+                )
+                .insert_header((AUTHORIZATION, authorization.as_str()))
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::OK);
+            let events: AuditSearchResult = test::read_body_json(resp).await;
+            assert_eq!(events.total_items, 1);
+            let event = &events.result[0];
+            assert_eq!(event.action, "delete");
+            assert!(event.request_id.is_none());
+            assert!(event.changes["deleted_at"]["before"].is_null());
+            assert!(event.changes["deleted_at"]["after"].is_string());
+        }
+
+        // test 400, a date range needs both ends
+        {
+            let req = test::TestRequest::get()
+                .uri("/api/audit?start_date=2024-01-01T00:00:00Z")
+                .insert_header((AUTHORIZATION, authorization.as_str()))
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+        }
+
+        // test 403, only administrators read the audit log
+        {
+            let user_authorization = format!(
+                "Bearer {}",
+                access_token(&state.settings, &user.id.to_string(), 60)
+            );
+            let req = test::TestRequest::get()
+                .uri("/api/audit")
+                .insert_header((AUTHORIZATION, user_authorization.as_str()))
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+        }
+    }
+}
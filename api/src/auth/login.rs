@@ -0,0 +1,149 @@
+use crate::auth::token::encode_token;
+use crate::auth::{Login, Tenant};
+use crate::error::AsHttpError;
+use crate::{AppState, Authentication, Refresh};
+use actix_web::web::{Data, Json};
+use actix_web::{post, Responder};
+use services_local::credential::CredentialManagement;
+use services_local::refresh_token::RefreshTokenManagement;
+use std::sync::Arc;
+
+#[utoipa::path(
+tag = "Authentication",
+context_path = "/api/auth",
+request_body = Login,
+params(
+("X-Tenant" = Option<String>, Header, description = "Slug of the organization, given by the subdomain or the default organization when missing")
+),
+responses((status=200, description = "Authentication succeed", body = AccessToken),
+(status=400, description = "Data sent not correct"),
+(status=401, description = "Invalid email or password"),
+(status=404, description = "Organization not found")
+)
+)]
+#[post("/login")]
+pub(super) async fn login(
+    login: Json<Login>,
+    tenant: Tenant,
+    app_data: Data<Arc<AppState>>,
+) -> actix_web::Result<impl Responder> {
+    let login = login.into_inner();
+    let authentication: Authentication<_, _, _> =
+        Authentication(Box::new(CredentialManagement::new(tenant.0)));
+    let user = authentication
+        .0
+        .authenticate(login.email, login.password, &app_data.db_connection)
+        .await
+        .map_err(|e| AsHttpError::from(e.get_core_error()))?;
+    let refresh: Refresh<_, _> = Refresh(Box::new(RefreshTokenManagement::new(tenant.0)));
+    let refresh_token = refresh
+        .0
+        .issue(
+            user.get_id(),
+            app_data.settings.authentication.refresh_token_lifetime,
+            &app_data.db_connection,
+        )
+        .await
+        .map_err(|e| AsHttpError::from(e.get_core_error()))?;
+    let mut access_token = encode_token(
+        user.get_id().to_string(),
+        tenant.0,
+        &app_data.settings.authentication,
+    )
+    .map_err(AsHttpError::from)?;
+    access_token.refresh_token = Some(refresh_token);
+    Ok(Json(access_token))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::auth::login::login;
+    use crate::auth::token::decode_token;
+    use crate::auth::{AccessToken, Login};
+    use crate::tests::{app_state, test_settings};
+    use crate::user::User;
+    use actix_web::http::StatusCode;
+    use actix_web::web::Data;
+    use actix_web::{test, web, App};
+    use common::credential::Authenticable;
+    use common::management::Manageable;
+    use services_local::credential::CredentialManagement;
+    use services_local::organization::DEFAULT_ORGANIZATION_ID;
+    use services_local::user::UserManagement;
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn test_login() {
+        let state = app_state(test_settings()).await;
+
+        let user = UserManagement::new(DEFAULT_ORGANIZATION_ID)
+            .create(
+                Box::new(User {
+                    id: Default::default(),
+                    first_name: String::from("Paul"),
+                    last_name: String::from("Martin"),
+                    email: String::from("paul.martin@example.com"),
+                    version: Default::default(),
+                    deleted_at: None,
+                }),
+                &state.db_connection,
+            )
+            .await
+            .unwrap();
+        let user_id = user.get_id().to_string();
+        CredentialManagement::new(DEFAULT_ORGANIZATION_ID)
+            .set_password(
+                user.get_id(),
+                String::from("p4ssw0rd!"),
+                &state.db_connection,
+            )
+            .await
+            .unwrap();
+
+        let app = test::init_service(
+            App::new()
+                .app_data(Data::new(Arc::clone(&state)))
+                .service(web::scope("/api/auth").service(login)),
+        )
+        .await;
+
+        // test 200
+        {
+            let req = test::TestRequest::post()
+                .uri("/api/auth/login")
+                .set_json(Login {
+                    email: String::from("paul.martin@example.com"),
+                    password: String::from("p4ssw0rd!"),
+                })
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::OK);
+            let access_token: AccessToken = test::read_body_json(resp).await;
+            let claims = decode_token(
+                access_token.access_token.as_str(),
+                &state.settings.authentication,
+            )
+            .unwrap();
+            assert_eq!(claims.sub, user_id);
+            assert!(access_token.refresh_token.is_some());
+        }
+
+        // test 401
+        {
+            for (email, password) in [
+                ("paul.martin@example.com", "wrong password"),
+                ("nobody@example.com", "p4ssw0rd!"),
+            ] {
+                let req = test::TestRequest::post()
+                    .uri("/api/auth/login")
+                    .set_json(Login {
+                        email: email.to_string(),
+                        password: password.to_string(),
+                    })
+                    .to_request();
+                let resp = test::call_service(&app, req).await;
+                assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+            }
+        }
+    }
+}
@@ -0,0 +1,108 @@
+use crate::auth::{RefreshToken, Tenant};
+use crate::error::AsHttpError;
+use crate::{AppState, Refresh};
+use actix_web::web::{Data, Json};
+use actix_web::{post, HttpResponse, Responder};
+use services_local::refresh_token::RefreshTokenManagement;
+use std::sync::Arc;
+
+#[utoipa::path(
+tag = "Authentication",
+context_path = "/api/auth",
+request_body = RefreshToken,
+params(
+("X-Tenant" = Option<String>, Header, description = "Slug of the organization, given by the subdomain or the default organization when missing")
+),
+responses((status=204, description = "Logout succeed"),
+(status=400, description = "Data sent not correct"),
+(status=404, description = "Organization not found")
+)
+)]
+#[post("/logout")]
+pub(super) async fn logout(
+    refresh_token: Json<RefreshToken>,
+    tenant: Tenant,
+    app_data: Data<Arc<AppState>>,
+) -> actix_web::Result<impl Responder> {
+    let refresh: Refresh<_, _> = Refresh(Box::new(RefreshTokenManagement::new(tenant.0)));
+    refresh
+        .0
+        .revoke(
+            refresh_token.into_inner().refresh_token,
+            &app_data.db_connection,
+        )
+        .await
+        .map_err(|e| AsHttpError::from(e.get_core_error()))?;
+    Ok(HttpResponse::NoContent().finish())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::auth::logout::logout;
+    use crate::auth::refresh::refresh;
+    use crate::auth::RefreshToken;
+    use crate::tests::{app_state, test_settings};
+    use crate::user::User;
+    use actix_web::http::StatusCode;
+    use actix_web::web::Data;
+    use actix_web::{test, web, App};
+    use common::management::Manageable;
+    use common::token::Refreshable;
+    use services_local::organization::DEFAULT_ORGANIZATION_ID;
+    use services_local::refresh_token::RefreshTokenManagement;
+    use services_local::user::UserManagement;
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn test_logout() {
+        let state = app_state(test_settings()).await;
+
+        let user = UserManagement::new(DEFAULT_ORGANIZATION_ID)
+            .create(
+                Box::new(User {
+                    id: Default::default(),
+                    first_name: String::from("Lea"),
+                    last_name: String::from("Moreau"),
+                    email: String::from("lea.moreau@example.com"),
+                    version: Default::default(),
+                    deleted_at: None,
+                }),
+                &state.db_connection,
+            )
+            .await
+            .unwrap();
+        let refresh_token = RefreshTokenManagement::new(DEFAULT_ORGANIZATION_ID)
+            .issue(user.get_id(), 60, &state.db_connection)
+            .await
+            .unwrap();
+
+        let app = test::init_service(
+            App::new()
+                .app_data(Data::new(Arc::clone(&state)))
+                .service(web::scope("/api/auth").service(refresh).service(logout)),
+        )
+        .await;
+
+        // test 204
+        {
+            let req = test::TestRequest::post()
+                .uri("/api/auth/logout")
+                .set_json(RefreshToken {
+                    refresh_token: refresh_token.to_owned(),
+                })
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::NO_CONTENT);
+        }
+
+        // test 401 once logged out
+        {
+            let req = test::TestRequest::post()
+                .uri("/api/auth/refresh")
+                .set_json(RefreshToken { refresh_token })
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+        }
+    }
+}
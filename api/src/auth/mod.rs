@@ -0,0 +1,179 @@
+use crate::swagger::SecurityAddon;
+use crate::user::User;
+use crate::{AppState, Authentication, Authorization, Management, Tenancy};
+use actix_web::web::{scope, ServiceConfig};
+
+use crate::auth::login::login as login_user;
+use crate::auth::logout::logout as logout_user;
+use crate::auth::password::change_password;
+use crate::auth::refresh::refresh as refresh_token;
+use common::error::CoreError;
+use sea_orm::{DatabaseTransaction, TransactionTrait};
+use serde::{Deserialize, Serialize};
+use service_config::AdministratorSettings;
+use services_local::authorization::RoleManagement;
+use services_local::credential::CredentialManagement;
+use services_local::organization::{OrganizationManagement, DEFAULT_ORGANIZATION_ID};
+use services_local::user::UserManagement;
+use utoipa::{OpenApi, ToSchema};
+use uuid::Uuid;
+
+mod login;
+mod logout;
+mod password;
+mod refresh;
+mod tenant;
+mod token;
+
+pub(crate) use tenant::Tenant;
+pub(crate) use token::Claims;
+
+/// Role granted to every user created through the api
+pub(crate) const DEFAULT_ROLE: &str = "user";
+const ADMINISTRATOR_ROLE: &str = "admin";
+
+pub(crate) fn init(cfg: &mut ServiceConfig) {
+    cfg.service(
+        scope("")
+            .service(login_user)
+            .service(refresh_token)
+            .service(logout_user)
+            .service(change_password),
+    );
+}
+
+#[derive(Default, Serialize, Deserialize, ToSchema)]
+pub(super) struct Login {
+    pub email: String,
+    pub password: String,
+}
+
+#[derive(Default, Serialize, Deserialize, ToSchema)]
+pub(super) struct PasswordChange {
+    #[serde(default)]
+    pub current_password: String,
+    pub new_password: String,
+}
+
+#[derive(Default, Serialize, Deserialize, ToSchema)]
+pub(super) struct RefreshToken {
+    pub refresh_token: String,
+}
+
+#[derive(Default, Serialize, Deserialize, ToSchema)]
+pub(super) struct AccessToken {
+    pub access_token: String,
+    pub token_type: String,
+    /// Lifetime of the access token in seconds
+    pub expires_in: u64,
+    /// Single use token exchanged for a new access token on `/api/auth/refresh`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub refresh_token: Option<String>,
+}
+
+/// Create the account described by `administrator` in the organization `tenant` if no user owns
+/// its email there yet, and make sure it holds the administrator role.
+async fn seed_administrator(
+    tenant: Uuid,
+    administrator: &AdministratorSettings,
+    transaction: &DatabaseTransaction,
+) -> Result<(), CoreError> {
+    let authentication: Authentication<_, _, _> =
+        Authentication(Box::new(CredentialManagement::new(tenant)));
+    let user = match authentication
+        .0
+        .identify(administrator.email.to_owned(), transaction)
+        .await
+        .map_err(|e| e.get_core_error())?
+    {
+        Some(user) => user,
+        None => {
+            let management: Management<_, _, _, _, _, _> =
+                Management(Box::new(UserManagement::new(tenant)));
+            let user = management
+                .0
+                .create(
+                    Box::new(User {
+                        id: Default::default(),
+                        first_name: administrator.first_name.to_owned(),
+                        last_name: administrator.last_name.to_owned(),
+                        email: administrator.email.to_owned(),
+                        version: Default::default(),
+                        deleted_at: None,
+                    }),
+                    transaction,
+                )
+                .await
+                .map_err(|e| e.get_core_error())?;
+            authentication
+                .0
+                .set_password(
+                    user.get_id(),
+                    administrator.password.to_owned(),
+                    transaction,
+                )
+                .await
+                .map_err(|e| e.get_core_error())?;
+            user
+        }
+    };
+    let authorization: Authorization<_, _> = Authorization(Box::new(RoleManagement::new(tenant)));
+    authorization
+        .0
+        .assign(user.get_id(), String::from(ADMINISTRATOR_ROLE), transaction)
+        .await
+        .map_err(|e| e.get_core_error())
+}
+
+/// Seed the account described by the `administrator` settings in the default organization, then
+/// provision the `organizations` settings: each one is created when its slug is still free and
+/// gets its own administrator account.
+pub async fn init_administrator(app_state: &AppState) -> Result<(), CoreError> {
+    let transaction = app_state
+        .db_connection
+        .begin()
+        .await
+        .map_err(|e| CoreError::UnkownError(e.to_string()))?;
+    if let Some(administrator) = &app_state.settings.administrator {
+        seed_administrator(DEFAULT_ORGANIZATION_ID, administrator, &transaction).await?;
+    }
+    {
+        let tenancy: Tenancy<_, _> = Tenancy(Box::new(OrganizationManagement));
+        for organization in &app_state.settings.organizations {
+            let tenant = match tenancy
+                .0
+                .resolve(organization.slug.to_owned(), &transaction)
+                .await
+                .map_err(|e| e.get_core_error())
+            {
+                Ok(tenant) => tenant,
+                Err(CoreError::ResourceNotFound(_)) => tenancy
+                    .0
+                    .create(
+                        organization.name.to_owned(),
+                        organization.slug.to_owned(),
+                        &transaction,
+                    )
+                    .await
+                    .map_err(|e| e.get_core_error())?,
+                Err(e) => return Err(e),
+            };
+            seed_administrator(tenant, &organization.administrator, &transaction).await?;
+        }
+    }
+    transaction
+        .commit()
+        .await
+        .map_err(|e| CoreError::UnkownError(e.to_string()))
+}
+
+pub fn api_docs() -> utoipa::openapi::OpenApi {
+    #[derive(OpenApi)]
+    #[openapi(
+    paths(crate::auth::login::login,crate::auth::refresh::refresh,crate::auth::logout::logout,crate::auth::password::change_password),
+    components(schemas(crate::auth::Login,crate::auth::PasswordChange,crate::auth::RefreshToken,crate::auth::AccessToken)),
+    modifiers(&SecurityAddon)
+    )]
+    struct ApiDocs;
+    ApiDocs::openapi()
+}
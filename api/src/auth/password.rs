@@ -0,0 +1,147 @@
+use crate::auth::{Claims, PasswordChange};
+use crate::error::AsHttpError;
+use crate::{AppState, Authentication};
+use actix_web::web::{Data, Json};
+use actix_web::{put, HttpResponse, Responder};
+use services_local::credential::CredentialManagement;
+use std::sync::Arc;
+
+#[utoipa::path(
+tag = "Authentication",
+context_path = "/api/auth",
+request_body = PasswordChange,
+responses((status=204, description = "Password change succeed"),
+(status=400, description = "Data sent not correct"),
+(status=401, description = "Authentication required or current password incorrect")
+),
+security(("api_jwt_token" = []))
+)]
+#[put("/password")]
+pub(super) async fn change_password(
+    password_change: Json<PasswordChange>,
+    claims: Claims,
+    app_data: Data<Arc<AppState>>,
+) -> actix_web::Result<impl Responder> {
+    let password_change = password_change.into_inner();
+    let authentication: Authentication<_, _, _> =
+        Authentication(Box::new(CredentialManagement::new(claims.tenant)));
+    authentication
+        .0
+        .change_password(
+            Box::new(claims.sub),
+            password_change.current_password,
+            password_change.new_password,
+            &app_data.db_connection,
+        )
+        .await
+        .map_err(|e| AsHttpError::from(e.get_core_error()))?;
+    Ok(HttpResponse::NoContent().finish())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::auth::password::change_password;
+    use crate::auth::PasswordChange;
+    use crate::tests::{access_token, app_state, test_settings};
+    use crate::user::User;
+    use actix_web::http::header::AUTHORIZATION;
+    use actix_web::http::StatusCode;
+    use actix_web::web::Data;
+    use actix_web::{test, web, App};
+    use common::credential::Authenticable;
+    use common::management::Manageable;
+    use services_local::credential::CredentialManagement;
+    use services_local::organization::DEFAULT_ORGANIZATION_ID;
+    use services_local::user::UserManagement;
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn test_change_password() {
+        let state = app_state(test_settings()).await;
+
+        let user = UserManagement::new(DEFAULT_ORGANIZATION_ID)
+            .create(
+                Box::new(User {
+                    id: Default::default(),
+                    first_name: String::from("Anne"),
+                    last_name: String::from("Dupont"),
+                    email: String::from("anne.dupont@example.com"),
+                    version: Default::default(),
+                    deleted_at: None,
+                }),
+                &state.db_connection,
+            )
+            .await
+            .unwrap();
+        CredentialManagement::new(DEFAULT_ORGANIZATION_ID)
+            .set_password(
+                user.get_id(),
+                String::from("first-password"),
+                &state.db_connection,
+            )
+            .await
+            .unwrap();
+
+        let authorization = format!(
+            "Bearer {}",
+            access_token(&state.settings, &user.get_id().to_string(), 60)
+        );
+
+        let app = test::init_service(
+            App::new()
+                .app_data(Data::new(Arc::clone(&state)))
+                .service(web::scope("/api/auth").service(change_password)),
+        )
+        .await;
+
+        // test 401
+        {
+            let req = test::TestRequest::put()
+                .uri("/api/auth/password")
+                .insert_header((AUTHORIZATION, authorization.as_str()))
+                .set_json(PasswordChange {
+                    current_password: String::from("wrong-password"),
+                    new_password: String::from("second-password"),
+                })
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+        }
+
+        // test 400
+        {
+            let req = test::TestRequest::put()
+                .uri("/api/auth/password")
+                .insert_header((AUTHORIZATION, authorization.as_str()))
+                .set_json(PasswordChange {
+                    current_password: String::from("first-password"),
+                    new_password: String::from("short"),
+                })
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+        }
+
+        // test 204
+        {
+            let req = test::TestRequest::put()
+                .uri("/api/auth/password")
+                .insert_header((AUTHORIZATION, authorization.as_str()))
+                .set_json(PasswordChange {
+                    current_password: String::from("first-password"),
+                    new_password: String::from("second-password"),
+                })
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::NO_CONTENT);
+            assert!(CredentialManagement::new(DEFAULT_ORGANIZATION_ID)
+                .authenticate(
+                    String::from("anne.dupont@example.com"),
+                    String::from("second-password"),
+                    &state.db_connection,
+                )
+                .await
+                .is_ok());
+        }
+    }
+}
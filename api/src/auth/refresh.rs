@@ -0,0 +1,141 @@
+use crate::auth::token::encode_token;
+use crate::auth::{RefreshToken, Tenant};
+use crate::error::AsHttpError;
+use crate::{AppState, Refresh};
+use actix_web::web::{Data, Json};
+use actix_web::{post, Responder};
+use services_local::refresh_token::RefreshTokenManagement;
+use std::sync::Arc;
+
+#[utoipa::path(
+tag = "Authentication",
+context_path = "/api/auth",
+request_body = RefreshToken,
+params(
+("X-Tenant" = Option<String>, Header, description = "Slug of the organization, given by the subdomain or the default organization when missing")
+),
+responses((status=200, description = "Token refresh succeed", body = AccessToken),
+(status=400, description = "Data sent not correct"),
+(status=401, description = "Refresh token invalid, expired, revoked, already used or of another organization"),
+(status=404, description = "Organization not found")
+)
+)]
+#[post("/refresh")]
+pub(super) async fn refresh(
+    refresh_token: Json<RefreshToken>,
+    tenant: Tenant,
+    app_data: Data<Arc<AppState>>,
+) -> actix_web::Result<impl Responder> {
+    let refresh: Refresh<_, _> = Refresh(Box::new(RefreshTokenManagement::new(tenant.0)));
+    let (user_id, refresh_token) = refresh
+        .0
+        .rotate(
+            refresh_token.into_inner().refresh_token,
+            app_data.settings.authentication.refresh_token_lifetime,
+            &app_data.db_connection,
+        )
+        .await
+        .map_err(|e| AsHttpError::from(e.get_core_error()))?;
+    let mut access_token = encode_token(
+        user_id.to_string(),
+        tenant.0,
+        &app_data.settings.authentication,
+    )
+    .map_err(AsHttpError::from)?;
+    access_token.refresh_token = Some(refresh_token);
+    Ok(Json(access_token))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::auth::refresh::refresh;
+    use crate::auth::token::decode_token;
+    use crate::auth::{AccessToken, RefreshToken};
+    use crate::tests::{app_state, test_settings};
+    use crate::user::User;
+    use actix_web::http::StatusCode;
+    use actix_web::web::Data;
+    use actix_web::{test, web, App};
+    use common::management::Manageable;
+    use common::token::Refreshable;
+    use services_local::organization::DEFAULT_ORGANIZATION_ID;
+    use services_local::refresh_token::RefreshTokenManagement;
+    use services_local::user::UserManagement;
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn test_refresh() {
+        let state = app_state(test_settings()).await;
+
+        let user = UserManagement::new(DEFAULT_ORGANIZATION_ID)
+            .create(
+                Box::new(User {
+                    id: Default::default(),
+                    first_name: String::from("Marc"),
+                    last_name: String::from("Leroy"),
+                    email: String::from("marc.leroy@example.com"),
+                    version: Default::default(),
+                    deleted_at: None,
+                }),
+                &state.db_connection,
+            )
+            .await
+            .unwrap();
+        let first_token = RefreshTokenManagement::new(DEFAULT_ORGANIZATION_ID)
+            .issue(user.get_id(), 60, &state.db_connection)
+            .await
+            .unwrap();
+
+        let app = test::init_service(
+            App::new()
+                .app_data(Data::new(Arc::clone(&state)))
+                .service(web::scope("/api/auth").service(refresh)),
+        )
+        .await;
+
+        // test 200
+        let second_token = {
+            let req = test::TestRequest::post()
+                .uri("/api/auth/refresh")
+                .set_json(RefreshToken {
+                    refresh_token: first_token.to_owned(),
+                })
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::OK);
+            let access_token: AccessToken = test::read_body_json(resp).await;
+            let claims = decode_token(
+                access_token.access_token.as_str(),
+                &state.settings.authentication,
+            )
+            .unwrap();
+            assert_eq!(claims.sub, user.get_id().to_string());
+            access_token.refresh_token.unwrap()
+        };
+        assert_ne!(first_token, second_token);
+
+        // test 401 on reuse, the whole family is revoked
+        {
+            for refresh_token in [first_token, second_token] {
+                let req = test::TestRequest::post()
+                    .uri("/api/auth/refresh")
+                    .set_json(RefreshToken { refresh_token })
+                    .to_request();
+                let resp = test::call_service(&app, req).await;
+                assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+            }
+        }
+
+        // test 401 on unknown token
+        {
+            let req = test::TestRequest::post()
+                .uri("/api/auth/refresh")
+                .set_json(RefreshToken {
+                    refresh_token: String::from("unknown"),
+                })
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+        }
+    }
+}
@@ -0,0 +1,473 @@
+use crate::error::AsHttpError;
+use crate::{AppState, Tenancy};
+use actix_web::dev::Payload;
+use actix_web::web::Data;
+use actix_web::{FromRequest, HttpRequest};
+use common::error::CoreError;
+use futures::future::LocalBoxFuture;
+use services_local::organization::{OrganizationManagement, DEFAULT_ORGANIZATION_ID};
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// Header naming the organization of a request made without access token
+pub(crate) const TENANT: &str = "X-Tenant";
+
+/// Organization of a request made without access token, e.g. a login.
+/// It is named by the `X-Tenant` header, else by the subdomain of `application.tenant_domain`,
+/// else it is the default organization. Requests with an access token use `Claims::tenant` instead.
+pub(crate) struct Tenant(pub Uuid);
+
+/// Leftmost label of `host` when it is a direct subdomain of `domain`.
+fn subdomain<'h>(host: &'h str, domain: &str) -> Option<&'h str> {
+    let host = host.split(':').next().unwrap_or_default();
+    host.strip_suffix(domain)
+        .and_then(|prefix| prefix.strip_suffix('.'))
+        .filter(|label| !label.is_empty() && !label.contains('.'))
+}
+
+impl Tenant {
+    async fn from_http_request(req: &HttpRequest) -> Result<Self, CoreError> {
+        let app_data = req.app_data::<Data<Arc<AppState>>>().ok_or_else(|| {
+            CoreError::UnkownError(String::from("Application state not configured"))
+        })?;
+        let header = req
+            .headers()
+            .get(TENANT)
+            .and_then(|value| value.to_str().ok())
+            .map(|value| value.trim().to_lowercase())
+            .filter(|value| !value.is_empty());
+        let slug = match (header, &app_data.settings.application.tenant_domain) {
+            (Some(slug), _) => Some(slug),
+            (None, Some(domain)) => subdomain(
+                req.connection_info().host().to_lowercase().as_str(),
+                domain.to_lowercase().as_str(),
+            )
+            .map(String::from),
+            (None, None) => None,
+        };
+        let Some(slug) = slug else {
+            return Ok(Self(DEFAULT_ORGANIZATION_ID));
+        };
+        let tenancy: Tenancy<_, _> = Tenancy(Box::new(OrganizationManagement));
+        tenancy
+            .0
+            .resolve(slug, &app_data.db_connection)
+            .await
+            .map(Self)
+            .map_err(|e| e.get_core_error())
+    }
+}
+
+impl FromRequest for Tenant {
+    type Error = AsHttpError;
+    type Future = LocalBoxFuture<'static, Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, _payload: &mut Payload) -> Self::Future {
+        let req = req.clone();
+        Box::pin(async move {
+            Self::from_http_request(&req)
+                .await
+                .map_err(AsHttpError::from)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::auth::login::login;
+    use crate::auth::tenant::{subdomain, TENANT};
+    use crate::auth::token::decode_token;
+    use crate::auth::{AccessToken, Login};
+    use crate::init_administrator;
+    use crate::tests::{
+        access_token, administrator_id, app_state, tenant_access_token, test_settings,
+    };
+    use crate::user::{User, UserSearchResult};
+    use actix_web::http::header::{AUTHORIZATION, HOST};
+    use actix_web::http::StatusCode;
+    use actix_web::web::Data;
+    use actix_web::{test, web, App};
+    use common::authorization::Authorizable;
+    use common::credential::Authenticable;
+    use common::error::CoreError;
+    use common::management::{Manageable, Precondition};
+    use common::organization::Organizable;
+    use service_config::{AdministratorSettings, OrganizationSettings};
+    use services_local::authorization::RoleManagement;
+    use services_local::credential::CredentialManagement;
+    use services_local::organization::{OrganizationManagement, DEFAULT_ORGANIZATION_ID};
+    use services_local::user::UserManagement;
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn test_subdomain() {
+        // Only the direct subdomains of the tenant domain name an organization.
+        assert_eq!(subdomain("acme.example.com", "example.com"), Some("acme"));
+        assert_eq!(
+            subdomain("acme.example.com:8080", "example.com"),
+            Some("acme")
+        );
+        assert_eq!(subdomain("example.com", "example.com"), None);
+        assert_eq!(subdomain(".example.com", "example.com"), None);
+        assert_eq!(subdomain("a.b.example.com", "example.com"), None);
+        assert_eq!(subdomain("acmeexample.com", "example.com"), None);
+        assert_eq!(subdomain("acme.example.org", "example.com"), None);
+        assert_eq!(subdomain("", "example.com"), None);
+    }
+
+    #[tokio::test]
+    async fn test_tenant_isolation() {
+        let mut settings = test_settings();
+        settings.application.tenant_domain = Some(String::from("example.com"));
+        let state = app_state(settings).await;
+
+        // The email of the administrator of the default organization is free in another one.
+        let email = state
+            .settings
+            .administrator
+            .as_ref()
+            .unwrap()
+            .email
+            .to_owned();
+        let tenant = OrganizationManagement
+            .create(
+                String::from("Acme"),
+                String::from("acme"),
+                &state.db_connection,
+            )
+            .await
+            .unwrap();
+        let user = UserManagement::new(tenant)
+            .create(
+                Box::new(User {
+                    id: Default::default(),
+                    first_name: String::from("Wile"),
+                    last_name: String::from("Coyote"),
+                    email: email.to_owned(),
+                    version: Default::default(),
+                    deleted_at: None,
+                }),
+                &state.db_connection,
+            )
+            .await
+            .unwrap();
+        let user_id = user.get_id().to_string();
+        CredentialManagement::new(tenant)
+            .set_password(
+                user.get_id(),
+                String::from("acme-p4ssw0rd"),
+                &state.db_connection,
+            )
+            .await
+            .unwrap();
+        RoleManagement::new(tenant)
+            .assign(user.get_id(), String::from("admin"), &state.db_connection)
+            .await
+            .unwrap();
+
+        let administrator_id = administrator_id(&state).await;
+        let authorization = format!(
+            "Bearer {}",
+            access_token(&state.settings, &administrator_id, 60)
+        );
+        let tenant_authorization = format!(
+            "Bearer {}",
+            tenant_access_token(&state.settings, &user_id, tenant, 60)
+        );
+
+        let app = test::init_service(
+            App::new()
+                .app_data(Data::new(Arc::clone(&state)))
+                .service(web::scope("/api/auth").service(login))
+                .service(web::scope("/api/user").configure(crate::user::init)),
+        )
+        .await;
+
+        // test 200, the organization is named by the header or by the subdomain
+        for header in [(TENANT, "acme"), (HOST.as_str(), "acme.example.com")] {
+            let req = test::TestRequest::post()
+                .uri("/api/auth/login")
+                .insert_header(header)
+                .set_json(Login {
+                    email: email.to_owned(),
+                    password: String::from("acme-p4ssw0rd"),
+                })
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::OK);
+            let access_token: AccessToken = test::read_body_json(resp).await;
+            let claims = decode_token(
+                access_token.access_token.as_str(),
+                &state.settings.authentication,
+            )
+            .unwrap();
+            assert_eq!(claims.sub, user_id);
+            assert_eq!(claims.tenant, tenant);
+        }
+
+        // test 401, the same email in the default organization has another password
+        {
+            let req = test::TestRequest::post()
+                .uri("/api/auth/login")
+                .set_json(Login {
+                    email: email.to_owned(),
+                    password: String::from("acme-p4ssw0rd"),
+                })
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+        }
+
+        // test 404, unknown organization
+        {
+            let req = test::TestRequest::post()
+                .uri("/api/auth/login")
+                .insert_header((TENANT, "unknown"))
+                .set_json(Login {
+                    email: email.to_owned(),
+                    password: String::from("acme-p4ssw0rd"),
+                })
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+        }
+
+        // test 404, an administrator never reaches the users of another organization
+        for (authorization, other_id) in [
+            (authorization.as_str(), user_id.as_str()),
+            (tenant_authorization.as_str(), administrator_id.as_str()),
+        ] {
+            let uri = format!("/api/user/{}", other_id);
+            for req in [
+                test::TestRequest::get().uri(uri.as_str()),
+                test::TestRequest::put().uri(uri.as_str()).set_json(User {
+                    id: Default::default(),
+                    first_name: String::from("Road"),
+                    last_name: String::from("Runner"),
+                    email: String::from("road.runner@example.com"),
+                    version: Default::default(),
+                    deleted_at: None,
+                }),
+                test::TestRequest::delete().uri(uri.as_str()),
+                test::TestRequest::get().uri(format!("{}/role", uri).as_str()),
+            ] {
+                let req = req
+                    .insert_header((AUTHORIZATION, authorization))
+                    .to_request();
+                let resp = test::call_service(&app, req).await;
+                assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+            }
+        }
+
+        // test 200, each organization only lists its own users
+        for (authorization, expected_id) in [
+            (authorization.as_str(), administrator_id.as_str()),
+            (tenant_authorization.as_str(), user_id.as_str()),
+        ] {
+            let req = test::TestRequest::get()
+                .uri(format!("/api/user?pattern={}&match_mode=exact", email).as_str())
+                .insert_header((AUTHORIZATION, authorization))
+                .to_request();
+            let search_result: UserSearchResult = test::call_and_read_body_json(&app, req).await;
+            assert_eq!(search_result.total_items, Some(1));
+            assert_eq!(search_result.result[0].id.to_string(), expected_id);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_role_isolation() {
+        let state = app_state(test_settings()).await;
+        let tenant = OrganizationManagement
+            .create(
+                String::from("Globex"),
+                String::from("globex"),
+                &state.db_connection,
+            )
+            .await
+            .unwrap();
+        let user = UserManagement::new(tenant)
+            .create(
+                Box::new(User {
+                    id: Default::default(),
+                    first_name: String::from("Hank"),
+                    last_name: String::from("Scorpio"),
+                    email: String::from("hank.scorpio@example.com"),
+                    version: Default::default(),
+                    deleted_at: None,
+                }),
+                &state.db_connection,
+            )
+            .await
+            .unwrap();
+        let user_id = user.get_id().to_string();
+        RoleManagement::new(tenant)
+            .assign(user.get_id(), String::from("admin"), &state.db_connection)
+            .await
+            .unwrap();
+        let administrator_id = administrator_id(&state).await;
+
+        // test not found, the roles of the users of another organization are out of reach
+        for (tenant, other_id) in [
+            (DEFAULT_ORGANIZATION_ID, user_id.as_str()),
+            (tenant, administrator_id.as_str()),
+        ] {
+            let management = RoleManagement::new(tenant);
+            assert!(matches!(
+                management
+                    .get_roles(Box::new(other_id.to_owned()), &state.db_connection)
+                    .await
+                    .map_err(|e| e.get_core_error()),
+                Err(CoreError::ResourceNotFound(_))
+            ));
+            assert!(matches!(
+                management
+                    .assign(
+                        Box::new(other_id.to_owned()),
+                        String::from("admin"),
+                        &state.db_connection
+                    )
+                    .await
+                    .map_err(|e| e.get_core_error()),
+                Err(CoreError::ResourceNotFound(_))
+            ));
+            assert!(matches!(
+                management
+                    .unassign(
+                        Box::new(other_id.to_owned()),
+                        String::from("admin"),
+                        &state.db_connection
+                    )
+                    .await
+                    .map_err(|e| e.get_core_error()),
+                Err(CoreError::ResourceNotFound(_))
+            ));
+        }
+
+        // test the roles are left untouched in their own organization
+        let roles = RoleManagement::new(tenant)
+            .get_roles(Box::new(user_id.to_owned()), &state.db_connection)
+            .await
+            .unwrap();
+        assert!(roles.contains(&String::from("admin")));
+        let roles = RoleManagement::new(DEFAULT_ORGANIZATION_ID)
+            .get_roles(Box::new(administrator_id), &state.db_connection)
+            .await
+            .unwrap();
+        assert!(roles.contains(&String::from("admin")));
+
+        // test not found, a deleted user has no roles to read or change
+        UserManagement::new(tenant)
+            .delete(user.get_id(), Precondition::Any, &state.db_connection)
+            .await
+            .unwrap();
+        let management = RoleManagement::new(tenant);
+        assert!(matches!(
+            management
+                .get_roles(Box::new(user_id.to_owned()), &state.db_connection)
+                .await
+                .map_err(|e| e.get_core_error()),
+            Err(CoreError::ResourceNotFound(_))
+        ));
+        assert!(matches!(
+            management
+                .assign(
+                    Box::new(user_id),
+                    String::from("admin"),
+                    &state.db_connection
+                )
+                .await
+                .map_err(|e| e.get_core_error()),
+            Err(CoreError::ResourceNotFound(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_organization_provisioning() {
+        let mut settings = test_settings();
+        settings.organizations.push(OrganizationSettings {
+            name: String::from("Initech"),
+            slug: String::from("initech"),
+            administrator: AdministratorSettings {
+                first_name: String::from("Bill"),
+                last_name: String::from("Lumbergh"),
+                email: String::from("bill.lumbergh@example.com"),
+                password: String::from("initech-p4ssw0rd"),
+            },
+        });
+        let state = app_state(settings).await;
+        // Provisioning again at the next startup changes nothing.
+        init_administrator(&state).await.unwrap();
+        init_administrator(&state).await.unwrap();
+        let tenant = OrganizationManagement
+            .resolve(String::from("initech"), &state.db_connection)
+            .await
+            .unwrap();
+
+        let app = test::init_service(
+            App::new()
+                .app_data(Data::new(Arc::clone(&state)))
+                .service(web::scope("/api/auth").service(login)),
+        )
+        .await;
+
+        // test 200, the administrator of the new organization logs in and holds the admin role
+        {
+            let req = test::TestRequest::post()
+                .uri("/api/auth/login")
+                .insert_header((TENANT, "initech"))
+                .set_json(Login {
+                    email: String::from("bill.lumbergh@example.com"),
+                    password: String::from("initech-p4ssw0rd"),
+                })
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::OK);
+            let access_token: AccessToken = test::read_body_json(resp).await;
+            let claims = decode_token(
+                access_token.access_token.as_str(),
+                &state.settings.authentication,
+            )
+            .unwrap();
+            assert_eq!(claims.tenant, tenant);
+            let roles = RoleManagement::new(tenant)
+                .get_roles(Box::new(claims.sub), &state.db_connection)
+                .await
+                .unwrap();
+            assert_eq!(roles, vec![String::from("admin")]);
+        }
+
+        // test 401, the administrator only belongs to the new organization
+        {
+            let req = test::TestRequest::post()
+                .uri("/api/auth/login")
+                .set_json(Login {
+                    email: String::from("bill.lumbergh@example.com"),
+                    password: String::from("initech-p4ssw0rd"),
+                })
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+        }
+
+        // test data error, an organization is only provisioned with a valid slug
+        {
+            let mut settings = test_settings();
+            settings.administrator = None;
+            settings.organizations.push(OrganizationSettings {
+                name: String::from("Bad slug"),
+                slug: String::from("Bad Slug"),
+                administrator: AdministratorSettings {
+                    first_name: String::from("Bad"),
+                    last_name: String::from("Slug"),
+                    email: String::from("bad.slug@example.com"),
+                    password: String::from("bad-p4ssw0rd"),
+                },
+            });
+            let state = app_state(settings).await;
+            assert!(matches!(
+                init_administrator(&state).await,
+                Err(CoreError::DataError(_))
+            ));
+        }
+    }
+}
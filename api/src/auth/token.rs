@@ -0,0 +1,189 @@
+use crate::auth::AccessToken;
+use crate::error::AsHttpError;
+use crate::AppState;
+use actix_web::dev::Payload;
+use actix_web::http::header::AUTHORIZATION;
+use actix_web::web::Data;
+use actix_web::{FromRequest, HttpRequest};
+use common::error::CoreError;
+use jsonwebtoken::{decode, encode, Algorithm, DecodingKey, EncodingKey, Header, Validation};
+use serde::{Deserialize, Serialize};
+use service_config::AuthenticationSettings;
+use services_local::organization::DEFAULT_ORGANIZATION_ID;
+use std::future::{ready, Ready};
+use std::str::FromStr;
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// Claims carried by a validated JWT access token.
+/// Add it as a handler argument to require authentication on a route.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Claims {
+    pub sub: String,
+    /// Organization of the user, every request of the token is confined to it
+    #[serde(default = "default_tenant")]
+    pub tenant: Uuid,
+    pub iat: usize,
+    pub exp: usize,
+}
+
+/// Tokens issued before the organizations existed belong to the default one.
+fn default_tenant() -> Uuid {
+    DEFAULT_ORGANIZATION_ID
+}
+
+impl Claims {
+    fn from_http_request(req: &HttpRequest) -> Result<Self, CoreError> {
+        let app_data = req.app_data::<Data<Arc<AppState>>>().ok_or_else(|| {
+            CoreError::UnkownError(String::from("Application state not configured"))
+        })?;
+        let token = req
+            .headers()
+            .get(AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .ok_or_else(|| {
+                CoreError::OperationNotAuthorized(String::from("Bearer token required"))
+            })?;
+        decode_token(token, &app_data.settings.authentication)
+    }
+}
+
+impl FromRequest for Claims {
+    type Error = AsHttpError;
+    type Future = Ready<Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, _payload: &mut Payload) -> Self::Future {
+        ready(Self::from_http_request(req).map_err(AsHttpError::from))
+    }
+}
+
+fn algorithm(settings: &AuthenticationSettings) -> Result<Algorithm, CoreError> {
+    Algorithm::from_str(settings.algorithm.as_str())
+        .map_err(|e| CoreError::UnkownError(e.to_string()))
+}
+
+fn decoding_key(settings: &AuthenticationSettings) -> Result<DecodingKey, CoreError> {
+    match algorithm(settings)? {
+        Algorithm::HS256 => Ok(DecodingKey::from_secret(
+            settings.secret.clone().unwrap_or_default().as_bytes(),
+        )),
+        Algorithm::RS256 => {
+            DecodingKey::from_rsa_pem(settings.public_key.clone().unwrap_or_default().as_bytes())
+                .map_err(|e| CoreError::UnkownError(e.to_string()))
+        }
+        other => Err(CoreError::UnkownError(format!(
+            "{:?} is not a supported algorithm",
+            other
+        ))),
+    }
+}
+
+fn encoding_key(settings: &AuthenticationSettings) -> Result<EncodingKey, CoreError> {
+    match algorithm(settings)? {
+        Algorithm::HS256 => Ok(EncodingKey::from_secret(
+            settings.secret.clone().unwrap_or_default().as_bytes(),
+        )),
+        Algorithm::RS256 => {
+            EncodingKey::from_rsa_pem(settings.private_key.clone().unwrap_or_default().as_bytes())
+                .map_err(|e| CoreError::UnkownError(e.to_string()))
+        }
+        other => Err(CoreError::UnkownError(format!(
+            "{:?} is not a supported algorithm",
+            other
+        ))),
+    }
+}
+
+/// Sign an access token for `subject` of the organization `tenant` valid for `access_token_lifetime` seconds.
+pub(crate) fn encode_token(
+    subject: String,
+    tenant: Uuid,
+    settings: &AuthenticationSettings,
+) -> Result<AccessToken, CoreError> {
+    let now = chrono::Utc::now().timestamp() as usize;
+    let claims = Claims {
+        sub: subject,
+        tenant,
+        iat: now,
+        exp: now + settings.access_token_lifetime as usize,
+    };
+    encode(
+        &Header::new(algorithm(settings)?),
+        &claims,
+        &encoding_key(settings)?,
+    )
+    .map(|access_token| AccessToken {
+        access_token,
+        token_type: String::from("Bearer"),
+        expires_in: settings.access_token_lifetime,
+        refresh_token: None,
+    })
+    .map_err(|e| CoreError::UnkownError(e.to_string()))
+}
+
+/// Verify the signature and the expiration of `token`.
+pub(crate) fn decode_token(
+    token: &str,
+    settings: &AuthenticationSettings,
+) -> Result<Claims, CoreError> {
+    let validation = Validation::new(algorithm(settings)?);
+    decode::<Claims>(token, &decoding_key(settings)?, &validation)
+        .map(|data| data.claims)
+        .map_err(|e| CoreError::OperationNotAuthorized(e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::auth::token::{decode_token, encode_token};
+    use crate::tests::{access_token, test_settings};
+    use common::error::CoreError;
+    use uuid::Uuid;
+
+    #[test]
+    fn test_decode_token() {
+        let mut settings = test_settings();
+
+        // valid token
+        {
+            let token = access_token(&settings, "f3b1c0de-0000-4000-8000-000000000000", 60);
+            let claims = decode_token(token.as_str(), &settings.authentication).unwrap();
+            assert_eq!(claims.sub, "f3b1c0de-0000-4000-8000-000000000000");
+        }
+
+        // issued token
+        {
+            let tenant = Uuid::new_v4();
+            let token = encode_token(
+                String::from("f3b1c0de-0000-4000-8000-000000000000"),
+                tenant,
+                &settings.authentication,
+            )
+            .unwrap();
+            assert_eq!(token.token_type, "Bearer");
+            let claims =
+                decode_token(token.access_token.as_str(), &settings.authentication).unwrap();
+            assert_eq!(claims.sub, "f3b1c0de-0000-4000-8000-000000000000");
+            assert_eq!(claims.tenant, tenant);
+        }
+
+        // expired token
+        {
+            let token = access_token(&settings, "f3b1c0de-0000-4000-8000-000000000000", -3600);
+            assert!(matches!(
+                decode_token(token.as_str(), &settings.authentication),
+                Err(CoreError::OperationNotAuthorized(_))
+            ));
+        }
+
+        // token signed with another secret
+        {
+            let token = access_token(&settings, "f3b1c0de-0000-4000-8000-000000000000", 60);
+            settings.authentication.secret = Some(String::from("another-secret"));
+            assert!(matches!(
+                decode_token(token.as_str(), &settings.authentication),
+                Err(CoreError::OperationNotAuthorized(_))
+            ));
+        }
+    }
+}
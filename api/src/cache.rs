@@ -0,0 +1,101 @@
+use crate::error::AsHttpError;
+use actix_web::http::header::{
+    CacheControl, CacheDirective, ETag, EntityTag, Header, HttpDate, IfModifiedSince, IfNoneMatch,
+    LastModified, IF_MODIFIED_SINCE, IF_NONE_MATCH, VARY,
+};
+use actix_web::http::StatusCode;
+use actix_web::{HttpRequest, HttpResponse, HttpResponseBuilder};
+use chrono::{DateTime, Utc};
+use common::error::CoreError;
+use serde::Serialize;
+use service_config::ApplicationSettings;
+use sha2::{Digest, Sha256};
+use std::time::SystemTime;
+
+/// JSON body of a read response with the validators a client revalidates it with.
+pub(crate) struct Representation {
+    body: Vec<u8>,
+    etag: EntityTag,
+    last_modified: Option<DateTime<Utc>>,
+}
+
+impl Representation {
+    /// The entity tag is a digest of the body, so that it changes with any item of a list.
+    pub(crate) fn new<T: Serialize>(
+        value: &T,
+        last_modified: Option<DateTime<Utc>>,
+    ) -> Result<Self, AsHttpError> {
+        let body = serde_json::to_vec(value)
+            .map_err(|e| AsHttpError::from(CoreError::UnkownError(e.to_string())))?;
+        let etag = EntityTag::new_strong(format!("{:x}", Sha256::digest(&body)));
+        Ok(Self {
+            body,
+            etag,
+            last_modified,
+        })
+    }
+
+    /// Replace the digest by another entity tag, e.g. the version of a single record.
+    pub(crate) fn with_etag(self, etag: EntityTag) -> Self {
+        Self { etag, ..self }
+    }
+
+    /// `If-None-Match`, using the weak comparison, takes precedence over `If-Modified-Since`
+    /// (RFC 9110 section 13.2.2).
+    fn is_fresh(&self, request: &HttpRequest) -> bool {
+        if request.headers().contains_key(IF_NONE_MATCH) {
+            return match IfNoneMatch::parse(request) {
+                Ok(IfNoneMatch::Any) => true,
+                Ok(IfNoneMatch::Items(tags)) => tags.iter().any(|tag| tag.weak_eq(&self.etag)),
+                Err(_) => false,
+            };
+        }
+        match (
+            self.last_modified,
+            request
+                .headers()
+                .contains_key(IF_MODIFIED_SINCE)
+                .then(|| IfModifiedSince::parse(request).ok())
+                .flatten(),
+        ) {
+            // HTTP dates have no fraction of second
+            (Some(last_modified), Some(IfModifiedSince(since))) => {
+                last_modified.timestamp()
+                    <= DateTime::<Utc>::from(SystemTime::from(since)).timestamp()
+            }
+            _ => false,
+        }
+    }
+
+    /// Send the body with `response`, or an empty `304 Not Modified` when the client copy is fresh.
+    pub(crate) fn respond(
+        self,
+        request: &HttpRequest,
+        settings: &ApplicationSettings,
+        mut response: HttpResponseBuilder,
+    ) -> HttpResponse {
+        let cache_control = match settings.cache_max_age {
+            0 => vec![CacheDirective::Private, CacheDirective::NoCache],
+            max_age => vec![
+                CacheDirective::Private,
+                CacheDirective::MaxAge(max_age),
+                CacheDirective::MustRevalidate,
+            ],
+        };
+        response
+            .insert_header(ETag(self.etag.to_owned()))
+            .insert_header(CacheControl(cache_control))
+            .insert_header((VARY, "Authorization"));
+        if let Some(last_modified) = self.last_modified {
+            response.insert_header(LastModified(HttpDate::from(SystemTime::from(
+                last_modified,
+            ))));
+        }
+        if self.is_fresh(request) {
+            return response.status(StatusCode::NOT_MODIFIED).finish();
+        }
+        response
+            .content_type(mime::APPLICATION_JSON)
+            .body(self.body)
+    }
+}
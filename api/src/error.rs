@@ -1,14 +1,79 @@
 use actix_web::body::BoxBody;
 use actix_web::http::StatusCode;
+use actix_web::web::{JsonConfig, PathConfig, QueryConfig, ServiceConfig};
 use actix_web::{HttpResponse, ResponseError};
 
-use common::error::CoreError;
-use serde_json::json;
+use common::error::{CoreError, FieldError};
+use log::error;
+use serde_json::{json, Value};
 use thiserror::Error;
+use uuid::Uuid;
+use validator::ValidationErrors;
 #[derive(Error, Debug)]
 #[error(transparent)]
 pub struct AsHttpError(#[from] CoreError);
 
+/// Media type of the RFC 7807 error responses
+pub(crate) const PROBLEM_JSON: &str = "application/problem+json";
+
+impl AsHttpError {
+    /// Stable identifier of the kind of error, meant for machines
+    pub(crate) fn code(&self) -> &'static str {
+        match self.0 {
+            CoreError::DataError(_) => "invalid_data",
+            CoreError::ResourceNotFound(_) => "not_found",
+            CoreError::OperationNotAuthorized(_) => "unauthorized",
+            CoreError::OperationForbiden(_) => "forbidden",
+            CoreError::UnkownError(_) => "internal_error",
+            CoreError::ValidationError(_) => "validation_failed",
+            CoreError::Conflict(_) => "conflict",
+            CoreError::PreconditionFailed(_) => "precondition_failed",
+            CoreError::NotAcceptable(_) => "not_acceptable",
+            CoreError::UnsupportedMediaType(_) => "unsupported_media_type",
+        }
+    }
+
+    /// RFC 7807 problem describing the error, `instance` identifies this occurrence in the logs.
+    pub(crate) fn problem(&self) -> Value {
+        let status = self.status_code();
+        let instance = format!("urn:uuid:{}", Uuid::new_v4());
+        let detail = match &self.0 {
+            CoreError::UnkownError(message) => {
+                // The cause may hold database internals, it is only logged.
+                error!("{}: {}", instance, message);
+                String::from("An unexpected error occurred")
+            }
+            core_error => core_error.to_string(),
+        };
+        let mut problem = json!({
+            "type": "about:blank",
+            "title": status.canonical_reason().unwrap_or_default(),
+            "status": status.as_u16(),
+            "detail": detail,
+            "instance": instance,
+            "code": self.code()
+        });
+        let errors = match &self.0 {
+            CoreError::ValidationError(errors) => Some(errors.to_owned()),
+            CoreError::Conflict(error) => Some(vec![error.to_owned()]),
+            _ => None,
+        };
+        if let Some(errors) = errors {
+            problem["errors"] = errors
+                .iter()
+                .map(|error| {
+                    json!({
+                        "field": error.get_field(),
+                        "code": error.get_code(),
+                        "message": error.get_message()
+                    })
+                })
+                .collect();
+        }
+        problem
+    }
+}
+
 impl ResponseError for AsHttpError {
     fn status_code(&self) -> StatusCode {
         match self.0.clone() {
@@ -17,26 +82,51 @@ impl ResponseError for AsHttpError {
             CoreError::OperationNotAuthorized(_) => StatusCode::UNAUTHORIZED,
             CoreError::OperationForbiden(_) => StatusCode::FORBIDDEN,
             CoreError::UnkownError(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            CoreError::ValidationError(_) => StatusCode::UNPROCESSABLE_ENTITY,
+            CoreError::Conflict(_) => StatusCode::CONFLICT,
+            CoreError::PreconditionFailed(_) => StatusCode::PRECONDITION_FAILED,
+            CoreError::NotAcceptable(_) => StatusCode::NOT_ACCEPTABLE,
+            CoreError::UnsupportedMediaType(_) => StatusCode::UNSUPPORTED_MEDIA_TYPE,
         }
     }
 
+    /// Render the error as a RFC 7807 problem.
     fn error_response(&self) -> HttpResponse<BoxBody> {
-        match self.0.clone() {
-            CoreError::DataError(s) => HttpResponse::BadRequest().json(json!({
-                "description": s.to_owned()
-            })),
-            CoreError::ResourceNotFound(s) => HttpResponse::NotFound().json(json!({
-                "description": s.to_owned()
-            })),
-            CoreError::OperationNotAuthorized(s) => HttpResponse::Unauthorized().json(json!({
-                "description": s.to_owned()
-            })),
-            CoreError::OperationForbiden(s) => HttpResponse::Forbidden().json(json!({
-                "description": s.to_owned()
-            })),
-            CoreError::UnkownError(s) => HttpResponse::InternalServerError().json(json!({
-                "description": s.to_owned()
-            })),
-        }
+        HttpResponse::build(self.status_code())
+            .content_type(PROBLEM_JSON)
+            .json(self.problem())
+    }
+}
+
+/// Render the errors of the JSON, path and query extractors as problems.
+pub(crate) fn extractor_errors(cfg: &mut ServiceConfig) {
+    cfg.app_data(JsonConfig::default().error_handler(|error, _| {
+        AsHttpError::from(CoreError::DataError(error.to_string())).into()
+    }))
+    .app_data(PathConfig::default().error_handler(|error, _| {
+        AsHttpError::from(CoreError::DataError(error.to_string())).into()
+    }))
+    .app_data(QueryConfig::default().error_handler(|error, _| {
+        AsHttpError::from(CoreError::DataError(error.to_string())).into()
+    }));
+}
+
+impl From<ValidationErrors> for AsHttpError {
+    fn from(value: ValidationErrors) -> Self {
+        let mut errors: Vec<FieldError> = value
+            .field_errors()
+            .into_iter()
+            .flat_map(|(field, errors)| {
+                errors.iter().map(move |error| {
+                    FieldError::new(
+                        field,
+                        error.code.as_ref(),
+                        error.message.as_deref().unwrap_or(error.code.as_ref()),
+                    )
+                })
+            })
+            .collect();
+        errors.sort_by(|a, b| a.get_field().cmp(b.get_field()));
+        AsHttpError(CoreError::ValidationError(errors))
     }
 }
@@ -0,0 +1,164 @@
+use crate::auth::Claims;
+use crate::error::AsHttpError;
+use crate::{AppState, Authorization, Membership};
+use actix_web::web::{Data, Path};
+use actix_web::{put, HttpResponse, Responder};
+use services_local::authorization::RoleManagement;
+use services_local::group::GroupManagement;
+use std::sync::Arc;
+
+#[utoipa::path(
+tag = "Group members",
+context_path = "/api/group",
+params(
+("group_id" = Uuid, Path, description = "Group identifier"),
+("user_id" = Uuid, Path, description = "User identifier")
+),
+responses((status=204, description = "Member addition succeed"),
+(status=401, description = "Authentication required"),
+(status=403, description = "Permission group:update required"),
+(status=404, description = "Group or user not found")
+),
+security(("api_jwt_token" = []))
+)]
+#[put("/{group_id}/member/{user_id}")]
+pub(super) async fn add_member(
+    path: Path<(String, String)>,
+    claims: Claims,
+    app_data: Data<Arc<AppState>>,
+) -> actix_web::Result<impl Responder> {
+    let (group_id, user_id) = path.into_inner();
+    let authorization: Authorization<_, _> =
+        Authorization(Box::new(RoleManagement::new(claims.tenant)));
+    authorization
+        .0
+        .authorize(
+            Box::new(claims.sub.to_owned()),
+            String::from("group:update"),
+            None,
+            &app_data.db_connection,
+        )
+        .await
+        .map_err(|e| AsHttpError::from(e.get_core_error()))?;
+    let membership: Membership<_, _> = Membership(Box::new(GroupManagement::new(claims.tenant)));
+    membership
+        .0
+        .add_member(
+            Box::new(group_id),
+            Box::new(user_id),
+            &app_data.db_connection,
+        )
+        .await
+        .map_err(|e| AsHttpError::from(e.get_core_error()))?;
+    Ok(HttpResponse::NoContent().finish())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::group::Group;
+    use crate::tests::{access_token, administrator_id, app_state, test_settings};
+    use crate::user::{NewUser, User, UserSearchResult};
+    use actix_web::http::header::AUTHORIZATION;
+    use actix_web::http::StatusCode;
+    use actix_web::web::Data;
+    use actix_web::{test, web, App};
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn test_group_add_member() {
+        let state = app_state(test_settings()).await;
+        let authorization = format!(
+            "Bearer {}",
+            access_token(&state.settings, &administrator_id(&state).await, 60)
+        );
+
+        let app = test::init_service(
+            App::new()
+                .app_data(Data::new(Arc::clone(&state)))
+                .service(web::scope("/api/user").configure(crate::user::init))
+                .service(web::scope("/api/group").configure(crate::group::init)),
+        )
+        .await;
+
+        let req = test::TestRequest::post()
+            .uri("/api/group")
+            .insert_header((AUTHORIZATION, authorization.as_str()))
+            .set_json(&Group {
+                name: "Engineering".to_string(),
+                ..Default::default()
+            })
+            .to_request();
+        let group: Group = test::call_and_read_body_json(&app, req).await;
+        let mut users = Vec::new();
+        for (first_name, email) in [
+            ("Ada", "ada.member@example.com"),
+            ("Alan", "alan.member@example.com"),
+        ] {
+            let req = test::TestRequest::post()
+                .uri("/api/user")
+                .insert_header((AUTHORIZATION, authorization.as_str()))
+                .set_json(&NewUser {
+                    first_name: first_name.to_string(),
+                    last_name: "Member".to_string(),
+                    email: email.to_string(),
+                    password: None,
+                })
+                .to_request();
+            let user: User = test::call_and_read_body_json(&app, req).await;
+            users.push(user);
+        }
+        let path = format!("/api/group/{}/member/{}", group.id, users[0].id);
+
+        // test 204, adding a member twice does nothing
+        for _ in 0..2 {
+            let req = test::TestRequest::put()
+                .uri(path.as_str())
+                .insert_header((AUTHORIZATION, authorization.as_str()))
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::NO_CONTENT);
+        }
+
+        // test 200, only the members are listed with the group filter
+        {
+            let req = test::TestRequest::get()
+                .uri(format!("/api/user?group={}", group.id).as_str())
+                .insert_header((AUTHORIZATION, authorization.as_str()))
+                .to_request();
+            let search_result: UserSearchResult = test::call_and_read_body_json(&app, req).await;
+            assert_eq!(search_result.total_items, Some(1));
+            assert_eq!(search_result.result[0].id, users[0].id);
+        }
+
+        // test 404, the group or the user does not exist
+        {
+            for uri in [
+                format!("/api/group/{}/member/{}", uuid::Uuid::new_v4(), users[0].id),
+                format!("/api/group/{}/member/{}", group.id, uuid::Uuid::new_v4()),
+            ] {
+                let req = test::TestRequest::put()
+                    .uri(uri.as_str())
+                    .insert_header((AUTHORIZATION, authorization.as_str()))
+                    .to_request();
+                let resp = test::call_service(&app, req).await;
+                assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+            }
+        }
+
+        // test 403, a user can not add himself to a group
+        {
+            let req = test::TestRequest::put()
+                .uri(format!("/api/group/{}/member/{}", group.id, users[1].id).as_str())
+                .insert_header((
+                    AUTHORIZATION,
+                    format!(
+                        "Bearer {}",
+                        access_token(&state.settings, &users[1].id.to_string(), 60)
+                    ),
+                ))
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+        }
+    }
+}
@@ -0,0 +1,178 @@
+use crate::audit::request_id;
+use crate::auth::Claims;
+use crate::error::AsHttpError;
+use crate::group::{audited, Group};
+use crate::user::entity_tag;
+use crate::{AppState, Management};
+use actix_web::web::{Data, Json};
+use actix_web::{post, HttpRequest, HttpResponse, Responder};
+use common::error::CoreError;
+use sea_orm::TransactionTrait;
+use std::sync::Arc;
+use validator::Validate;
+
+#[utoipa::path(
+tag = "Groups",
+context_path = "/api/group",
+request_body = Group,
+responses((status=201, description = "Group creation succeed", body = Group, headers(("ETag" = String, description = "Version of the group"))),
+(status=400, description = "Data sent not correct"),
+(status=401, description = "Authentication required"),
+(status=403, description = "Permission group:create required"),
+(status=409, description = "Name already used"),
+(status=422, description = "Fields not valid")
+),
+security(("api_jwt_token" = []))
+)]
+#[post("")]
+pub(super) async fn create(
+    request: HttpRequest,
+    group: Json<Group>,
+    claims: Claims,
+    app_data: Data<Arc<AppState>>,
+) -> actix_web::Result<impl Responder> {
+    let group = group.into_inner();
+    group.validate().map_err(AsHttpError::from)?;
+    let transaction = app_data
+        .db_connection
+        .begin()
+        .await
+        .map_err(|e| AsHttpError::from(CoreError::UnkownError(e.to_string())))?;
+    let response = {
+        let management: Management<_, _, _, _, _, _> = Management(Box::new(audited(
+            claims.sub,
+            claims.tenant,
+            request_id(&request),
+        )));
+        management
+            .0
+            .create(Box::new(group), &transaction)
+            .await
+            .map(Group::from)
+            .map_err(|e| AsHttpError::from(e.get_core_error()))?
+    };
+    transaction
+        .commit()
+        .await
+        .map_err(|e| AsHttpError::from(CoreError::UnkownError(e.to_string())))?;
+    Ok(HttpResponse::Created()
+        .insert_header(entity_tag(response.version))
+        .json(response))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::group::create::create;
+    use crate::group::Group;
+    use crate::tests::{access_token, administrator_id, app_state, test_settings};
+    use crate::user::{NewUser, User};
+    use actix_web::http::header::{AUTHORIZATION, ETAG};
+    use actix_web::http::StatusCode;
+    use actix_web::web::Data;
+    use actix_web::{test, web, App};
+    use serde_json::json;
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn test_group_create() {
+        let state = app_state(test_settings()).await;
+        let authorization = format!(
+            "Bearer {}",
+            access_token(&state.settings, &administrator_id(&state).await, 60)
+        );
+
+        let app = test::init_service(
+            App::new()
+                .app_data(Data::new(Arc::clone(&state)))
+                .service(web::scope("/api/user").configure(crate::user::init))
+                .service(web::scope("/api/group").service(create)),
+        )
+        .await;
+
+        // test 201
+        let new_group = Group {
+            name: "Accounting".to_string(),
+            description: Some("People paying the bills".to_string()),
+            ..Default::default()
+        };
+        {
+            let req = test::TestRequest::post()
+                .uri("/api/group")
+                .insert_header((AUTHORIZATION, authorization.as_str()))
+                .set_json(&new_group)
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::CREATED);
+            assert_eq!(resp.headers().get(ETAG).unwrap(), "\"1\"");
+            let group: Group = test::read_body_json(resp).await;
+            assert_eq!(group.version, 1);
+            assert_eq!(group.name, new_group.name);
+            assert_eq!(group.description, new_group.description);
+        }
+
+        // test 409, the name is already used
+        {
+            let req = test::TestRequest::post()
+                .uri("/api/group")
+                .insert_header((AUTHORIZATION, authorization.as_str()))
+                .set_json(&new_group)
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::CONFLICT);
+            let response: serde_json::Value = test::read_body_json(resp).await;
+            assert_eq!(response["errors"][0]["field"], "name");
+        }
+
+        // test 422
+        {
+            let req = test::TestRequest::post()
+                .uri("/api/group")
+                .insert_header((AUTHORIZATION, authorization.as_str()))
+                .set_json(json!({"name": ""}))
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::UNPROCESSABLE_ENTITY);
+            let response: serde_json::Value = test::read_body_json(resp).await;
+            assert_eq!(response["errors"][0]["field"], "name");
+            assert_eq!(response["errors"][0]["code"], "required");
+        }
+
+        // test 403, only administrators create groups
+        {
+            let req = test::TestRequest::post()
+                .uri("/api/user")
+                .insert_header((AUTHORIZATION, authorization.as_str()))
+                .set_json(&NewUser {
+                    first_name: "Group".to_string(),
+                    last_name: "Creator".to_string(),
+                    email: "group.creator@example.com".to_string(),
+                    password: None,
+                })
+                .to_request();
+            let user: User = test::call_and_read_body_json(&app, req).await;
+            let req = test::TestRequest::post()
+                .uri("/api/group")
+                .insert_header((
+                    AUTHORIZATION,
+                    format!(
+                        "Bearer {}",
+                        access_token(&state.settings, &user.id.to_string(), 60)
+                    ),
+                ))
+                .set_json(json!({"name": "Forbidden"}))
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+        }
+
+        // test 401
+        {
+            let req = test::TestRequest::post()
+                .uri("/api/group")
+                .set_json(&new_group)
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+        }
+    }
+}
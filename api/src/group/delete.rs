@@ -0,0 +1,141 @@
+use crate::audit::request_id;
+use crate::auth::Claims;
+use crate::error::AsHttpError;
+use crate::group::audited;
+use crate::user::precondition;
+use crate::{AppState, Management};
+use actix_web::web::{Data, Path};
+use actix_web::{delete, HttpRequest, HttpResponse, Responder};
+use common::error::CoreError;
+use sea_orm::TransactionTrait;
+use std::sync::Arc;
+
+#[utoipa::path(
+tag = "Groups",
+context_path = "/api/group",
+params(
+("group_id" = Uuid, Path, description = "Group identifier"),
+("If-Match" = Option<String>, Header, description = "ETag of the version to delete")
+),
+responses((status=204, description = "Group deletion succeed"),
+(status=401, description = "Authentication required"),
+(status=403, description = "Permission group:delete required"),
+(status=404, description = "Group not found"),
+(status=412, description = "Group changed since the version given by If-Match")
+),
+security(("api_jwt_token" = []))
+)]
+#[delete("/{group_id}")]
+pub(super) async fn delete(
+    request: HttpRequest,
+    group_id: Path<String>,
+    claims: Claims,
+    app_data: Data<Arc<AppState>>,
+) -> actix_web::Result<impl Responder> {
+    let group_id = group_id.into_inner();
+    let transaction = app_data
+        .db_connection
+        .begin()
+        .await
+        .map_err(|e| AsHttpError::from(CoreError::UnkownError(e.to_string())))?;
+    {
+        let management: Management<_, _, _, _, _, _> = Management(Box::new(audited(
+            claims.sub,
+            claims.tenant,
+            request_id(&request),
+        )));
+        management
+            .0
+            .delete(Box::new(group_id), precondition(&request), &transaction)
+            .await
+            .map_err(|e| AsHttpError::from(e.get_core_error()))?;
+    }
+    transaction
+        .commit()
+        .await
+        .map_err(|e| AsHttpError::from(CoreError::UnkownError(e.to_string())))?;
+    Ok(HttpResponse::NoContent().finish())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::group::create::create;
+    use crate::group::delete::delete;
+    use crate::group::find::find;
+    use crate::group::Group;
+    use crate::tests::{access_token, administrator_id, app_state, test_settings};
+    use actix_web::http::header::{AUTHORIZATION, IF_MATCH};
+    use actix_web::http::StatusCode;
+    use actix_web::web::Data;
+    use actix_web::{test, web, App};
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn test_group_delete() {
+        let state = app_state(test_settings()).await;
+        let authorization = format!(
+            "Bearer {}",
+            access_token(&state.settings, &administrator_id(&state).await, 60)
+        );
+
+        let app = test::init_service(
+            App::new().app_data(Data::new(Arc::clone(&state))).service(
+                web::scope("/api/group")
+                    .service(create)
+                    .service(find)
+                    .service(delete),
+            ),
+        )
+        .await;
+
+        let req = test::TestRequest::post()
+            .uri("/api/group")
+            .insert_header((AUTHORIZATION, authorization.as_str()))
+            .set_json(&Group {
+                name: "Temporary".to_string(),
+                ..Default::default()
+            })
+            .to_request();
+        let group: Group = test::call_and_read_body_json(&app, req).await;
+        let path = format!("/api/group/{}", group.id);
+
+        // test 412
+        {
+            let req = test::TestRequest::delete()
+                .uri(path.as_str())
+                .insert_header((AUTHORIZATION, authorization.as_str()))
+                .insert_header((IF_MATCH, "\"2\""))
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::PRECONDITION_FAILED);
+        }
+
+        // test 204, the deleted group is not found anymore
+        {
+            let req = test::TestRequest::delete()
+                .uri(path.as_str())
+                .insert_header((AUTHORIZATION, authorization.as_str()))
+                .insert_header((IF_MATCH, "\"1\""))
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::NO_CONTENT);
+
+            let req = test::TestRequest::get()
+                .uri(path.as_str())
+                .insert_header((AUTHORIZATION, authorization.as_str()))
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+        }
+
+        // test 404, a group is deleted once
+        {
+            let req = test::TestRequest::delete()
+                .uri(path.as_str())
+                .insert_header((AUTHORIZATION, authorization.as_str()))
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+        }
+    }
+}
@@ -0,0 +1,132 @@
+use crate::auth::Claims;
+use crate::cache::Representation;
+use crate::error::AsHttpError;
+use crate::group::Group;
+use crate::user::entity_tag;
+use crate::{AppState, Management};
+use actix_web::web::{Data, Path};
+use actix_web::{get, HttpRequest, HttpResponse, Responder};
+use services_local::authorization::Authorized;
+use services_local::group::GroupManagement;
+use std::sync::Arc;
+
+#[utoipa::path(
+tag = "Groups",
+context_path = "/api/group",
+params(
+("group_id" = Uuid, Path, description = "Group identifier")
+),
+responses((status=200, description = "Group read succeed", body = Group,
+headers(("ETag" = String, description = "Version of the group"),
+("Last-Modified" = String, description = "Date of the last change of the group"))),
+(status=304, description = "The group did not change since the ETag or the date of the client copy"),
+(status=400, description = "Group identifier not correct"),
+(status=401, description = "Authentication required"),
+(status=404, description = "Group not found")
+),
+security(("api_jwt_token" = []))
+)]
+#[get("/{group_id}")]
+pub(super) async fn find(
+    request: HttpRequest,
+    group_id: Path<String>,
+    claims: Claims,
+    app_data: Data<Arc<AppState>>,
+) -> actix_web::Result<impl Responder> {
+    let group_id = group_id.into_inner();
+    let management: Management<_, _, _, _, _, _> = Management(Box::new(Authorized::new(
+        Box::new(claims.sub),
+        claims.tenant,
+        "group",
+        GroupManagement::new(claims.tenant),
+    )));
+    let response = management
+        .0
+        .find(Box::new(group_id), false, &app_data.db_connection)
+        .await
+        .map_err(|e| AsHttpError::from(e.get_core_error()))?;
+    let last_modified = response.get_last_modified();
+    let response = Group::from(response);
+    Ok(Representation::new(&response, last_modified)?
+        .with_etag(entity_tag(response.version).0)
+        .respond(&request, &app_data.settings.application, HttpResponse::Ok()))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::group::create::create;
+    use crate::group::find::find;
+    use crate::group::Group;
+    use crate::tests::{access_token, administrator_id, app_state, test_settings};
+    use actix_web::http::header::{AUTHORIZATION, ETAG, IF_NONE_MATCH};
+    use actix_web::http::StatusCode;
+    use actix_web::web::Data;
+    use actix_web::{test, web, App};
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn test_group_find() {
+        let state = app_state(test_settings()).await;
+        let authorization = format!(
+            "Bearer {}",
+            access_token(&state.settings, &administrator_id(&state).await, 60)
+        );
+
+        let app = test::init_service(
+            App::new()
+                .app_data(Data::new(Arc::clone(&state)))
+                .service(web::scope("/api/group").service(create).service(find)),
+        )
+        .await;
+
+        let req = test::TestRequest::post()
+            .uri("/api/group")
+            .insert_header((AUTHORIZATION, authorization.as_str()))
+            .set_json(&Group {
+                name: "Support".to_string(),
+                ..Default::default()
+            })
+            .to_request();
+        let group: Group = test::call_and_read_body_json(&app, req).await;
+        let path = format!("/api/group/{}", group.id);
+
+        // test 200 then 304 with the ETag of the version
+        {
+            let req = test::TestRequest::get()
+                .uri(path.as_str())
+                .insert_header((AUTHORIZATION, authorization.as_str()))
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::OK);
+            assert_eq!(resp.headers().get(ETAG).unwrap(), "\"1\"");
+            let found_group: Group = test::read_body_json(resp).await;
+            assert_eq!(found_group.id, group.id);
+            assert_eq!(found_group.name, group.name);
+
+            let req = test::TestRequest::get()
+                .uri(path.as_str())
+                .insert_header((AUTHORIZATION, authorization.as_str()))
+                .insert_header((IF_NONE_MATCH, "\"1\""))
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::NOT_MODIFIED);
+        }
+
+        // test 404
+        {
+            let req = test::TestRequest::get()
+                .uri(format!("/api/group/{}", uuid::Uuid::new_v4()).as_str())
+                .insert_header((AUTHORIZATION, authorization.as_str()))
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+        }
+
+        // test 401
+        {
+            let req = test::TestRequest::get().uri(path.as_str()).to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+        }
+    }
+}
@@ -0,0 +1,223 @@
+use crate::swagger::SecurityAddon;
+use actix_web::web::{scope, ServiceConfig};
+use chrono::{DateTime, Utc};
+use services_local::audit::Audited;
+use services_local::authorization::Authorized;
+use services_local::group::GroupManagement;
+
+use crate::group::add_member::add_member as add_group_member;
+use crate::group::create::create as create_group;
+use crate::group::delete::delete as delete_group;
+use crate::group::find::find as find_group;
+use crate::group::patch::patch as patch_group;
+use crate::group::purge::purge as purge_group;
+use crate::group::read::read as read_group;
+use crate::group::remove_member::remove_member as remove_group_member;
+use crate::group::restore::restore as restore_group;
+use crate::group::update::update as update_group;
+use crate::user::deserialize_present;
+use common::group::groupable::Groupable;
+use common::group::patchable::Patchable;
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::fmt::Display;
+use std::str::FromStr;
+use utoipa::{OpenApi, ToSchema};
+use uuid::Uuid;
+use validator::{Validate, ValidationError, ValidationErrors};
+
+mod add_member;
+mod create;
+mod delete;
+mod find;
+mod patch;
+mod purge;
+mod read;
+mod remove_member;
+mod restore;
+mod update;
+
+pub(crate) fn init(cfg: &mut ServiceConfig) {
+    cfg.service(
+        scope("")
+            .service(read_group)
+            .service(find_group)
+            .service(create_group)
+            .service(update_group)
+            .service(patch_group)
+            .service(delete_group)
+            .service(restore_group)
+            .service(purge_group)
+            .service(add_group_member)
+            .service(remove_group_member),
+    );
+}
+
+#[derive(Default, Serialize, Deserialize, ToSchema, Validate, Debug, Clone)]
+pub struct Group {
+    #[serde(default)]
+    pub id: Uuid,
+    #[validate(
+        length(min = 1, code = "required", message = "Name must not be empty"),
+        length(
+            max = 100,
+            code = "too_long",
+            message = "Name must contain at most 100 characters"
+        )
+    )]
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[validate(length(
+        max = 1000,
+        code = "too_long",
+        message = "Description must contain at most 1000 characters"
+    ))]
+    pub description: Option<String>,
+    /// Incremented on every change, sent back as the `ETag` of the group
+    #[serde(default)]
+    #[schema(read_only)]
+    pub version: i32,
+    /// Set while the group is deleted, only listed with `include_deleted`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schema(read_only)]
+    pub deleted_at: Option<DateTime<Utc>>,
+}
+
+/// RFC 7396 merge patch of a group, the fields left out keep their value.
+/// The name can not be removed, `null` removes the description.
+#[derive(Default, Serialize, Deserialize, ToSchema, Validate)]
+#[serde(deny_unknown_fields)]
+pub(super) struct GroupPatch {
+    #[serde(
+        default,
+        deserialize_with = "deserialize_present",
+        skip_serializing_if = "Option::is_none"
+    )]
+    #[schema(value_type = Option<String>)]
+    #[validate(
+        length(min = 1, code = "required", message = "Name must not be empty"),
+        length(
+            max = 100,
+            code = "too_long",
+            message = "Name must contain at most 100 characters"
+        )
+    )]
+    pub name: Option<Option<String>>,
+    #[serde(
+        default,
+        deserialize_with = "deserialize_present",
+        skip_serializing_if = "Option::is_none"
+    )]
+    #[schema(value_type = Option<String>)]
+    #[validate(length(
+        max = 1000,
+        code = "too_long",
+        message = "Description must contain at most 1000 characters"
+    ))]
+    pub description: Option<Option<String>>,
+}
+
+impl GroupPatch {
+    /// Validate the fields given and refuse a name sent as `null`.
+    fn check(&self) -> Result<(), ValidationErrors> {
+        let mut errors = self.validate().err().unwrap_or_default();
+        if matches!(self.name, Some(None)) {
+            let mut error = ValidationError::new("required");
+            error.message = Some(Cow::from("name can not be removed"));
+            errors.add("name", error);
+        }
+        match errors.is_empty() {
+            true => Ok(()),
+            false => Err(errors),
+        }
+    }
+}
+
+impl Patchable for GroupPatch {
+    fn get_name(&self) -> Option<String> {
+        self.name.clone().flatten()
+    }
+
+    fn get_description(&self) -> Option<Option<String>> {
+        self.description.clone()
+    }
+}
+
+/// Groups of the organization `tenant` managed on behalf of `actor`,
+/// checking the permissions and recording the changes
+pub(super) fn audited(
+    actor: String,
+    tenant: Uuid,
+    request_id: Option<String>,
+) -> Authorized<Audited<GroupManagement>> {
+    Authorized::new(
+        Box::new(actor.to_owned()),
+        tenant,
+        "group",
+        Audited::new(
+            Box::new(actor),
+            tenant,
+            request_id,
+            "group",
+            GroupManagement::new(tenant),
+        ),
+    )
+}
+
+impl Groupable for Group {
+    fn get_id(&self) -> Box<dyn Display + Sync + Send> {
+        Box::new(self.id) as Box<dyn Display + Sync + Send>
+    }
+
+    fn get_name(&self) -> String {
+        self.name.to_owned()
+    }
+
+    fn get_description(&self) -> Option<String> {
+        self.description.to_owned()
+    }
+
+    fn get_version(&self) -> i32 {
+        self.version
+    }
+
+    fn get_last_modified(&self) -> Option<DateTime<Utc>> {
+        None
+    }
+
+    fn get_deleted_at(&self) -> Option<DateTime<Utc>> {
+        self.deleted_at
+    }
+}
+
+impl From<Box<dyn Groupable>> for Group {
+    fn from(value: Box<dyn Groupable>) -> Self {
+        Self {
+            id: Uuid::from_str(value.get_id().to_string().as_str()).unwrap(),
+            name: value.get_name(),
+            description: value.get_description(),
+            version: value.get_version(),
+            deleted_at: value.get_deleted_at(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, ToSchema, Clone)]
+pub struct GroupSearchResult {
+    pub num_pages: usize,
+    pub total_items: usize,
+    pub page: usize,
+    pub per_page: usize,
+    pub result: Vec<Group>,
+}
+
+pub fn api_docs() -> utoipa::openapi::OpenApi {
+    #[derive(OpenApi)]
+    #[openapi(
+    paths(crate::group::create::create,crate::group::read::read,crate::group::find::find,crate::group::update::update,crate::group::patch::patch,crate::group::delete::delete,crate::group::restore::restore,crate::group::purge::purge,crate::group::add_member::add_member,crate::group::remove_member::remove_member),
+    components(schemas(crate::group::Group,crate::group::GroupPatch,crate::group::GroupSearchResult)),
+    modifiers(&SecurityAddon)
+    )]
+    struct ApiDocs;
+    ApiDocs::openapi()
+}
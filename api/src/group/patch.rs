@@ -0,0 +1,154 @@
+use crate::audit::request_id;
+use crate::auth::Claims;
+use crate::error::AsHttpError;
+use crate::group::{audited, Group, GroupPatch};
+use crate::user::{entity_tag, precondition};
+use crate::{AppState, Management};
+use actix_web::web::{Data, Json, Path};
+use actix_web::{patch, HttpRequest, HttpResponse, Responder};
+use common::error::CoreError;
+use sea_orm::TransactionTrait;
+use std::sync::Arc;
+
+#[utoipa::path(
+tag = "Groups",
+context_path = "/api/group",
+params(
+("group_id" = Uuid, Path, description = "Group identifier"),
+("If-Match" = Option<String>, Header, description = "ETag of the version to update")
+),
+request_body(content = GroupPatch, content_type = "application/merge-patch+json"),
+responses((status=200, description = "Group update succeed", body = Group, headers(("ETag" = String, description = "Version of the group"))),
+(status=400, description = "Sent data not correct"),
+(status=401, description = "Authentication required"),
+(status=403, description = "Permission group:update required"),
+(status=404, description = "Group not found"),
+(status=409, description = "Name already used"),
+(status=412, description = "Group changed since the version given by If-Match"),
+(status=422, description = "Fields not valid")
+),
+security(("api_jwt_token" = []))
+)]
+#[patch("/{group_id}")]
+pub(super) async fn patch(
+    request: HttpRequest,
+    group_id: Path<String>,
+    group_patch: Json<GroupPatch>,
+    claims: Claims,
+    app_data: Data<Arc<AppState>>,
+) -> actix_web::Result<impl Responder> {
+    let group_id = group_id.into_inner();
+    let group_patch = group_patch.into_inner();
+    group_patch.check().map_err(AsHttpError::from)?;
+    let transaction = app_data
+        .db_connection
+        .begin()
+        .await
+        .map_err(|e| AsHttpError::from(CoreError::UnkownError(e.to_string())))?;
+    let response = {
+        let management: Management<_, _, _, _, _, _> = Management(Box::new(audited(
+            claims.sub,
+            claims.tenant,
+            request_id(&request),
+        )));
+        management
+            .0
+            .patch(
+                Box::new(group_id),
+                Box::new(group_patch),
+                precondition(&request),
+                &transaction,
+            )
+            .await
+            .map(Group::from)
+            .map_err(|e| AsHttpError::from(e.get_core_error()))?
+    };
+    transaction
+        .commit()
+        .await
+        .map_err(|e| AsHttpError::from(CoreError::UnkownError(e.to_string())))?;
+    Ok(HttpResponse::Ok()
+        .insert_header(entity_tag(response.version))
+        .json(response))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::group::create::create;
+    use crate::group::patch::patch;
+    use crate::group::Group;
+    use crate::tests::{access_token, administrator_id, app_state, test_settings};
+    use actix_web::http::header::{AUTHORIZATION, ETAG};
+    use actix_web::http::StatusCode;
+    use actix_web::web::Data;
+    use actix_web::{test, web, App};
+    use serde_json::json;
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn test_group_patch() {
+        let state = app_state(test_settings()).await;
+        let authorization = format!(
+            "Bearer {}",
+            access_token(&state.settings, &administrator_id(&state).await, 60)
+        );
+
+        let app = test::init_service(
+            App::new()
+                .app_data(Data::new(Arc::clone(&state)))
+                .service(web::scope("/api/group").service(create).service(patch)),
+        )
+        .await;
+
+        let req = test::TestRequest::post()
+            .uri("/api/group")
+            .insert_header((AUTHORIZATION, authorization.as_str()))
+            .set_json(&Group {
+                name: "Legal".to_string(),
+                description: Some("Reading contracts".to_string()),
+                ..Default::default()
+            })
+            .to_request();
+        let group: Group = test::call_and_read_body_json(&app, req).await;
+        let path = format!("/api/group/{}", group.id);
+
+        // test 200, only the fields given change and null removes the description
+        {
+            let req = test::TestRequest::patch()
+                .uri(path.as_str())
+                .insert_header((AUTHORIZATION, authorization.as_str()))
+                .set_json(json!({"description": null}))
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::OK);
+            assert_eq!(resp.headers().get(ETAG).unwrap(), "\"2\"");
+            let patched_group: Group = test::read_body_json(resp).await;
+            assert_eq!(patched_group.name, group.name);
+            assert_eq!(patched_group.description, None);
+        }
+
+        // test 422, the name can not be removed
+        {
+            let req = test::TestRequest::patch()
+                .uri(path.as_str())
+                .insert_header((AUTHORIZATION, authorization.as_str()))
+                .set_json(json!({"name": null}))
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::UNPROCESSABLE_ENTITY);
+            let response: serde_json::Value = test::read_body_json(resp).await;
+            assert_eq!(response["errors"][0]["field"], "name");
+        }
+
+        // test 400, unknown fields are refused
+        {
+            let req = test::TestRequest::patch()
+                .uri(path.as_str())
+                .insert_header((AUTHORIZATION, authorization.as_str()))
+                .set_json(json!({"members": []}))
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+        }
+    }
+}
@@ -0,0 +1,284 @@
+use crate::auth::Claims;
+use crate::cache::Representation;
+use crate::error::AsHttpError;
+use crate::group::{Group, GroupSearchResult};
+use crate::{AppState, Authorization, Management};
+use actix_web::web::Data;
+use actix_web::{get, web, HttpRequest, HttpResponse, Responder};
+use common::error::CoreError;
+use common::management::{Cursor, DateRange, Filter, MatchMode, Pagination, Searchable, Sort};
+use serde::{Deserialize, Serialize};
+use services_local::authorization::{Authorized, RoleManagement};
+use services_local::group::GroupManagement;
+use std::fmt::Display;
+use std::str::FromStr;
+use std::sync::Arc;
+
+#[derive(Default, Serialize, Deserialize, Clone)]
+pub(super) struct QueryGroup {
+    /// Searched in the name and the description
+    pattern: Option<String>,
+    /// Comparison of `pattern`: `exact`, `prefix` or `contains` (default), ignoring case
+    match_mode: Option<String>,
+    /// Comma separated fields to sort on, prefixed by `-` for descending order, e.g. `-created_at`
+    sort: Option<String>,
+    page: Option<usize>,
+    per_page: Option<usize>,
+    /// Also list the deleted groups, requires the `group:restore` permission
+    include_deleted: Option<bool>,
+    /// Bounds of `per_page`, read from the settings
+    #[serde(skip)]
+    default_per_page: usize,
+    #[serde(skip)]
+    max_per_page: usize,
+}
+
+impl Searchable for QueryGroup {
+    type Id = Box<dyn Display + Sync + Send>;
+
+    fn get_id(&self) -> Option<Self::Id> {
+        None
+    }
+
+    fn get_pattern(&self) -> Option<Box<dyn Display + Sync + Send>> {
+        self.pattern
+            .clone()
+            .map(|value| Box::new(value) as Box<dyn Display + Sync + Send>)
+    }
+
+    fn get_match_mode(&self) -> Result<MatchMode, CoreError> {
+        match &self.match_mode {
+            Some(match_mode) => MatchMode::from_str(match_mode.as_str()),
+            None => Ok(MatchMode::default()),
+        }
+    }
+
+    fn get_filters(&self) -> Vec<Filter> {
+        Vec::new()
+    }
+
+    fn get_date_range(&self) -> Result<Option<DateRange>, CoreError> {
+        Ok(None)
+    }
+
+    fn get_sort(&self) -> Result<Vec<Sort>, CoreError> {
+        match &self.sort {
+            Some(sort) => sort.split(',').map(Sort::from_str).collect(),
+            None => Ok(Vec::new()),
+        }
+    }
+
+    fn get_cursor(&self) -> Result<Option<Cursor>, CoreError> {
+        Ok(None)
+    }
+
+    fn get_pagination(&self) -> Result<Pagination, CoreError> {
+        Pagination::new(
+            self.page,
+            self.per_page,
+            self.default_per_page,
+            self.max_per_page,
+        )
+    }
+
+    fn get_include_deleted(&self) -> bool {
+        self.include_deleted.unwrap_or_default()
+    }
+}
+
+struct Search(Box<dyn Searchable<Id = Box<dyn Display + Sync + Send>>>);
+
+#[utoipa::path(
+tag = "Groups",
+context_path = "/api/group",
+params(
+("pattern" = Option<String>, Query, description = "Searched in the name and the description"),
+("match_mode" = Option<String>, Query, description = "exact, prefix or contains (default)"),
+("sort" = Option<String>, Query, description = "Comma separated fields among id, name, created_at and updated_at, prefixed by - for a descending order"),
+("page" = Option<usize>, Query, description = "Page number, starting from 1"),
+("per_page" = Option<usize>, Query, description = "Number of groups per page"),
+("include_deleted" = Option<bool>, Query, description = "Also list the deleted groups")
+),
+responses((status=200, description = "Groups list succeed", body = GroupSearchResult,
+headers(("ETag" = String, description = "Digest of the page"),
+("Last-Modified" = String, description = "Latest change among the groups of the organization"))),
+(status=304, description = "The page did not change since the ETag or the date of the client copy"),
+(status=400, description = "Query not correct"),
+(status=401, description = "Authentication required"),
+(status=403, description = "Permission group:restore required by include_deleted")
+),
+security(("api_jwt_token" = []))
+)]
+#[get("")]
+pub(super) async fn read(
+    request: HttpRequest,
+    app_data: Data<Arc<AppState>>,
+    query: web::Query<QueryGroup>,
+    claims: Claims,
+) -> actix_web::Result<impl Responder> {
+    if query.include_deleted.unwrap_or_default() {
+        let authorization: Authorization<_, _> =
+            Authorization(Box::new(RoleManagement::new(claims.tenant)));
+        authorization
+            .0
+            .authorize(
+                Box::new(claims.sub.to_owned()),
+                String::from("group:restore"),
+                None,
+                &app_data.db_connection,
+            )
+            .await
+            .map_err(|e| AsHttpError::from(e.get_core_error()))?;
+    }
+    let (management, search): (Management<_, _, _, _, _, _>, Search) = (
+        Management(Box::new(Authorized::new(
+            Box::new(claims.sub),
+            claims.tenant,
+            "group",
+            GroupManagement::new(claims.tenant),
+        ))),
+        Search(Box::new(QueryGroup {
+            default_per_page: app_data.settings.pagination.default_page_size,
+            max_per_page: app_data.settings.pagination.max_page_size,
+            ..query.into_inner()
+        })),
+    );
+    let search_result = management
+        .0
+        .read(search.0, &app_data.db_connection)
+        .await
+        .map_err(|e| AsHttpError::from(e.get_core_error()))?;
+    let response = GroupSearchResult {
+        num_pages: search_result.get_num_pages().unwrap_or_default(),
+        total_items: search_result.get_total_items().unwrap_or_default(),
+        page: search_result.get_page().unwrap_or_default(),
+        per_page: search_result.get_per_page(),
+        result: search_result.get_result().map(Group::from).collect(),
+    };
+    Ok(
+        Representation::new(&response, search_result.get_last_modified())?.respond(
+            &request,
+            &app_data.settings.application,
+            HttpResponse::Ok(),
+        ),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::group::{Group, GroupSearchResult};
+    use crate::tests::{access_token, administrator_id, app_state, test_settings};
+    use crate::user::{NewUser, User};
+    use actix_web::http::header::AUTHORIZATION;
+    use actix_web::http::StatusCode;
+    use actix_web::web::Data;
+    use actix_web::{test, web, App};
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn test_group_read() {
+        let state = app_state(test_settings()).await;
+        let authorization = format!(
+            "Bearer {}",
+            access_token(&state.settings, &administrator_id(&state).await, 60)
+        );
+
+        let app = test::init_service(
+            App::new()
+                .app_data(Data::new(Arc::clone(&state)))
+                .service(web::scope("/api/user").configure(crate::user::init))
+                .service(web::scope("/api/group").configure(crate::group::init)),
+        )
+        .await;
+
+        let mut groups = Vec::new();
+        for name in ["Reading room", "Reading club", "Deleted readers"] {
+            let req = test::TestRequest::post()
+                .uri("/api/group")
+                .insert_header((AUTHORIZATION, authorization.as_str()))
+                .set_json(&Group {
+                    name: name.to_string(),
+                    ..Default::default()
+                })
+                .to_request();
+            let group: Group = test::call_and_read_body_json(&app, req).await;
+            groups.push(group);
+        }
+        let req = test::TestRequest::delete()
+            .uri(format!("/api/group/{}", groups[2].id).as_str())
+            .insert_header((AUTHORIZATION, authorization.as_str()))
+            .to_request();
+        test::call_service(&app, req).await;
+
+        // test 200, the deleted groups are left out
+        {
+            let req = test::TestRequest::get()
+                .uri("/api/group?pattern=read&sort=-name&per_page=1")
+                .insert_header((AUTHORIZATION, authorization.as_str()))
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::OK);
+            let search_result: GroupSearchResult = test::read_body_json(resp).await;
+            assert_eq!(search_result.total_items, 2);
+            assert_eq!(search_result.num_pages, 2);
+            assert_eq!(search_result.per_page, 1);
+            assert_eq!(search_result.result[0].id, groups[0].id);
+        }
+
+        // test 200, include_deleted also lists the deleted groups
+        {
+            let req = test::TestRequest::get()
+                .uri("/api/group?pattern=read&include_deleted=true")
+                .insert_header((AUTHORIZATION, authorization.as_str()))
+                .to_request();
+            let search_result: GroupSearchResult = test::call_and_read_body_json(&app, req).await;
+            assert_eq!(search_result.total_items, 3);
+            assert!(search_result
+                .result
+                .iter()
+                .any(|group| group.id == groups[2].id && group.deleted_at.is_some()));
+        }
+
+        // test 403, include_deleted requires group:restore
+        {
+            let req = test::TestRequest::post()
+                .uri("/api/user")
+                .insert_header((AUTHORIZATION, authorization.as_str()))
+                .set_json(&NewUser {
+                    first_name: "Group".to_string(),
+                    last_name: "Reader".to_string(),
+                    email: "group.reader@example.com".to_string(),
+                    password: None,
+                })
+                .to_request();
+            let user: User = test::call_and_read_body_json(&app, req).await;
+            let user_authorization = format!(
+                "Bearer {}",
+                access_token(&state.settings, &user.id.to_string(), 60)
+            );
+            let req = test::TestRequest::get()
+                .uri("/api/group?pattern=read")
+                .insert_header((AUTHORIZATION, user_authorization.as_str()))
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::OK);
+
+            let req = test::TestRequest::get()
+                .uri("/api/group?include_deleted=true")
+                .insert_header((AUTHORIZATION, user_authorization.as_str()))
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+        }
+
+        // test 400
+        {
+            let req = test::TestRequest::get()
+                .uri("/api/group?sort=email")
+                .insert_header((AUTHORIZATION, authorization.as_str()))
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+        }
+    }
+}
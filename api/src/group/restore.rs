@@ -0,0 +1,132 @@
+use crate::audit::request_id;
+use crate::auth::Claims;
+use crate::error::AsHttpError;
+use crate::group::{audited, Group};
+use crate::user::entity_tag;
+use crate::{AppState, Management};
+use actix_web::web::{Data, Path};
+use actix_web::{post, HttpRequest, HttpResponse, Responder};
+use common::error::CoreError;
+use sea_orm::TransactionTrait;
+use std::sync::Arc;
+
+#[utoipa::path(
+tag = "Groups",
+context_path = "/api/group",
+params(
+("group_id" = Uuid, Path, description = "Group identifier")
+),
+responses((status=200, description = "Group restoration succeed", body = Group,
+headers(("ETag" = String, description = "Version of the group"))),
+(status=401, description = "Authentication required"),
+(status=403, description = "Permission group:restore required"),
+(status=404, description = "Group not found")
+),
+security(("api_jwt_token" = []))
+)]
+#[post("/{group_id}/restore")]
+pub(super) async fn restore(
+    request: HttpRequest,
+    group_id: Path<String>,
+    claims: Claims,
+    app_data: Data<Arc<AppState>>,
+) -> actix_web::Result<impl Responder> {
+    let group_id = group_id.into_inner();
+    let transaction = app_data
+        .db_connection
+        .begin()
+        .await
+        .map_err(|e| AsHttpError::from(CoreError::UnkownError(e.to_string())))?;
+    let response = {
+        let management: Management<_, _, _, _, _, _> = Management(Box::new(audited(
+            claims.sub,
+            claims.tenant,
+            request_id(&request),
+        )));
+        management
+            .0
+            .restore(Box::new(group_id), &transaction)
+            .await
+            .map(Group::from)
+            .map_err(|e| AsHttpError::from(e.get_core_error()))?
+    };
+    transaction
+        .commit()
+        .await
+        .map_err(|e| AsHttpError::from(CoreError::UnkownError(e.to_string())))?;
+    Ok(HttpResponse::Ok()
+        .insert_header(entity_tag(response.version))
+        .json(response))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::group::create::create;
+    use crate::group::delete::delete;
+    use crate::group::restore::restore;
+    use crate::group::Group;
+    use crate::tests::{access_token, administrator_id, app_state, test_settings};
+    use actix_web::http::header::AUTHORIZATION;
+    use actix_web::http::StatusCode;
+    use actix_web::web::Data;
+    use actix_web::{test, web, App};
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn test_group_restore() {
+        let state = app_state(test_settings()).await;
+        let authorization = format!(
+            "Bearer {}",
+            access_token(&state.settings, &administrator_id(&state).await, 60)
+        );
+
+        let app = test::init_service(
+            App::new().app_data(Data::new(Arc::clone(&state))).service(
+                web::scope("/api/group")
+                    .service(create)
+                    .service(delete)
+                    .service(restore),
+            ),
+        )
+        .await;
+
+        let req = test::TestRequest::post()
+            .uri("/api/group")
+            .insert_header((AUTHORIZATION, authorization.as_str()))
+            .set_json(&Group {
+                name: "Alumni".to_string(),
+                ..Default::default()
+            })
+            .to_request();
+        let group: Group = test::call_and_read_body_json(&app, req).await;
+        let req = test::TestRequest::delete()
+            .uri(format!("/api/group/{}", group.id).as_str())
+            .insert_header((AUTHORIZATION, authorization.as_str()))
+            .to_request();
+        test::call_service(&app, req).await;
+
+        // test 200
+        {
+            let req = test::TestRequest::post()
+                .uri(format!("/api/group/{}/restore", group.id).as_str())
+                .insert_header((AUTHORIZATION, authorization.as_str()))
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::OK);
+            let restored_group: Group = test::read_body_json(resp).await;
+            assert_eq!(restored_group.id, group.id);
+            assert_eq!(restored_group.deleted_at, None);
+            assert_eq!(restored_group.version, 3);
+        }
+
+        // test 404
+        {
+            let req = test::TestRequest::post()
+                .uri(format!("/api/group/{}/restore", uuid::Uuid::new_v4()).as_str())
+                .insert_header((AUTHORIZATION, authorization.as_str()))
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+        }
+    }
+}
@@ -0,0 +1,158 @@
+use crate::audit::request_id;
+use crate::auth::Claims;
+use crate::error::AsHttpError;
+use crate::group::{audited, Group};
+use crate::user::{entity_tag, precondition};
+use crate::{AppState, Management};
+use actix_web::web::{Data, Json, Path};
+use actix_web::{put, HttpRequest, HttpResponse, Responder};
+use common::error::CoreError;
+use sea_orm::TransactionTrait;
+use std::sync::Arc;
+use validator::Validate;
+
+#[utoipa::path(
+tag = "Groups",
+context_path = "/api/group",
+params(
+("group_id" = Uuid, Path, description = "Group identifier"),
+("If-Match" = Option<String>, Header, description = "ETag of the version to update")
+),
+request_body = Group,
+responses((status=200, description = "Group update succeed", body = Group, headers(("ETag" = String, description = "Version of the group"))),
+(status=400, description = "Sent data not correct"),
+(status=401, description = "Authentication required"),
+(status=403, description = "Permission group:update required"),
+(status=404, description = "Group not found"),
+(status=409, description = "Name already used"),
+(status=412, description = "Group changed since the version given by If-Match"),
+(status=422, description = "Fields not valid")
+),
+security(("api_jwt_token" = []))
+)]
+#[put("/{group_id}")]
+pub(super) async fn update(
+    request: HttpRequest,
+    group_id: Path<String>,
+    group: Json<Group>,
+    claims: Claims,
+    app_data: Data<Arc<AppState>>,
+) -> actix_web::Result<impl Responder> {
+    let group_id = group_id.into_inner();
+    let group = group.into_inner();
+    group.validate().map_err(AsHttpError::from)?;
+    let transaction = app_data
+        .db_connection
+        .begin()
+        .await
+        .map_err(|e| AsHttpError::from(CoreError::UnkownError(e.to_string())))?;
+    let response = {
+        let management: Management<_, _, _, _, _, _> = Management(Box::new(audited(
+            claims.sub,
+            claims.tenant,
+            request_id(&request),
+        )));
+        management
+            .0
+            .update(
+                Box::new(group_id),
+                Box::new(group),
+                precondition(&request),
+                &transaction,
+            )
+            .await
+            .map(Group::from)
+            .map_err(|e| AsHttpError::from(e.get_core_error()))?
+    };
+    transaction
+        .commit()
+        .await
+        .map_err(|e| AsHttpError::from(CoreError::UnkownError(e.to_string())))?;
+    Ok(HttpResponse::Ok()
+        .insert_header(entity_tag(response.version))
+        .json(response))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::group::create::create;
+    use crate::group::update::update;
+    use crate::group::Group;
+    use crate::tests::{access_token, administrator_id, app_state, test_settings};
+    use actix_web::http::header::{AUTHORIZATION, ETAG, IF_MATCH};
+    use actix_web::http::StatusCode;
+    use actix_web::web::Data;
+    use actix_web::{test, web, App};
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn test_group_update() {
+        let state = app_state(test_settings()).await;
+        let authorization = format!(
+            "Bearer {}",
+            access_token(&state.settings, &administrator_id(&state).await, 60)
+        );
+
+        let app = test::init_service(
+            App::new()
+                .app_data(Data::new(Arc::clone(&state)))
+                .service(web::scope("/api/group").service(create).service(update)),
+        )
+        .await;
+
+        let req = test::TestRequest::post()
+            .uri("/api/group")
+            .insert_header((AUTHORIZATION, authorization.as_str()))
+            .set_json(&Group {
+                name: "Marketing".to_string(),
+                description: Some("Selling things".to_string()),
+                ..Default::default()
+            })
+            .to_request();
+        let group: Group = test::call_and_read_body_json(&app, req).await;
+        let path = format!("/api/group/{}", group.id);
+
+        // test 200, every field is replaced
+        {
+            let req = test::TestRequest::put()
+                .uri(path.as_str())
+                .insert_header((AUTHORIZATION, authorization.as_str()))
+                .insert_header((IF_MATCH, "\"1\""))
+                .set_json(&Group {
+                    name: "Growth".to_string(),
+                    ..Default::default()
+                })
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::OK);
+            assert_eq!(resp.headers().get(ETAG).unwrap(), "\"2\"");
+            let updated_group: Group = test::read_body_json(resp).await;
+            assert_eq!(updated_group.id, group.id);
+            assert_eq!(updated_group.name, "Growth");
+            assert_eq!(updated_group.description, None);
+        }
+
+        // test 412, the group changed since version 1
+        {
+            let req = test::TestRequest::put()
+                .uri(path.as_str())
+                .insert_header((AUTHORIZATION, authorization.as_str()))
+                .insert_header((IF_MATCH, "\"1\""))
+                .set_json(&group)
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::PRECONDITION_FAILED);
+        }
+
+        // test 404
+        {
+            let req = test::TestRequest::put()
+                .uri(format!("/api/group/{}", uuid::Uuid::new_v4()).as_str())
+                .insert_header((AUTHORIZATION, authorization.as_str()))
+                .set_json(&group)
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+        }
+    }
+}
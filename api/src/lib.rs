@@ -1,22 +1,56 @@
 use actix_web::web::{scope, ServiceConfig};
 
+use common::audit::Auditable;
+use common::authorization::Authorizable;
+use common::credential::Authenticable;
+use common::group::memberable::Memberable;
+use common::import::Importable;
 use common::management::Manageable;
+use common::organization::Organizable;
+use common::token::Refreshable;
 use sea_orm::DatabaseConnection;
+use service_config::Settings;
 use utoipa::OpenApi;
 use utoipa_swagger_ui::SwaggerUi;
 
+mod audit;
+mod auth;
+mod cache;
 mod error;
+mod group;
+mod scim;
+
+pub use auth::init_administrator;
 pub mod swagger;
 mod user;
 
 pub struct AppState {
     pub db_connection: DatabaseConnection,
+    pub settings: Settings,
 }
 
-struct Management<'a, B: ?Sized, D: ?Sized, S: ?Sized, R: ?Sized, I: ?Sized>(
-    Box<dyn Manageable<'a, B, Data = D, Id = I, Result = R, Search = S>>,
+struct Management<'a, B: ?Sized, D: ?Sized, S: ?Sized, R: ?Sized, I: ?Sized, P: ?Sized>(
+    Box<dyn Manageable<'a, B, Data = D, Id = I, Result = R, Search = S, Patch = P>>,
+);
+
+struct Authentication<'a, B: ?Sized, I: ?Sized, U: ?Sized>(
+    Box<dyn Authenticable<'a, B, Id = I, User = U>>,
 );
 
+struct Refresh<'a, B: ?Sized, I: ?Sized>(Box<dyn Refreshable<'a, B, Id = I>>);
+
+struct Audit<'a, B: ?Sized, S: ?Sized, R: ?Sized>(
+    Box<dyn Auditable<'a, B, Search = S, Result = R>>,
+);
+
+struct Authorization<'a, B: ?Sized, I: ?Sized>(Box<dyn Authorizable<'a, B, Id = I>>);
+
+struct Membership<'a, B: ?Sized, I: ?Sized>(Box<dyn Memberable<'a, B, Id = I>>);
+
+struct Tenancy<'a, B: ?Sized, I: ?Sized>(Box<dyn Organizable<'a, B, Id = I>>);
+
+struct Import<'a, B: ?Sized, I: ?Sized>(Box<dyn Importable<'a, B, Id = I>>);
+
 pub fn init(cfg: &mut ServiceConfig) {
     #[derive(OpenApi)]
     #[openapi(info(
@@ -29,22 +63,38 @@ pub fn init(cfg: &mut ServiceConfig) {
     ))]
     struct ApiDocs;
     let mut api_docs = ApiDocs::openapi();
+    api_docs.merge(auth::api_docs());
     api_docs.merge(user::api_docs());
-    cfg.service(scope("/api/user").configure(user::init))
+    api_docs.merge(group::api_docs());
+    api_docs.merge(audit::api_docs());
+    api_docs.merge(scim::api_docs());
+    cfg.configure(error::extractor_errors)
+        .service(scope("/api/auth").configure(auth::init))
+        .service(scope("/api/user").configure(user::init))
+        .service(scope("/api/group").configure(group::init))
+        .service(scope("/api/audit").configure(audit::init))
+        .service(scope("/scim/v2").configure(scim::init))
         .service(SwaggerUi::new("/api/docs/{_:.*}").url("/api/api-docs/openapi.json", api_docs));
 }
 
 #[cfg(test)]
 pub mod tests {
+    use crate::auth::Claims;
+    use crate::{init_administrator, AppState};
+    use common::credential::Authenticable;
     use futures::future::BoxFuture;
     use inventory;
+    use jsonwebtoken::{encode, EncodingKey, Header};
     use lazy_static::lazy_static;
     use log::Level;
     use migration::{Migrator, MigratorTrait};
     use sea_orm::{ConnectOptions, Database, DatabaseConnection, TransactionTrait};
     use service_config::Settings;
+    use services_local::credential::CredentialManagement;
+    use services_local::organization::DEFAULT_ORGANIZATION_ID;
     use std::sync::Arc;
     use tokio::sync::Mutex;
+    use uuid::Uuid;
 
     lazy_static! {
         static ref DB_INITIALIZED: Arc<Mutex<bool>> = Arc::new(Mutex::new(false));
@@ -56,6 +106,81 @@ pub mod tests {
 
     inventory::collect!(InitializeDB);
 
+    /// Settings of the test environment.
+    pub fn test_settings() -> Settings {
+        std::env::set_var("APP_ENVIRONMENT", "test");
+        Settings::load().expect("Error occurs when trying to load settings")
+    }
+
+    /// State of an application configured by `settings` on the initialized test database.
+    pub async fn app_state(settings: Settings) -> Arc<AppState> {
+        initialize_db().await;
+        let mut db_connection_opt = ConnectOptions::new(settings.database.get_url());
+        db_connection_opt.sqlx_logging_level(settings.application.loglevel.to_level_filter());
+
+        let db_connection: DatabaseConnection = Database::connect(db_connection_opt)
+            .await
+            .expect("Error occurs when trying to connect to database");
+        Arc::new(AppState {
+            db_connection,
+            settings,
+        })
+    }
+
+    /// Identifier of the administrator described by the test settings.
+    pub async fn administrator_id(state: &AppState) -> String {
+        CredentialManagement::new(DEFAULT_ORGANIZATION_ID)
+            .identify(
+                state
+                    .settings
+                    .administrator
+                    .as_ref()
+                    .unwrap()
+                    .email
+                    .to_owned(),
+                &state.db_connection,
+            )
+            .await
+            .unwrap()
+            .unwrap()
+            .get_id()
+            .to_string()
+    }
+
+    /// Sign an HS256 access token for `subject` of the default organization expiring in `lifetime` seconds.
+    pub fn access_token(settings: &Settings, subject: &str, lifetime: i64) -> String {
+        tenant_access_token(settings, subject, DEFAULT_ORGANIZATION_ID, lifetime)
+    }
+
+    /// Sign an HS256 access token for `subject` of the organization `tenant` expiring in `lifetime` seconds.
+    pub fn tenant_access_token(
+        settings: &Settings,
+        subject: &str,
+        tenant: Uuid,
+        lifetime: i64,
+    ) -> String {
+        let now = chrono::Utc::now().timestamp();
+        let claims = Claims {
+            sub: subject.to_string(),
+            tenant,
+            iat: now as usize,
+            exp: (now + lifetime) as usize,
+        };
+        encode(
+            &Header::default(),
+            &claims,
+            &EncodingKey::from_secret(
+                settings
+                    .authentication
+                    .secret
+                    .clone()
+                    .unwrap_or_default()
+                    .as_bytes(),
+            ),
+        )
+        .unwrap()
+    }
+
     pub async fn initialize_db() {
         let mut db_initialized = DB_INITIALIZED.lock().await;
         if !(*db_initialized) {
@@ -81,6 +206,13 @@ pub mod tests {
             .await
             .expect("Cannot apply migration !");
 
+            init_administrator(&AppState {
+                db_connection: (*db_connection).clone(),
+                settings: Settings::load().expect("Error occurs when trying to load settings"),
+            })
+            .await
+            .expect("Cannot create administrator !");
+
             for init in inventory::iter::<InitializeDB> {
                 (init.instruction)(
                     database_url.to_owned(),
@@ -0,0 +1,295 @@
+use crate::scim::{
+    location, ScimError, GROUP_SCHEMA, LIST_RESPONSE_SCHEMA, SCIM_JSON, USER_SCHEMA,
+};
+use crate::AppState;
+use actix_web::web::{Data, Path};
+use actix_web::{get, HttpRequest, HttpResponse};
+use common::error::CoreError;
+use serde_json::{json, Value};
+use std::sync::Arc;
+
+/// Attribute of a SCIM schema (RFC 7643 section 7)
+fn attribute(name: &str, kind: &str, required: bool, uniqueness: &str) -> Value {
+    json!({
+        "name": name,
+        "type": kind,
+        "multiValued": false,
+        "required": required,
+        "caseExact": false,
+        "mutability": "readWrite",
+        "returned": "default",
+        "uniqueness": uniqueness,
+    })
+}
+
+/// Schemas of the resources, with the attributes they are given by
+fn resource_schemas(request: &HttpRequest) -> Vec<Value> {
+    let mut name = attribute("name", "complex", true, "none");
+    name["subAttributes"] = json!([
+        attribute("givenName", "string", true, "none"),
+        attribute("familyName", "string", true, "none"),
+    ]);
+    let mut emails = attribute("emails", "complex", false, "none");
+    emails["multiValued"] = json!(true);
+    emails["subAttributes"] = json!([
+        attribute("value", "string", false, "none"),
+        attribute("type", "string", false, "none"),
+        attribute("primary", "boolean", false, "none"),
+    ]);
+    let mut password = attribute("password", "string", false, "none");
+    password["mutability"] = json!("writeOnly");
+    password["returned"] = json!("never");
+    let mut members = attribute("members", "complex", false, "none");
+    members["multiValued"] = json!(true);
+    members["subAttributes"] = json!([
+        attribute("value", "string", false, "none"),
+        attribute("display", "string", false, "none"),
+        attribute("$ref", "reference", false, "none"),
+    ]);
+    members["subAttributes"][1]["mutability"] = json!("readOnly");
+    members["subAttributes"][2]["mutability"] = json!("readOnly");
+    vec![
+        json!({
+            "schemas": ["urn:ietf:params:scim:schemas:core:2.0:Schema"],
+            "id": USER_SCHEMA,
+            "name": "User",
+            "description": "User account, userName being its email",
+            "attributes": [
+                attribute("userName", "string", true, "server"),
+                name,
+                emails,
+                attribute("active", "boolean", false, "none"),
+                password,
+            ],
+            "meta": {
+                "resourceType": "Schema",
+                "location": location(request, format!("/Schemas/{}", USER_SCHEMA).as_str()),
+            },
+        }),
+        json!({
+            "schemas": ["urn:ietf:params:scim:schemas:core:2.0:Schema"],
+            "id": GROUP_SCHEMA,
+            "name": "Group",
+            "description": "Group of users, displayName being its name",
+            "attributes": [
+                attribute("displayName", "string", true, "server"),
+                members,
+            ],
+            "meta": {
+                "resourceType": "Schema",
+                "location": location(request, format!("/Schemas/{}", GROUP_SCHEMA).as_str()),
+            },
+        }),
+    ]
+}
+
+/// Types of the resources provisioned through SCIM
+fn resource_type_list(request: &HttpRequest) -> Vec<Value> {
+    vec![
+        json!({
+            "schemas": ["urn:ietf:params:scim:schemas:core:2.0:ResourceType"],
+            "id": "User",
+            "name": "User",
+            "endpoint": "/Users",
+            "schema": USER_SCHEMA,
+            "meta": {
+                "resourceType": "ResourceType",
+                "location": location(request, "/ResourceTypes/User"),
+            },
+        }),
+        json!({
+            "schemas": ["urn:ietf:params:scim:schemas:core:2.0:ResourceType"],
+            "id": "Group",
+            "name": "Group",
+            "endpoint": "/Groups",
+            "schema": GROUP_SCHEMA,
+            "meta": {
+                "resourceType": "ResourceType",
+                "location": location(request, "/ResourceTypes/Group"),
+            },
+        }),
+    ]
+}
+
+fn list(resources: Vec<Value>) -> HttpResponse {
+    HttpResponse::Ok().content_type(SCIM_JSON).json(json!({
+        "schemas": [LIST_RESPONSE_SCHEMA],
+        "totalResults": resources.len(),
+        "startIndex": 1,
+        "itemsPerPage": resources.len(),
+        "Resources": resources,
+    }))
+}
+
+/// Resource of `resources` identified by `id`
+fn one(resources: Vec<Value>, id: &str) -> Result<HttpResponse, ScimError> {
+    resources
+        .into_iter()
+        .find(|resource| resource["id"] == id)
+        .map(|resource| HttpResponse::Ok().content_type(SCIM_JSON).json(resource))
+        .ok_or_else(|| ScimError::from(CoreError::ResourceNotFound(format!("{} not found", id))))
+}
+
+#[utoipa::path(
+tag = "SCIM",
+context_path = "/scim/v2",
+responses((status=200, description = "Features of the SCIM endpoint", content_type = "application/scim+json"))
+)]
+#[get("/ServiceProviderConfig")]
+pub(super) async fn service_provider_config(
+    request: HttpRequest,
+    app_data: Data<Arc<AppState>>,
+) -> HttpResponse {
+    HttpResponse::Ok().content_type(SCIM_JSON).json(json!({
+        "schemas": ["urn:ietf:params:scim:schemas:core:2.0:ServiceProviderConfig"],
+        "patch": {"supported": true},
+        "bulk": {"supported": false, "maxOperations": 0, "maxPayloadSize": 0},
+        "filter": {"supported": true, "maxResults": app_data.settings.pagination.max_page_size},
+        "changePassword": {"supported": true},
+        "sort": {"supported": true},
+        "etag": {"supported": true},
+        "authenticationSchemes": [{
+            "type": "oauthbearertoken",
+            "name": "OAuth Bearer Token",
+            "description": "Access token given by POST /api/auth/login",
+            "primary": true,
+        }],
+        "meta": {
+            "resourceType": "ServiceProviderConfig",
+            "location": location(&request, "/ServiceProviderConfig"),
+        },
+    }))
+}
+
+#[utoipa::path(
+tag = "SCIM",
+context_path = "/scim/v2",
+responses((status=200, description = "Schemas of the resources", content_type = "application/scim+json"))
+)]
+#[get("/Schemas")]
+pub(super) async fn schemas(request: HttpRequest) -> HttpResponse {
+    list(resource_schemas(&request))
+}
+
+#[utoipa::path(
+tag = "SCIM",
+context_path = "/scim/v2",
+params(
+("schema_id" = String, Path, description = "Schema URN, e.g. urn:ietf:params:scim:schemas:core:2.0:User")
+),
+responses((status=200, description = "Schema of a resource", content_type = "application/scim+json"),
+(status=404, description = "Schema not found")
+)
+)]
+#[get("/Schemas/{schema_id}")]
+pub(super) async fn schema(
+    request: HttpRequest,
+    schema_id: Path<String>,
+) -> Result<HttpResponse, ScimError> {
+    one(resource_schemas(&request), &schema_id)
+}
+
+#[utoipa::path(
+tag = "SCIM",
+context_path = "/scim/v2",
+responses((status=200, description = "Types of the resources", content_type = "application/scim+json"))
+)]
+#[get("/ResourceTypes")]
+pub(super) async fn resource_types(request: HttpRequest) -> HttpResponse {
+    list(resource_type_list(&request))
+}
+
+#[utoipa::path(
+tag = "SCIM",
+context_path = "/scim/v2",
+params(
+("resource_type_id" = String, Path, description = "Resource type, e.g. User")
+),
+responses((status=200, description = "Type of a resource", content_type = "application/scim+json"),
+(status=404, description = "Resource type not found")
+)
+)]
+#[get("/ResourceTypes/{resource_type_id}")]
+pub(super) async fn resource_type(
+    request: HttpRequest,
+    resource_type_id: Path<String>,
+) -> Result<HttpResponse, ScimError> {
+    one(resource_type_list(&request), &resource_type_id)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{app_state, test_settings};
+    use actix_web::http::StatusCode;
+    use actix_web::web::Data;
+    use actix_web::{test, web, App};
+    use serde_json::Value;
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn test_scim_discovery() {
+        let state = app_state(test_settings()).await;
+
+        let app = test::init_service(
+            App::new()
+                .app_data(Data::new(Arc::clone(&state)))
+                .service(web::scope("/scim/v2").configure(crate::scim::init)),
+        )
+        .await;
+
+        // test 200, no authentication is required
+        {
+            let req = test::TestRequest::get()
+                .uri("/scim/v2/ServiceProviderConfig")
+                .to_request();
+            let config: Value = test::call_and_read_body_json(&app, req).await;
+            assert_eq!(config["patch"]["supported"], true);
+            assert_eq!(config["bulk"]["supported"], false);
+            assert_eq!(
+                config["filter"]["maxResults"],
+                state.settings.pagination.max_page_size
+            );
+        }
+
+        // test 200, the schemas and the resource types are listed
+        {
+            let req = test::TestRequest::get()
+                .uri("/scim/v2/Schemas")
+                .to_request();
+            let schemas: Value = test::call_and_read_body_json(&app, req).await;
+            assert_eq!(
+                schemas["Resources"][0]["id"],
+                "urn:ietf:params:scim:schemas:core:2.0:User"
+            );
+
+            let req = test::TestRequest::get()
+                .uri("/scim/v2/ResourceTypes/User")
+                .to_request();
+            let resource_type: Value = test::call_and_read_body_json(&app, req).await;
+            assert_eq!(resource_type["endpoint"], "/Users");
+
+            let req = test::TestRequest::get()
+                .uri("/scim/v2/Schemas/urn:ietf:params:scim:schemas:core:2.0:Group")
+                .to_request();
+            let schema: Value = test::call_and_read_body_json(&app, req).await;
+            assert_eq!(schema["attributes"][0]["name"], "displayName");
+            assert_eq!(schema["attributes"][1]["name"], "members");
+
+            let req = test::TestRequest::get()
+                .uri("/scim/v2/ResourceTypes")
+                .to_request();
+            let resource_types: Value = test::call_and_read_body_json(&app, req).await;
+            assert_eq!(resource_types["totalResults"], 2);
+            assert_eq!(resource_types["Resources"][1]["endpoint"], "/Groups");
+        }
+
+        // test 404
+        {
+            let req = test::TestRequest::get()
+                .uri("/scim/v2/ResourceTypes/Printer")
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+        }
+    }
+}
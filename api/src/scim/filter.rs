@@ -0,0 +1,281 @@
+use crate::scim::ScimError;
+use common::error::CoreError;
+use common::management::{Filter, MatchMode, Sort, SortDirection};
+use std::str::FromStr;
+use uuid::Uuid;
+
+/// Resources matched by a SCIM filter (RFC 7644 section 3.4.2.2),
+/// limited to `and` of `eq`, `co` and `sw` comparisons
+#[derive(Debug, Default, PartialEq, Eq)]
+pub(super) struct ScimFilter {
+    pub id: Option<Uuid>,
+    pub filters: Vec<Filter>,
+}
+
+fn invalid_filter(message: String) -> ScimError {
+    ScimError::new(CoreError::DataError(message), "invalidFilter")
+}
+
+/// Resources provisioned through SCIM, each filtered and sorted on its own attributes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum Resource {
+    User,
+    Group,
+}
+
+impl Resource {
+    fn plural(&self) -> &'static str {
+        match self {
+            Self::User => "Users",
+            Self::Group => "Groups",
+        }
+    }
+
+    /// Field of the resources holding the SCIM attribute `path`, compared ignoring case
+    fn field(&self, path: &str) -> Option<&'static str> {
+        let path = path.to_lowercase();
+        let schema = match self {
+            Self::User => "urn:ietf:params:scim:schemas:core:2.0:user:",
+            Self::Group => "urn:ietf:params:scim:schemas:core:2.0:group:",
+        };
+        let path = path.strip_prefix(schema).unwrap_or(path.as_str());
+        match (self, path) {
+            (_, "id") => Some("id"),
+            (_, "meta.created") => Some("created_at"),
+            (_, "meta.lastmodified") => Some("updated_at"),
+            (Self::User, "username" | "emails" | "emails.value") => Some("email"),
+            (Self::User, "name.givenname") => Some("first_name"),
+            (Self::User, "name.familyname") => Some("last_name"),
+            (Self::Group, "displayname") => Some("name"),
+            _ => None,
+        }
+    }
+}
+
+/// Words of `filter`, a quoted value being read as a JSON string
+fn tokens(filter: &str) -> Result<Vec<String>, ScimError> {
+    let mut tokens = Vec::new();
+    let mut chars = filter.char_indices().peekable();
+    while let Some((start, c)) = chars.next() {
+        if c.is_whitespace() {
+            continue;
+        }
+        if c == '"' {
+            let mut escaped = false;
+            let end = chars
+                .by_ref()
+                .find(|(_, c)| {
+                    let closing = *c == '"' && !escaped;
+                    escaped = *c == '\\' && !escaped;
+                    closing
+                })
+                .map(|(end, _)| end)
+                .ok_or_else(|| invalid_filter(String::from("A value is not closed by a quote")))?;
+            let value: String = serde_json::from_str(&filter[start..=end])
+                .map_err(|e| invalid_filter(e.to_string()))?;
+            // Values are kept quoted to tell them from the attributes and the operators.
+            tokens.push(format!("\"{}", value));
+            continue;
+        }
+        let mut end = start + c.len_utf8();
+        while let Some((index, c)) = chars.peek() {
+            if c.is_whitespace() {
+                break;
+            }
+            end = index + c.len_utf8();
+            chars.next();
+        }
+        tokens.push(filter[start..end].to_owned());
+    }
+    Ok(tokens)
+}
+
+impl FromStr for ScimFilter {
+    type Err = ScimError;
+
+    /// Filter of the users
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s, Resource::User)
+    }
+}
+
+impl ScimFilter {
+    pub(super) fn parse(s: &str, resource: Resource) -> Result<Self, ScimError> {
+        let tokens = tokens(s)?;
+        let mut scim_filter = Self::default();
+        for (index, comparison) in tokens
+            .split(|token| token.eq_ignore_ascii_case("and"))
+            .enumerate()
+        {
+            let [path, operator, value] = comparison else {
+                return Err(invalid_filter(format!(
+                    "Comparison {} is not made of an attribute, an operator and a quoted value",
+                    index + 1
+                )));
+            };
+            let Some(value) = value.strip_prefix('"') else {
+                return Err(invalid_filter(format!("{} is not a quoted value", value)));
+            };
+            let field = resource
+                .field(path)
+                .filter(|field| !field.ends_with("_at"))
+                .ok_or_else(|| {
+                    invalid_filter(format!(
+                        "{} can not be filtered on {}",
+                        resource.plural(),
+                        path
+                    ))
+                })?;
+            let match_mode = match operator.to_lowercase().as_str() {
+                "eq" => MatchMode::Exact,
+                "co" => MatchMode::Contains,
+                "sw" => MatchMode::Prefix,
+                other => {
+                    return Err(invalid_filter(format!(
+                        "{} is not a supported operator. Use either eq, co or sw.",
+                        other
+                    )))
+                }
+            };
+            if field == "id" {
+                if match_mode != MatchMode::Exact || scim_filter.id.is_some() {
+                    return Err(invalid_filter(String::from(
+                        "id is only compared once with eq",
+                    )));
+                }
+                scim_filter.id = Some(Uuid::parse_str(value).map_err(|e| {
+                    ScimError::new(CoreError::DataError(e.to_string()), "invalidValue")
+                })?);
+                continue;
+            }
+            scim_filter
+                .filters
+                .push(Filter::new(field, match_mode, value));
+        }
+        Ok(scim_filter)
+    }
+}
+
+/// Sort of the resources on the SCIM attribute `sort_by`, in `sort_order`
+pub(super) fn sort(
+    resource: Resource,
+    sort_by: &str,
+    sort_order: Option<&str>,
+) -> Result<Sort, ScimError> {
+    let field = resource.field(sort_by).ok_or_else(|| {
+        ScimError::new(
+            CoreError::DataError(format!(
+                "{} can not be sorted on {}",
+                resource.plural(),
+                sort_by
+            )),
+            "invalidValue",
+        )
+    })?;
+    let direction = match sort_order.map(str::to_lowercase).as_deref() {
+        None | Some("ascending") => SortDirection::Ascending,
+        Some("descending") => SortDirection::Descending,
+        Some(other) => {
+            return Err(ScimError::new(
+                CoreError::DataError(format!(
+                    "{} is not a sort order. Use either ascending or descending.",
+                    other
+                )),
+                "invalidValue",
+            ))
+        }
+    };
+    Ok(Sort::from_str(
+        match direction {
+            SortDirection::Ascending => field.to_owned(),
+            SortDirection::Descending => format!("-{}", field),
+        }
+        .as_str(),
+    )?)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::scim::filter::{tokens, ScimFilter};
+    use common::management::{Filter, MatchMode};
+    use std::str::FromStr;
+    use uuid::Uuid;
+
+    #[test]
+    fn test_tokens() {
+        assert_eq!(
+            tokens(r#"name.givenName sw "Jean Luc" and userName co "a\"b\\""#).unwrap(),
+            vec![
+                "name.givenName",
+                "sw",
+                "\"Jean Luc",
+                "and",
+                "userName",
+                "co",
+                "\"a\"b\\",
+            ]
+        );
+        assert_eq!(tokens("  ").unwrap(), Vec::<String>::new());
+        assert!(tokens(r#"userName eq "open"#).is_err());
+        assert!(tokens(r#"userName eq "a\"#).is_err());
+        assert!(tokens(r#"userName eq "\x""#).is_err());
+    }
+
+    #[test]
+    fn test_scim_filter() {
+        let id = Uuid::new_v4();
+        assert_eq!(
+            ScimFilter::from_str(
+                format!(
+                    r#"id EQ "{}" AND emails.value sw "jean" and name.familyName eq "O\"Neil""#,
+                    id
+                )
+                .as_str()
+            )
+            .unwrap(),
+            ScimFilter {
+                id: Some(id),
+                filters: vec![
+                    Filter::new("email", MatchMode::Prefix, "jean"),
+                    Filter::new("last_name", MatchMode::Exact, "O\"Neil"),
+                ],
+            }
+        );
+        assert_eq!(
+            ScimFilter::from_str(r#"urn:ietf:params:scim:schemas:core:2.0:User:userName co "and""#)
+                .unwrap()
+                .filters,
+            vec![Filter::new("email", MatchMode::Contains, "and")]
+        );
+
+        for filter in [
+            // only `and` is supported
+            r#"userName eq "a" or userName eq "b""#,
+            r#"not (userName eq "a")"#,
+            r#"(userName eq "a")"#,
+            r#"userName pr"#,
+            r#"userName ne "a""#,
+            // values are quoted
+            r#"userName eq a"#,
+            r#"userName eq "a" and"#,
+            // dates and unknown attributes are not filtered
+            r#"meta.created eq "2024-01-01T00:00:00Z""#,
+            r#"title eq "a""#,
+        ] {
+            let error = ScimFilter::from_str(filter).unwrap_err();
+            assert_eq!(error.scim_type, Some("invalidFilter"), "{}", filter);
+        }
+
+        // `id` is only compared once with `eq` to an identifier
+        for filter in [
+            format!(r#"id co "{}""#, id),
+            format!(r#"id sw "{}""#, id),
+            format!(r#"id eq "{}" and id eq "{}""#, id, id),
+        ] {
+            let error = ScimFilter::from_str(filter.as_str()).unwrap_err();
+            assert_eq!(error.scim_type, Some("invalidFilter"), "{}", filter);
+        }
+        let error = ScimFilter::from_str(r#"id eq "42""#).unwrap_err();
+        assert_eq!(error.scim_type, Some("invalidValue"));
+    }
+}
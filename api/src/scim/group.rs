@@ -0,0 +1,909 @@
+use crate::audit::request_id;
+use crate::auth::Claims;
+use crate::error::AsHttpError;
+use crate::group::{audited, Group};
+use crate::scim::filter::{Resource, ScimFilter};
+use crate::scim::user::{ScimMeta, ScimOperation, ScimPatch};
+use crate::scim::{
+    authorize, invalid_value, location, string, QueryScim, ScimError, ScimSearch, GROUP_SCHEMA,
+    LIST_RESPONSE_SCHEMA, PATCH_OP_SCHEMA, SCIM_JSON,
+};
+use crate::user::{entity_tag, precondition, User};
+use crate::{AppState, Management, Membership};
+use actix_web::http::header::LOCATION;
+use actix_web::web::{Data, Json, Path, Query};
+use actix_web::{delete, get, patch, post, put, HttpRequest, HttpResponse, HttpResponseBuilder};
+use common::error::CoreError;
+use common::group::groupable::Groupable;
+use common::management::{Filter, MatchMode, Precondition};
+use sea_orm::{ConnectionTrait, DatabaseTransaction, TransactionTrait};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use services_local::authorization::Authorized;
+use services_local::group::GroupManagement;
+use services_local::user::UserManagement;
+use std::collections::BTreeSet;
+use std::str::FromStr;
+use std::sync::Arc;
+use utoipa::ToSchema;
+use uuid::Uuid;
+use validator::Validate;
+
+#[derive(Serialize, Deserialize, ToSchema, Clone)]
+pub(super) struct ScimMember {
+    /// Id of the user
+    pub value: Uuid,
+    /// First and last names of the user
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schema(read_only)]
+    pub display: Option<String>,
+    #[serde(rename = "$ref", default, skip_serializing_if = "Option::is_none")]
+    #[schema(read_only)]
+    pub reference: Option<String>,
+}
+
+/// Group in the SCIM core schema, `displayName` being the name of the group.
+/// The description has no counterpart, the changes made through SCIM keep it.
+#[derive(Serialize, Deserialize, ToSchema, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(super) struct ScimGroup {
+    #[serde(default)]
+    pub schemas: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schema(read_only)]
+    pub id: Option<Uuid>,
+    #[serde(default)]
+    pub display_name: String,
+    #[serde(default)]
+    pub members: Vec<ScimMember>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schema(read_only)]
+    pub meta: Option<ScimMeta>,
+}
+
+impl ScimGroup {
+    fn new(group: Box<dyn Groupable>, members: Vec<User>, request: &HttpRequest) -> Self {
+        let last_modified = group.get_last_modified();
+        let group = Group::from(group);
+        Self {
+            schemas: vec![String::from(GROUP_SCHEMA)],
+            id: Some(group.id),
+            display_name: group.name,
+            members: members
+                .into_iter()
+                .map(|user| ScimMember {
+                    value: user.id,
+                    display: Some(format!("{} {}", user.first_name, user.last_name)),
+                    reference: Some(location(request, format!("/Users/{}", user.id).as_str())),
+                })
+                .collect(),
+            meta: Some(ScimMeta {
+                resource_type: String::from("Group"),
+                location: location(request, format!("/Groups/{}", group.id).as_str()),
+                version: entity_tag(group.version).0.to_string(),
+                last_modified,
+            }),
+        }
+    }
+
+    /// Ids of the members, each one once
+    fn member_ids(&self) -> BTreeSet<Uuid> {
+        self.members.iter().map(|member| member.value).collect()
+    }
+
+    /// Apply an `add`, a `replace` or a `remove` operation, only the members can be removed
+    fn apply(&mut self, operation: &ScimOperation) -> Result<(), ScimError> {
+        let op = operation.op.to_lowercase();
+        let path = operation.path.as_deref().map(str::to_lowercase);
+        let path = path.as_deref().map(|path| {
+            path.strip_prefix("urn:ietf:params:scim:schemas:core:2.0:group:")
+                .unwrap_or(path)
+        });
+        match (op.as_str(), path) {
+            ("add" | "replace", Some(path)) => self.set(op.as_str(), path, &operation.value),
+            ("add" | "replace", None) => {
+                let Value::Object(attributes) = &operation.value else {
+                    return Err(ScimError::new(
+                        CoreError::DataError(String::from(
+                            "An operation without path must have an object value",
+                        )),
+                        "invalidSyntax",
+                    ));
+                };
+                attributes
+                    .iter()
+                    .try_for_each(|(path, value)| self.set(op.as_str(), path, value))
+            }
+            // Without value every member is removed, otherwise the members listed.
+            ("remove", Some("members")) => {
+                let removed = match &operation.value {
+                    Value::Null => self.member_ids(),
+                    value => members(value)?.iter().map(|member| member.value).collect(),
+                };
+                self.members
+                    .retain(|member| !removed.contains(&member.value));
+                Ok(())
+            }
+            ("remove", Some(path)) if path.starts_with("members[") => {
+                let removed = selected_member(path)?;
+                self.members.retain(|member| member.value != removed);
+                Ok(())
+            }
+            ("remove", _) => Err(ScimError::new(
+                CoreError::DataError(String::from("Only the members of a group can be removed")),
+                "mutability",
+            )),
+            (other, _) => Err(ScimError::new(
+                CoreError::DataError(format!(
+                    "{} is not an operation. Use either add, replace or remove.",
+                    other
+                )),
+                "invalidSyntax",
+            )),
+        }
+    }
+
+    fn set(&mut self, op: &str, path: &str, value: &Value) -> Result<(), ScimError> {
+        match path.to_lowercase().as_str() {
+            "displayname" => self.display_name = string(path, value)?,
+            "members" if op == "replace" => self.members = members(value)?,
+            "members" => self.members.extend(members(value)?),
+            "externalid" => {}
+            _ => {
+                return Err(ScimError::new(
+                    CoreError::DataError(format!("{} is not an attribute of a group", path)),
+                    "invalidPath",
+                ))
+            }
+        }
+        Ok(())
+    }
+}
+
+fn members(value: &Value) -> Result<Vec<ScimMember>, ScimError> {
+    serde_json::from_value(value.to_owned()).map_err(|e| invalid_value(e.to_string()))
+}
+
+/// Member selected by a `members[value eq "{id}"]` path
+fn selected_member(path: &str) -> Result<Uuid, ScimError> {
+    let filter = path
+        .strip_prefix("members[")
+        .and_then(|filter| filter.strip_suffix(']'))
+        .unwrap_or_default();
+    match filter.split_whitespace().collect::<Vec<_>>()[..] {
+        ["value", "eq", id] => {
+            Uuid::parse_str(id.trim_matches('"')).map_err(|e| invalid_value(e.to_string()))
+        }
+        _ => Err(ScimError::new(
+            CoreError::DataError(format!(
+                "{} does not select a member with value eq \"{{id}}\"",
+                path
+            )),
+            "invalidFilter",
+        )),
+    }
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub(super) struct ScimGroupList {
+    pub schemas: Vec<String>,
+    pub total_results: usize,
+    pub start_index: usize,
+    pub items_per_page: usize,
+    #[serde(rename = "Resources")]
+    pub resources: Vec<ScimGroup>,
+}
+
+/// Users of the group `group_id` in the organization `tenant`, read by pages of `per_page` users
+async fn read_members<C: ConnectionTrait>(
+    tenant: Uuid,
+    group_id: Uuid,
+    per_page: usize,
+    backend: &C,
+) -> Result<Vec<User>, ScimError> {
+    let management: Management<_, _, _, _, _, _> =
+        Management(Box::new(UserManagement::new(tenant)));
+    let mut members = Vec::new();
+    let mut page = 1;
+    loop {
+        let search_result = management
+            .0
+            .read(
+                Box::new(ScimSearch {
+                    filter: ScimFilter {
+                        id: None,
+                        filters: vec![Filter::new(
+                            "group",
+                            MatchMode::Exact,
+                            group_id.to_string().as_str(),
+                        )],
+                    },
+                    sort: Vec::new(),
+                    page,
+                    per_page,
+                    include_deleted: false,
+                }),
+                backend,
+            )
+            .await
+            .map_err(|e| ScimError::from(e.get_core_error()))?;
+        members.extend(search_result.get_result().map(User::from));
+        if page >= search_result.get_num_pages().unwrap_or_default() {
+            return Ok(members);
+        }
+        page += 1;
+    }
+}
+
+/// Answer `group` with its `members` in the SCIM schema, with its version as `ETag`
+fn respond(
+    mut builder: HttpResponseBuilder,
+    group: Box<dyn Groupable>,
+    members: Vec<User>,
+    request: &HttpRequest,
+) -> HttpResponse {
+    let version = group.get_version();
+    builder
+        .content_type(SCIM_JSON)
+        .insert_header(entity_tag(version))
+        .json(ScimGroup::new(group, members, request))
+}
+
+/// Give the group `group_id` of the organization `tenant` the members `target` instead of `current`.
+/// A user that is not a member of the organization is an invalid value.
+async fn save_members(
+    actor: &str,
+    tenant: Uuid,
+    group_id: Uuid,
+    current: BTreeSet<Uuid>,
+    target: BTreeSet<Uuid>,
+    transaction: &DatabaseTransaction,
+) -> Result<(), ScimError> {
+    if current == target {
+        return Ok(());
+    }
+    authorize(actor, tenant, "group:update", transaction).await?;
+    let membership: Membership<_, _> = Membership(Box::new(GroupManagement::new(tenant)));
+    for user_id in target.difference(&current) {
+        membership
+            .0
+            .add_member(Box::new(group_id), Box::new(*user_id), transaction)
+            .await
+            .map_err(|e| match e.get_core_error() {
+                CoreError::ResourceNotFound(_) => {
+                    invalid_value(format!("User {} not found", user_id))
+                }
+                error => ScimError::from(error),
+            })?;
+    }
+    for user_id in current.difference(&target) {
+        membership
+            .0
+            .remove_member(Box::new(group_id), Box::new(*user_id), transaction)
+            .await
+            .map_err(|e| ScimError::from(e.get_core_error()))?;
+    }
+    Ok(())
+}
+
+/// Change the group `group_id` to the state `change` computes from its current one, its members included
+async fn change<F>(
+    request: &HttpRequest,
+    group_id: Uuid,
+    claims: Claims,
+    app_data: &AppState,
+    change: F,
+) -> Result<HttpResponse, ScimError>
+where
+    F: FnOnce(ScimGroup) -> Result<ScimGroup, ScimError>,
+{
+    let transaction = app_data
+        .db_connection
+        .begin()
+        .await
+        .map_err(|e| ScimError::from(CoreError::UnkownError(e.to_string())))?;
+    let per_page = app_data.settings.pagination.max_page_size;
+    let management: Management<_, _, _, _, _, _> = Management(Box::new(audited(
+        claims.sub.to_owned(),
+        claims.tenant,
+        request_id(request),
+    )));
+    let current = management
+        .0
+        .find(Box::new(group_id), false, &transaction)
+        .await
+        .map(Group::from)
+        .map_err(|e| ScimError::from(e.get_core_error()))?;
+    if !precondition(request).is_met(current.version) {
+        return Err(ScimError::from(CoreError::PreconditionFailed(
+            String::from("Group changed since the version given by If-Match"),
+        )));
+    }
+    let members = read_members(claims.tenant, group_id, per_page, &transaction).await?;
+    let current_group = ScimGroup::new(Box::new(current.clone()), members, request);
+    let current_members = current_group.member_ids();
+    let group = change(current_group)?;
+    if group.display_name != current.name {
+        let changed = Group {
+            name: group.display_name.to_owned(),
+            ..current
+        };
+        changed.validate().map_err(AsHttpError::from)?;
+        management
+            .0
+            .update(
+                Box::new(group_id),
+                Box::new(changed),
+                Precondition::Any,
+                &transaction,
+            )
+            .await
+            .map_err(|e| ScimError::from(e.get_core_error()))?;
+    }
+    save_members(
+        &claims.sub,
+        claims.tenant,
+        group_id,
+        current_members,
+        group.member_ids(),
+        &transaction,
+    )
+    .await?;
+    let response = management
+        .0
+        .find(Box::new(group_id), false, &transaction)
+        .await
+        .map_err(|e| ScimError::from(e.get_core_error()))?;
+    let members = read_members(claims.tenant, group_id, per_page, &transaction).await?;
+    drop(management);
+    transaction
+        .commit()
+        .await
+        .map_err(|e| ScimError::from(CoreError::UnkownError(e.to_string())))?;
+    Ok(respond(HttpResponse::Ok(), response, members, request))
+}
+
+#[utoipa::path(
+tag = "SCIM",
+context_path = "/scim/v2",
+params(
+("filter" = Option<String>, Query, description = "`and` of `eq`, `co` or `sw` comparisons on `id` or `displayName`, e.g. `displayName eq \"Sales\"`"),
+("startIndex" = Option<usize>, Query, description = "Position of the first group, from 1"),
+("count" = Option<usize>, Query, description = "Number of groups, at most `pagination.max_page_size`"),
+("sortBy" = Option<String>, Query, description = "Attribute to sort on, e.g. `displayName` or `meta.lastModified`"),
+("sortOrder" = Option<String>, Query, description = "`ascending` (default) or `descending`")
+),
+responses((status=200, description = "Groups list succeed, with their members", body = ScimGroupList, content_type = "application/scim+json"),
+(status=400, description = "Filter or paging not correct"),
+(status=401, description = "Authentication required"),
+(status=403, description = "Permission group:read required")
+),
+security(("api_jwt_token" = []))
+)]
+#[get("/Groups")]
+pub(super) async fn read_groups(
+    request: HttpRequest,
+    query: Query<QueryScim>,
+    claims: Claims,
+    app_data: Data<Arc<AppState>>,
+) -> Result<HttpResponse, ScimError> {
+    let filter = query.filter(Resource::Group)?;
+    let sort = query.sort(Resource::Group)?;
+    let window = query.window(&app_data.settings.pagination);
+    let management: Management<_, _, _, _, _, _> = Management(Box::new(Authorized::new(
+        Box::new(claims.sub.to_owned()),
+        claims.tenant,
+        "group",
+        GroupManagement::new(claims.tenant),
+    )));
+    let mut total_results = 0;
+    let mut groups = Vec::new();
+    for page in window.pages {
+        let search_result = management
+            .0
+            .read(
+                Box::new(ScimSearch {
+                    filter: ScimFilter {
+                        id: filter.id,
+                        filters: filter.filters.to_owned(),
+                    },
+                    sort: sort.to_owned(),
+                    page,
+                    per_page: window.per_page,
+                    include_deleted: false,
+                }),
+                &app_data.db_connection,
+            )
+            .await
+            .map_err(|e| ScimError::from(e.get_core_error()))?;
+        total_results = search_result.get_total_items().unwrap_or_default();
+        groups.extend(search_result.get_result());
+    }
+    let mut resources = Vec::new();
+    for group in groups.into_iter().skip(window.skip).take(window.count) {
+        let group_id = Uuid::from_str(group.get_id().to_string().as_str())
+            .map_err(|e| ScimError::from(CoreError::UnkownError(e.to_string())))?;
+        let members = read_members(
+            claims.tenant,
+            group_id,
+            app_data.settings.pagination.max_page_size,
+            &app_data.db_connection,
+        )
+        .await?;
+        resources.push(ScimGroup::new(group, members, &request));
+    }
+    Ok(HttpResponse::Ok()
+        .content_type(SCIM_JSON)
+        .json(ScimGroupList {
+            schemas: vec![String::from(LIST_RESPONSE_SCHEMA)],
+            total_results,
+            start_index: window.start_index,
+            items_per_page: resources.len(),
+            resources,
+        }))
+}
+
+#[utoipa::path(
+tag = "SCIM",
+context_path = "/scim/v2",
+params(
+("group_id" = Uuid, Path, description = "Group identifier")
+),
+responses((status=200, description = "Group read succeed, with its members", body = ScimGroup, content_type = "application/scim+json",
+headers(("ETag" = String, description = "Version of the group"))),
+(status=401, description = "Authentication required"),
+(status=403, description = "Permission group:read required"),
+(status=404, description = "Group not found")
+),
+security(("api_jwt_token" = []))
+)]
+#[get("/Groups/{group_id}")]
+pub(super) async fn find_group(
+    request: HttpRequest,
+    group_id: Path<Uuid>,
+    claims: Claims,
+    app_data: Data<Arc<AppState>>,
+) -> Result<HttpResponse, ScimError> {
+    let group_id = group_id.into_inner();
+    let management: Management<_, _, _, _, _, _> = Management(Box::new(Authorized::new(
+        Box::new(claims.sub),
+        claims.tenant,
+        "group",
+        GroupManagement::new(claims.tenant),
+    )));
+    let response = management
+        .0
+        .find(Box::new(group_id), false, &app_data.db_connection)
+        .await
+        .map_err(|e| ScimError::from(e.get_core_error()))?;
+    let members = read_members(
+        claims.tenant,
+        group_id,
+        app_data.settings.pagination.max_page_size,
+        &app_data.db_connection,
+    )
+    .await?;
+    Ok(respond(HttpResponse::Ok(), response, members, &request))
+}
+
+#[utoipa::path(
+tag = "SCIM",
+context_path = "/scim/v2",
+request_body(content = ScimGroup, content_type = "application/scim+json"),
+responses((status=201, description = "Group creation succeed", body = ScimGroup, content_type = "application/scim+json",
+headers(("ETag" = String, description = "Version of the group"), ("Location" = String, description = "URL of the group"))),
+(status=400, description = "Data sent not correct, e.g. a member that is not a user"),
+(status=401, description = "Authentication required"),
+(status=403, description = "Permission group:create required, and group:update to add members"),
+(status=409, description = "displayName already used"),
+(status=422, description = "Attributes not valid")
+),
+security(("api_jwt_token" = []))
+)]
+#[post("/Groups")]
+pub(super) async fn create_group(
+    request: HttpRequest,
+    group: Json<ScimGroup>,
+    claims: Claims,
+    app_data: Data<Arc<AppState>>,
+) -> Result<HttpResponse, ScimError> {
+    let group = group.into_inner();
+    let created = Group {
+        name: group.display_name.to_owned(),
+        ..Group::default()
+    };
+    created.validate().map_err(AsHttpError::from)?;
+    let transaction = app_data
+        .db_connection
+        .begin()
+        .await
+        .map_err(|e| ScimError::from(CoreError::UnkownError(e.to_string())))?;
+    let management: Management<_, _, _, _, _, _> = Management(Box::new(audited(
+        claims.sub.to_owned(),
+        claims.tenant,
+        request_id(&request),
+    )));
+    let response = management
+        .0
+        .create(Box::new(created), &transaction)
+        .await
+        .map_err(|e| ScimError::from(e.get_core_error()))?;
+    let group_id = Group::from(
+        management
+            .0
+            .find(response.get_id(), false, &transaction)
+            .await
+            .map_err(|e| ScimError::from(e.get_core_error()))?,
+    )
+    .id;
+    save_members(
+        &claims.sub,
+        claims.tenant,
+        group_id,
+        BTreeSet::new(),
+        group.member_ids(),
+        &transaction,
+    )
+    .await?;
+    let members = read_members(
+        claims.tenant,
+        group_id,
+        app_data.settings.pagination.max_page_size,
+        &transaction,
+    )
+    .await?;
+    drop(management);
+    transaction
+        .commit()
+        .await
+        .map_err(|e| ScimError::from(CoreError::UnkownError(e.to_string())))?;
+    let mut builder = HttpResponse::Created();
+    builder.insert_header((
+        LOCATION,
+        location(&request, format!("/Groups/{}", group_id).as_str()),
+    ));
+    Ok(respond(builder, response, members, &request))
+}
+
+#[utoipa::path(
+tag = "SCIM",
+context_path = "/scim/v2",
+params(
+("group_id" = Uuid, Path, description = "Group identifier"),
+("If-Match" = Option<String>, Header, description = "ETag of the version to replace")
+),
+request_body(content = ScimGroup, content_type = "application/scim+json"),
+responses((status=200, description = "Group replace succeed, the members not listed are removed", body = ScimGroup, content_type = "application/scim+json",
+headers(("ETag" = String, description = "Version of the group"))),
+(status=400, description = "Data sent not correct, e.g. a member that is not a user"),
+(status=401, description = "Authentication required"),
+(status=403, description = "Permission group:update required"),
+(status=404, description = "Group not found"),
+(status=409, description = "displayName already used"),
+(status=412, description = "Group changed since the version given by If-Match"),
+(status=422, description = "Attributes not valid")
+),
+security(("api_jwt_token" = []))
+)]
+#[put("/Groups/{group_id}")]
+pub(super) async fn replace_group(
+    request: HttpRequest,
+    group_id: Path<Uuid>,
+    group: Json<ScimGroup>,
+    claims: Claims,
+    app_data: Data<Arc<AppState>>,
+) -> Result<HttpResponse, ScimError> {
+    let group = group.into_inner();
+    change(&request, group_id.into_inner(), claims, &app_data, |_| {
+        Ok(group)
+    })
+    .await
+}
+
+#[utoipa::path(
+tag = "SCIM",
+context_path = "/scim/v2",
+params(
+("group_id" = Uuid, Path, description = "Group identifier"),
+("If-Match" = Option<String>, Header, description = "ETag of the version to change")
+),
+request_body(content = ScimPatch, content_type = "application/scim+json"),
+responses((status=200, description = "Group patch succeed", body = ScimGroup, content_type = "application/scim+json",
+headers(("ETag" = String, description = "Version of the group"))),
+(status=400, description = "Operations not correct, e.g. a remove of the displayName or an unknown path"),
+(status=401, description = "Authentication required"),
+(status=403, description = "Permission group:update required"),
+(status=404, description = "Group not found"),
+(status=409, description = "displayName already used"),
+(status=412, description = "Group changed since the version given by If-Match"),
+(status=422, description = "Attributes not valid")
+),
+security(("api_jwt_token" = []))
+)]
+#[patch("/Groups/{group_id}")]
+pub(super) async fn patch_group(
+    request: HttpRequest,
+    group_id: Path<Uuid>,
+    operations: Json<ScimPatch>,
+    claims: Claims,
+    app_data: Data<Arc<AppState>>,
+) -> Result<HttpResponse, ScimError> {
+    let operations = operations.into_inner();
+    if !operations
+        .schemas
+        .iter()
+        .any(|schema| schema == PATCH_OP_SCHEMA)
+    {
+        return Err(ScimError::new(
+            CoreError::DataError(format!("schemas must contain {}", PATCH_OP_SCHEMA)),
+            "invalidSyntax",
+        ));
+    }
+    change(
+        &request,
+        group_id.into_inner(),
+        claims,
+        &app_data,
+        |mut group| {
+            for operation in &operations.operations {
+                group.apply(operation)?;
+            }
+            Ok(group)
+        },
+    )
+    .await
+}
+
+#[utoipa::path(
+tag = "SCIM",
+context_path = "/scim/v2",
+params(
+("group_id" = Uuid, Path, description = "Group identifier")
+),
+responses((status=204, description = "Group removed for good, the users it held are kept"),
+(status=401, description = "Authentication required"),
+(status=403, description = "Permissions group:delete and group:purge required"),
+(status=404, description = "Group not found")
+),
+security(("api_jwt_token" = []))
+)]
+#[delete("/Groups/{group_id}")]
+pub(super) async fn delete_group(
+    request: HttpRequest,
+    group_id: Path<Uuid>,
+    claims: Claims,
+    app_data: Data<Arc<AppState>>,
+) -> Result<HttpResponse, ScimError> {
+    let group_id = group_id.into_inner();
+    let transaction = app_data
+        .db_connection
+        .begin()
+        .await
+        .map_err(|e| ScimError::from(CoreError::UnkownError(e.to_string())))?;
+    {
+        let management: Management<_, _, _, _, _, _> = Management(Box::new(audited(
+            claims.sub,
+            claims.tenant,
+            request_id(&request),
+        )));
+        let current = management
+            .0
+            .find(Box::new(group_id), true, &transaction)
+            .await
+            .map_err(|e| ScimError::from(e.get_core_error()))?;
+        // Only deleted groups are purged, an active one is deleted first.
+        if current.get_deleted_at().is_none() {
+            management
+                .0
+                .delete(Box::new(group_id), precondition(&request), &transaction)
+                .await
+                .map_err(|e| ScimError::from(e.get_core_error()))?;
+        }
+        management
+            .0
+            .purge(Box::new(group_id), &transaction)
+            .await
+            .map_err(|e| ScimError::from(e.get_core_error()))?;
+    }
+    transaction
+        .commit()
+        .await
+        .map_err(|e| ScimError::from(CoreError::UnkownError(e.to_string())))?;
+    Ok(HttpResponse::NoContent().finish())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{access_token, administrator_id, app_state, test_settings};
+    use actix_web::http::header::{AUTHORIZATION, CONTENT_TYPE, ETAG, IF_MATCH, LOCATION};
+    use actix_web::http::StatusCode;
+    use actix_web::web::Data;
+    use actix_web::{test, web, App};
+    use serde_json::{json, Value};
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn test_scim_group() {
+        let state = app_state(test_settings()).await;
+        let authorization = format!(
+            "Bearer {}",
+            access_token(&state.settings, &administrator_id(&state).await, 60)
+        );
+
+        let app = test::init_service(
+            App::new()
+                .app_data(Data::new(Arc::clone(&state)))
+                .service(web::scope("/scim/v2").configure(crate::scim::init)),
+        )
+        .await;
+
+        let mut users = Vec::new();
+        for given_name in ["Dan", "Eve", "Fay"] {
+            let req = test::TestRequest::post()
+                .uri("/scim/v2/Users")
+                .insert_header((AUTHORIZATION, authorization.as_str()))
+                .set_json(json!({
+                    "userName": format!("{}@scim-group.example.com", given_name.to_lowercase()),
+                    "name": {"givenName": given_name, "familyName": "Group"}
+                }))
+                .to_request();
+            let user: Value = test::call_and_read_body_json(&app, req).await;
+            users.push(String::from(user["id"].as_str().unwrap()));
+        }
+
+        // test 201, the group is created with its members
+        let req = test::TestRequest::post()
+            .uri("/scim/v2/Groups")
+            .insert_header((AUTHORIZATION, authorization.as_str()))
+            .insert_header((CONTENT_TYPE, "application/scim+json"))
+            .set_payload(
+                json!({
+                    "schemas": ["urn:ietf:params:scim:schemas:core:2.0:Group"],
+                    "displayName": "Scim readers",
+                    "members": [{"value": users[0]}, {"value": users[1]}]
+                })
+                .to_string(),
+            )
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::CREATED);
+        assert_eq!(resp.headers().get(ETAG).unwrap(), "\"1\"");
+        let location = resp.headers().get(LOCATION).unwrap().to_owned();
+        let group: Value = test::read_body_json(resp).await;
+        let group_id = String::from(group["id"].as_str().unwrap());
+        assert_eq!(group["displayName"], "Scim readers");
+        assert_eq!(group["members"].as_array().unwrap().len(), 2);
+        assert_eq!(group["meta"]["resourceType"], "Group");
+        assert_eq!(group["meta"]["location"], location.to_str().unwrap());
+
+        // test 400, a member must be a user
+        {
+            let req = test::TestRequest::post()
+                .uri("/scim/v2/Groups")
+                .insert_header((AUTHORIZATION, authorization.as_str()))
+                .set_json(json!({
+                    "displayName": "Scim nobody",
+                    "members": [{"value": "00000000-0000-0000-0000-000000000000"}]
+                }))
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+            let error: Value = test::read_body_json(resp).await;
+            assert_eq!(error["scimType"], "invalidValue");
+        }
+
+        // test 200, the group is filtered by its displayName
+        {
+            let req = test::TestRequest::get()
+                .uri("/scim/v2/Groups?filter=displayName%20eq%20%22scim%20READERS%22")
+                .insert_header((AUTHORIZATION, authorization.as_str()))
+                .to_request();
+            let groups: Value = test::call_and_read_body_json(&app, req).await;
+            assert_eq!(groups["totalResults"], 1);
+            assert_eq!(groups["Resources"][0]["id"], group_id.as_str());
+            assert_eq!(
+                groups["Resources"][0]["members"].as_array().unwrap().len(),
+                2
+            );
+        }
+
+        // test 200, PATCH adds a member and removes another one
+        {
+            let req = test::TestRequest::patch()
+                .uri(format!("/scim/v2/Groups/{}", group_id).as_str())
+                .insert_header((AUTHORIZATION, authorization.as_str()))
+                .set_json(json!({
+                    "schemas": ["urn:ietf:params:scim:api:messages:2.0:PatchOp"],
+                    "Operations": [
+                        {"op": "add", "path": "members", "value": [{"value": users[2]}]},
+                        {"op": "remove", "path": format!("members[value eq \"{}\"]", users[0])}
+                    ]
+                }))
+                .to_request();
+            let group: Value = test::call_and_read_body_json(&app, req).await;
+            let mut members: Vec<&str> = group["members"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|member| member["value"].as_str().unwrap())
+                .collect();
+            members.sort();
+            let mut expected = vec![users[1].as_str(), users[2].as_str()];
+            expected.sort();
+            assert_eq!(members, expected);
+        }
+
+        // test 400, the displayName cannot be removed
+        {
+            let req = test::TestRequest::patch()
+                .uri(format!("/scim/v2/Groups/{}", group_id).as_str())
+                .insert_header((AUTHORIZATION, authorization.as_str()))
+                .set_json(json!({
+                    "schemas": ["urn:ietf:params:scim:api:messages:2.0:PatchOp"],
+                    "Operations": [{"op": "remove", "path": "displayName"}]
+                }))
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+            let error: Value = test::read_body_json(resp).await;
+            assert_eq!(error["scimType"], "mutability");
+        }
+
+        // test 200, PUT renames the group and replaces its members
+        {
+            let req = test::TestRequest::put()
+                .uri(format!("/scim/v2/Groups/{}", group_id).as_str())
+                .insert_header((AUTHORIZATION, authorization.as_str()))
+                .insert_header((IF_MATCH, "\"1\""))
+                .set_json(json!({
+                    "displayName": "Scim writers",
+                    "members": [{"value": users[0]}]
+                }))
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::OK);
+            assert_eq!(resp.headers().get(ETAG).unwrap(), "\"2\"");
+            let group: Value = test::read_body_json(resp).await;
+            assert_eq!(group["displayName"], "Scim writers");
+            assert_eq!(group["members"], json!([group["members"][0]]));
+            assert_eq!(group["members"][0]["value"], users[0].as_str());
+        }
+
+        // test 412, the group changed since the version given
+        {
+            let req = test::TestRequest::put()
+                .uri(format!("/scim/v2/Groups/{}", group_id).as_str())
+                .insert_header((AUTHORIZATION, authorization.as_str()))
+                .insert_header((IF_MATCH, "\"1\""))
+                .set_json(json!({"displayName": "Scim late"}))
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::PRECONDITION_FAILED);
+        }
+
+        // test 204, the group is removed and its users are kept
+        {
+            let req = test::TestRequest::delete()
+                .uri(format!("/scim/v2/Groups/{}", group_id).as_str())
+                .insert_header((AUTHORIZATION, authorization.as_str()))
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::NO_CONTENT);
+
+            let req = test::TestRequest::get()
+                .uri(format!("/scim/v2/Groups/{}", group_id).as_str())
+                .insert_header((AUTHORIZATION, authorization.as_str()))
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+
+            let req = test::TestRequest::get()
+                .uri(format!("/scim/v2/Users/{}", users[0]).as_str())
+                .insert_header((AUTHORIZATION, authorization.as_str()))
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::OK);
+        }
+    }
+}
@@ -0,0 +1,296 @@
+use crate::error::AsHttpError;
+use crate::scim::discovery::{
+    resource_type, resource_types, schema, schemas, service_provider_config,
+};
+use crate::scim::filter::{sort, Resource, ScimFilter};
+use crate::scim::group::{
+    create_group, delete_group, find_group, patch_group, read_groups, replace_group,
+};
+use crate::scim::user::{
+    create_user, delete_user, find_user, patch_user, read_users, replace_user,
+};
+use crate::swagger::SecurityAddon;
+use crate::Authorization;
+use actix_web::body::BoxBody;
+use actix_web::http::StatusCode;
+use actix_web::web::{scope, JsonConfig, PathConfig, QueryConfig, ServiceConfig};
+use actix_web::{HttpRequest, HttpResponse, ResponseError};
+use common::error::CoreError;
+use common::management::{Cursor, DateRange, Filter, MatchMode, Pagination, Searchable, Sort};
+use sea_orm::ConnectionTrait;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use service_config::PaginationSettings;
+use services_local::authorization::RoleManagement;
+use std::fmt::Display;
+use std::ops::RangeInclusive;
+use thiserror::Error;
+use utoipa::OpenApi;
+use uuid::Uuid;
+
+mod discovery;
+mod filter;
+mod group;
+mod user;
+
+/// Media type of the SCIM requests and responses
+const SCIM_JSON: &str = "application/scim+json";
+
+const ERROR_SCHEMA: &str = "urn:ietf:params:scim:api:messages:2.0:Error";
+const LIST_RESPONSE_SCHEMA: &str = "urn:ietf:params:scim:api:messages:2.0:ListResponse";
+const PATCH_OP_SCHEMA: &str = "urn:ietf:params:scim:api:messages:2.0:PatchOp";
+const USER_SCHEMA: &str = "urn:ietf:params:scim:schemas:core:2.0:User";
+const GROUP_SCHEMA: &str = "urn:ietf:params:scim:schemas:core:2.0:Group";
+
+pub(crate) fn init(cfg: &mut ServiceConfig) {
+    cfg.service(
+        scope("")
+            .app_data(JsonConfig::default().error_handler(|error, _| {
+                ScimError::from(AsHttpError::from(CoreError::DataError(error.to_string()))).into()
+            }))
+            .app_data(PathConfig::default().error_handler(|error, _| {
+                ScimError::from(AsHttpError::from(CoreError::DataError(error.to_string()))).into()
+            }))
+            .app_data(QueryConfig::default().error_handler(|error, _| {
+                ScimError::from(AsHttpError::from(CoreError::DataError(error.to_string()))).into()
+            }))
+            .service(read_users)
+            .service(find_user)
+            .service(create_user)
+            .service(replace_user)
+            .service(patch_user)
+            .service(delete_user)
+            .service(read_groups)
+            .service(find_group)
+            .service(create_group)
+            .service(replace_group)
+            .service(patch_group)
+            .service(delete_group)
+            .service(service_provider_config)
+            .service(schemas)
+            .service(schema)
+            .service(resource_types)
+            .service(resource_type),
+    );
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct QueryScim {
+    /// e.g. `userName eq "jane@example.com"`
+    filter: Option<String>,
+    start_index: Option<usize>,
+    count: Option<usize>,
+    sort_by: Option<String>,
+    sort_order: Option<String>,
+}
+
+/// Pages read to list `count` resources from `start_index`, numbered from 1.
+/// Pages hold `count` resources, a start index in the middle of one also reads the following one
+/// whose first `skip` resources are left out.
+struct Window {
+    start_index: usize,
+    count: usize,
+    per_page: usize,
+    pages: RangeInclusive<usize>,
+    skip: usize,
+}
+
+impl QueryScim {
+    fn filter(&self, resource: Resource) -> Result<ScimFilter, ScimError> {
+        match &self.filter {
+            Some(filter) => ScimFilter::parse(filter, resource),
+            None => Ok(ScimFilter::default()),
+        }
+    }
+
+    fn sort(&self, resource: Resource) -> Result<Vec<Sort>, ScimError> {
+        match &self.sort_by {
+            Some(sort_by) => Ok(vec![sort(resource, sort_by, self.sort_order.as_deref())?]),
+            None => Ok(Vec::new()),
+        }
+    }
+
+    /// Window of the list, `count` being bounded by `pagination`
+    fn window(&self, pagination: &PaginationSettings) -> Window {
+        let start_index = self.start_index.unwrap_or(1).max(1);
+        let count = self
+            .count
+            .unwrap_or(pagination.default_page_size)
+            .min(pagination.max_page_size);
+        let per_page = count.max(1);
+        let skip = (start_index - 1) % per_page;
+        let first_page = (start_index - 1) / per_page + 1;
+        Window {
+            start_index,
+            count,
+            per_page,
+            pages: first_page..=first_page + usize::from(skip > 0),
+            skip,
+        }
+    }
+}
+
+/// Error rendered as a SCIM error message (RFC 7644 section 3.12)
+#[derive(Error, Debug)]
+#[error("{error}")]
+pub(crate) struct ScimError {
+    error: AsHttpError,
+    /// Detail of a 400 error, e.g. `invalidFilter` or `uniqueness`
+    scim_type: Option<&'static str>,
+}
+
+impl ScimError {
+    fn new(error: CoreError, scim_type: &'static str) -> Self {
+        Self {
+            error: AsHttpError::from(error),
+            scim_type: Some(scim_type),
+        }
+    }
+}
+
+impl From<AsHttpError> for ScimError {
+    fn from(error: AsHttpError) -> Self {
+        let scim_type = match error.code() {
+            "conflict" => Some("uniqueness"),
+            "invalid_data" | "validation_failed" => Some("invalidValue"),
+            _ => None,
+        };
+        Self { error, scim_type }
+    }
+}
+
+impl From<CoreError> for ScimError {
+    fn from(error: CoreError) -> Self {
+        Self::from(AsHttpError::from(error))
+    }
+}
+
+impl ResponseError for ScimError {
+    fn status_code(&self) -> StatusCode {
+        self.error.status_code()
+    }
+
+    fn error_response(&self) -> HttpResponse<BoxBody> {
+        let status = self.status_code();
+        let mut message = json!({
+            "schemas": [ERROR_SCHEMA],
+            "status": status.as_u16().to_string(),
+            "detail": self.error.problem()["detail"],
+        });
+        if let Some(scim_type) = self.scim_type {
+            message["scimType"] = json!(scim_type);
+        }
+        HttpResponse::build(status)
+            .content_type(SCIM_JSON)
+            .json(message)
+    }
+}
+
+/// Absolute URL of `path` under the SCIM endpoint, e.g. `/Users/{id}`
+fn location(request: &HttpRequest, path: &str) -> String {
+    let connection = request.connection_info();
+    format!(
+        "{}://{}/scim/v2{}",
+        connection.scheme(),
+        connection.host(),
+        path
+    )
+}
+
+/// Page of the resources matching a SCIM filter
+struct ScimSearch {
+    filter: ScimFilter,
+    sort: Vec<Sort>,
+    page: usize,
+    per_page: usize,
+    /// Deleted users are listed as inactive, deleted groups are not listed
+    include_deleted: bool,
+}
+
+impl Searchable for ScimSearch {
+    type Id = Box<dyn Display + Sync + Send>;
+
+    fn get_id(&self) -> Option<Self::Id> {
+        self.filter.id.map(|x| Box::new(x) as Self::Id)
+    }
+
+    fn get_pattern(&self) -> Option<Box<dyn Display + Sync + Send>> {
+        None
+    }
+
+    fn get_match_mode(&self) -> Result<MatchMode, CoreError> {
+        Ok(MatchMode::default())
+    }
+
+    fn get_filters(&self) -> Vec<Filter> {
+        self.filter.filters.to_owned()
+    }
+
+    fn get_date_range(&self) -> Result<Option<DateRange>, CoreError> {
+        Ok(None)
+    }
+
+    fn get_sort(&self) -> Result<Vec<Sort>, CoreError> {
+        Ok(self.sort.to_owned())
+    }
+
+    fn get_cursor(&self) -> Result<Option<Cursor>, CoreError> {
+        Ok(None)
+    }
+
+    fn get_pagination(&self) -> Result<Pagination, CoreError> {
+        Pagination::new(
+            Some(self.page),
+            Some(self.per_page),
+            self.per_page,
+            self.per_page,
+        )
+    }
+
+    fn get_include_deleted(&self) -> bool {
+        self.include_deleted
+    }
+}
+
+/// Check the permission of `actor`, e.g. `user:restore` to see the inactive users
+async fn authorize<C: ConnectionTrait>(
+    actor: &str,
+    tenant: Uuid,
+    permission: &str,
+    backend: &C,
+) -> Result<(), ScimError> {
+    let authorization: Authorization<_, _> = Authorization(Box::new(RoleManagement::new(tenant)));
+    authorization
+        .0
+        .authorize(
+            Box::new(actor.to_owned()),
+            String::from(permission),
+            None,
+            backend,
+        )
+        .await
+        .map_err(|e| ScimError::from(e.get_core_error()))
+}
+
+fn invalid_value(message: String) -> ScimError {
+    ScimError::new(CoreError::DataError(message), "invalidValue")
+}
+
+fn string(path: &str, value: &Value) -> Result<String, ScimError> {
+    value
+        .as_str()
+        .map(str::to_owned)
+        .ok_or_else(|| invalid_value(format!("{} must be a string", path)))
+}
+
+pub fn api_docs() -> utoipa::openapi::OpenApi {
+    #[derive(OpenApi)]
+    #[openapi(
+    paths(crate::scim::user::read_users,crate::scim::user::find_user,crate::scim::user::create_user,crate::scim::user::replace_user,crate::scim::user::patch_user,crate::scim::user::delete_user,crate::scim::group::read_groups,crate::scim::group::find_group,crate::scim::group::create_group,crate::scim::group::replace_group,crate::scim::group::patch_group,crate::scim::group::delete_group,crate::scim::discovery::service_provider_config,crate::scim::discovery::schemas,crate::scim::discovery::schema,crate::scim::discovery::resource_types,crate::scim::discovery::resource_type),
+    components(schemas(crate::scim::user::ScimUser,crate::scim::user::ScimName,crate::scim::user::ScimEmail,crate::scim::user::ScimMeta,crate::scim::user::ScimUserList,crate::scim::user::ScimPatch,crate::scim::user::ScimOperation,crate::scim::group::ScimGroup,crate::scim::group::ScimMember,crate::scim::group::ScimGroupList)),
+    modifiers(&SecurityAddon)
+    )]
+    struct ApiDocs;
+    ApiDocs::openapi()
+}
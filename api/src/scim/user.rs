@@ -0,0 +1,959 @@
+use crate::audit::request_id;
+use crate::auth::Claims;
+use crate::error::AsHttpError;
+use crate::scim::filter::{Resource, ScimFilter};
+use crate::scim::{
+    authorize, invalid_value, location, string, QueryScim, ScimError, ScimSearch,
+    LIST_RESPONSE_SCHEMA, PATCH_OP_SCHEMA, SCIM_JSON, USER_SCHEMA,
+};
+use crate::user::{audited, entity_tag, insert, precondition, NewUser, User};
+use crate::{AppState, Authentication, Management};
+use actix_web::http::header::LOCATION;
+use actix_web::web::{Data, Json, Path, Query};
+use actix_web::{delete, get, patch, post, put, HttpRequest, HttpResponse, HttpResponseBuilder};
+use chrono::{DateTime, Utc};
+use common::error::CoreError;
+use common::management::Precondition;
+use common::user::userable::Userable;
+use sea_orm::{DatabaseTransaction, TransactionTrait};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use services_local::authorization::Authorized;
+use services_local::credential::CredentialManagement;
+use services_local::user::UserManagement;
+use std::sync::Arc;
+use utoipa::ToSchema;
+use uuid::Uuid;
+use validator::Validate;
+
+#[derive(Default, Serialize, Deserialize, ToSchema, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(super) struct ScimName {
+    #[serde(default)]
+    pub given_name: String,
+    #[serde(default)]
+    pub family_name: String,
+}
+
+#[derive(Serialize, Deserialize, ToSchema, Clone)]
+pub(super) struct ScimEmail {
+    pub value: String,
+    #[serde(rename = "type", default, skip_serializing_if = "Option::is_none")]
+    pub kind: Option<String>,
+    #[serde(default)]
+    pub primary: bool,
+}
+
+#[derive(Serialize, Deserialize, ToSchema, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(super) struct ScimMeta {
+    pub resource_type: String,
+    pub location: String,
+    /// Same as the `ETag` of the user
+    pub version: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_modified: Option<DateTime<Utc>>,
+}
+
+/// User in the SCIM core schema, `userName` being the email of the user.
+/// The attributes without a counterpart, e.g. `externalId`, are ignored.
+#[derive(Serialize, Deserialize, ToSchema, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(super) struct ScimUser {
+    #[serde(default)]
+    pub schemas: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schema(read_only)]
+    pub id: Option<Uuid>,
+    #[serde(default)]
+    pub user_name: String,
+    #[serde(default)]
+    pub name: ScimName,
+    /// Only read when `userName` is not given, the primary email first
+    #[serde(default)]
+    pub emails: Vec<ScimEmail>,
+    /// An inactive user is deleted, reactivating it restores it
+    #[serde(default = "active")]
+    pub active: bool,
+    #[serde(default, skip_serializing)]
+    #[schema(write_only)]
+    pub password: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schema(read_only)]
+    pub meta: Option<ScimMeta>,
+}
+
+fn active() -> bool {
+    true
+}
+
+impl ScimUser {
+    fn new(user: Box<dyn Userable>, request: &HttpRequest) -> Self {
+        let last_modified = user.get_last_modified();
+        let user = User::from(user);
+        Self {
+            schemas: vec![String::from(USER_SCHEMA)],
+            id: Some(user.id),
+            user_name: user.email.to_owned(),
+            name: ScimName {
+                given_name: user.first_name,
+                family_name: user.last_name,
+            },
+            emails: vec![ScimEmail {
+                value: user.email,
+                kind: Some(String::from("work")),
+                primary: true,
+            }],
+            active: user.deleted_at.is_none(),
+            password: None,
+            meta: Some(ScimMeta {
+                resource_type: String::from("User"),
+                location: location(request, format!("/Users/{}", user.id).as_str()),
+                version: entity_tag(user.version).0.to_string(),
+                last_modified,
+            }),
+        }
+    }
+
+    /// Email of the user, its `userName` or else its primary email
+    fn email(&self) -> String {
+        if !self.user_name.is_empty() {
+            return self.user_name.to_owned();
+        }
+        self.emails
+            .iter()
+            .find(|email| email.primary)
+            .or(self.emails.first())
+            .map(|email| email.value.to_owned())
+            .unwrap_or_default()
+    }
+
+    /// User to create or to update to, checked like the users of the REST api
+    fn new_user(&self) -> Result<NewUser, AsHttpError> {
+        let user = NewUser {
+            first_name: self.name.given_name.to_owned(),
+            last_name: self.name.family_name.to_owned(),
+            email: self.email(),
+            password: self.password.to_owned(),
+        };
+        user.validate().map_err(AsHttpError::from)?;
+        Ok(user)
+    }
+
+    /// Apply an `add` or a `replace` operation, the attributes can not be removed
+    fn apply(&mut self, operation: &ScimOperation) -> Result<(), ScimError> {
+        match operation.op.to_lowercase().as_str() {
+            "add" | "replace" => {}
+            "remove" => {
+                return Err(ScimError::new(
+                    CoreError::DataError(String::from("Attributes of a user can not be removed")),
+                    "mutability",
+                ))
+            }
+            other => {
+                return Err(ScimError::new(
+                    CoreError::DataError(format!(
+                        "{} is not an operation. Use either add, replace or remove.",
+                        other
+                    )),
+                    "invalidSyntax",
+                ))
+            }
+        }
+        match &operation.path {
+            Some(path) => self.set(path, &operation.value),
+            None => {
+                let Value::Object(attributes) = &operation.value else {
+                    return Err(ScimError::new(
+                        CoreError::DataError(String::from(
+                            "An operation without path must have an object value",
+                        )),
+                        "invalidSyntax",
+                    ));
+                };
+                attributes
+                    .iter()
+                    .try_for_each(|(path, value)| self.set(path, value))
+            }
+        }
+    }
+
+    fn set(&mut self, path: &str, value: &Value) -> Result<(), ScimError> {
+        let attribute = path.to_lowercase();
+        let attribute = attribute
+            .strip_prefix("urn:ietf:params:scim:schemas:core:2.0:user:")
+            .unwrap_or(attribute.as_str());
+        match attribute {
+            "username" | "emails.value" => self.user_name = string(path, value)?,
+            "emails" => {
+                let emails: Vec<ScimEmail> = serde_json::from_value(value.to_owned())
+                    .map_err(|e| invalid_value(e.to_string()))?;
+                self.emails = emails;
+                self.user_name = String::new();
+                self.user_name = self.email();
+            }
+            "name" => {
+                let Value::Object(attributes) = value else {
+                    return Err(invalid_value(format!("{} must be an object", path)));
+                };
+                for (name, value) in attributes {
+                    self.set(format!("name.{}", name).as_str(), value)?;
+                }
+            }
+            "name.givenname" => self.name.given_name = string(path, value)?,
+            "name.familyname" => self.name.family_name = string(path, value)?,
+            "active" => {
+                self.active = match value {
+                    Value::Bool(active) => *active,
+                    // Some providers send the boolean as a string.
+                    Value::String(active) if active.eq_ignore_ascii_case("true") => true,
+                    Value::String(active) if active.eq_ignore_ascii_case("false") => false,
+                    _ => return Err(invalid_value(format!("{} must be a boolean", path))),
+                }
+            }
+            "password" => self.password = Some(string(path, value)?),
+            "externalid" | "displayname" | "name.formatted" => {}
+            _ if attribute.starts_with("emails[") && attribute.ends_with("].value") => {
+                self.user_name = string(path, value)?
+            }
+            _ => {
+                return Err(ScimError::new(
+                    CoreError::DataError(format!("{} is not an attribute of a user", path)),
+                    "invalidPath",
+                ))
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub(super) struct ScimUserList {
+    pub schemas: Vec<String>,
+    pub total_results: usize,
+    pub start_index: usize,
+    pub items_per_page: usize,
+    #[serde(rename = "Resources")]
+    pub resources: Vec<ScimUser>,
+}
+
+/// Operations of a SCIM PATCH request (RFC 7644 section 3.5.2)
+#[derive(Serialize, Deserialize, ToSchema)]
+pub(super) struct ScimPatch {
+    #[serde(default)]
+    pub schemas: Vec<String>,
+    #[serde(rename = "Operations")]
+    pub operations: Vec<ScimOperation>,
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub(super) struct ScimOperation {
+    /// `add` or `replace`
+    pub op: String,
+    /// Attribute changed, e.g. `name.givenName` or `active`, the value is an object of attributes when absent
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+    #[serde(default)]
+    #[schema(value_type = Object)]
+    pub value: Value,
+}
+
+/// Answer `user` in the SCIM schema, with its version as `ETag`
+fn respond(
+    mut builder: HttpResponseBuilder,
+    user: Box<dyn Userable>,
+    request: &HttpRequest,
+) -> HttpResponse {
+    let version = user.get_version();
+    builder
+        .content_type(SCIM_JSON)
+        .insert_header(entity_tag(version))
+        .json(ScimUser::new(user, request))
+}
+
+/// Bring the user `user_id` of the organization `tenant` from `current` to the state of `user`.
+/// A user is deleted while inactive, so it is restored for the time of a change.
+async fn save(
+    actor: &str,
+    tenant: Uuid,
+    request: &HttpRequest,
+    user_id: &str,
+    current: User,
+    user: ScimUser,
+    transaction: &DatabaseTransaction,
+) -> Result<Box<dyn Userable>, ScimError> {
+    if !precondition(request).is_met(current.version) {
+        return Err(ScimError::from(CoreError::PreconditionFailed(
+            String::from("User changed since the version given by If-Match"),
+        )));
+    }
+    let mut changed = user.new_user()?;
+    let password = changed.password.take();
+    let deleted = current.deleted_at.is_some();
+    let modified = (&changed.first_name, &changed.last_name, &changed.email)
+        != (&current.first_name, &current.last_name, &current.email);
+    let management: Management<_, _, _, _, _, _> = Management(Box::new(audited(
+        actor.to_owned(),
+        tenant,
+        request_id(request),
+    )));
+    if deleted && (user.active || modified) {
+        management
+            .0
+            .restore(Box::new(user_id.to_owned()), transaction)
+            .await
+            .map_err(|e| ScimError::from(e.get_core_error()))?;
+    }
+    if modified {
+        management
+            .0
+            .update(
+                Box::new(user_id.to_owned()),
+                Box::new(User::from(changed)),
+                Precondition::Any,
+                transaction,
+            )
+            .await
+            .map_err(|e| ScimError::from(e.get_core_error()))?;
+    }
+    if !user.active && (!deleted || modified) {
+        management
+            .0
+            .delete(Box::new(user_id.to_owned()), Precondition::Any, transaction)
+            .await
+            .map_err(|e| ScimError::from(e.get_core_error()))?;
+    }
+    if let Some(password) = password {
+        let authentication: Authentication<_, _, _> =
+            Authentication(Box::new(CredentialManagement::new(tenant)));
+        authentication
+            .0
+            .set_password(Box::new(user_id.to_owned()), password, transaction)
+            .await
+            .map_err(|e| ScimError::from(e.get_core_error()))?;
+    }
+    management
+        .0
+        .find(Box::new(user_id.to_owned()), true, transaction)
+        .await
+        .map_err(|e| ScimError::from(e.get_core_error()))
+}
+
+/// Change the user `user_id` to the state `change` computes from its current one
+async fn change<F>(
+    request: &HttpRequest,
+    user_id: String,
+    claims: Claims,
+    app_data: &AppState,
+    change: F,
+) -> Result<HttpResponse, ScimError>
+where
+    F: FnOnce(ScimUser) -> Result<ScimUser, ScimError>,
+{
+    let transaction = app_data
+        .db_connection
+        .begin()
+        .await
+        .map_err(|e| ScimError::from(CoreError::UnkownError(e.to_string())))?;
+    let current = {
+        let management: Management<_, _, _, _, _, _> = Management(Box::new(audited(
+            claims.sub.to_owned(),
+            claims.tenant,
+            request_id(request),
+        )));
+        management
+            .0
+            .find(Box::new(user_id.to_owned()), true, &transaction)
+            .await
+            .map(User::from)
+            .map_err(|e| ScimError::from(e.get_core_error()))?
+    };
+    let user = change(ScimUser::new(Box::new(current.clone()), request))?;
+    if user.password.is_some() {
+        // A password is only given by an administrator, users change theirs with the current one.
+        authorize(
+            &claims.sub,
+            claims.tenant,
+            "user:update",
+            &app_data.db_connection,
+        )
+        .await?;
+    }
+    let response = save(
+        &claims.sub,
+        claims.tenant,
+        request,
+        &user_id,
+        current,
+        user,
+        &transaction,
+    )
+    .await?;
+    transaction
+        .commit()
+        .await
+        .map_err(|e| ScimError::from(CoreError::UnkownError(e.to_string())))?;
+    Ok(respond(HttpResponse::Ok(), response, request))
+}
+
+#[utoipa::path(
+tag = "SCIM",
+context_path = "/scim/v2",
+params(
+("filter" = Option<String>, Query, description = "`and` of `eq`, `co` or `sw` comparisons on `id`, `userName`, `emails`, `name.givenName` or `name.familyName`, e.g. `userName eq \"jane@example.com\"`"),
+("startIndex" = Option<usize>, Query, description = "Position of the first user, from 1"),
+("count" = Option<usize>, Query, description = "Number of users, at most `pagination.max_page_size`"),
+("sortBy" = Option<String>, Query, description = "Attribute to sort on, e.g. `userName` or `meta.lastModified`"),
+("sortOrder" = Option<String>, Query, description = "`ascending` (default) or `descending`")
+),
+responses((status=200, description = "Users list succeed, the inactive users included", body = ScimUserList, content_type = "application/scim+json"),
+(status=400, description = "Filter or paging not correct"),
+(status=401, description = "Authentication required"),
+(status=403, description = "Permission user:restore required")
+),
+security(("api_jwt_token" = []))
+)]
+#[get("/Users")]
+pub(super) async fn read_users(
+    request: HttpRequest,
+    query: Query<QueryScim>,
+    claims: Claims,
+    app_data: Data<Arc<AppState>>,
+) -> Result<HttpResponse, ScimError> {
+    let filter = query.filter(Resource::User)?;
+    let sort = query.sort(Resource::User)?;
+    authorize(
+        &claims.sub,
+        claims.tenant,
+        "user:restore",
+        &app_data.db_connection,
+    )
+    .await?;
+    let window = query.window(&app_data.settings.pagination);
+    let management: Management<_, _, _, _, _, _> = Management(Box::new(Authorized::new(
+        Box::new(claims.sub.to_owned()),
+        claims.tenant,
+        "user",
+        UserManagement::new(claims.tenant),
+    )));
+    let mut total_results = 0;
+    let mut users = Vec::new();
+    for page in window.pages {
+        let search_result = management
+            .0
+            .read(
+                Box::new(ScimSearch {
+                    filter: ScimFilter {
+                        id: filter.id,
+                        filters: filter.filters.to_owned(),
+                    },
+                    sort: sort.to_owned(),
+                    page,
+                    per_page: window.per_page,
+                    include_deleted: true,
+                }),
+                &app_data.db_connection,
+            )
+            .await
+            .map_err(|e| ScimError::from(e.get_core_error()))?;
+        total_results = search_result.get_total_items().unwrap_or_default();
+        users.extend(search_result.get_result());
+    }
+    let resources: Vec<ScimUser> = users
+        .into_iter()
+        .skip(window.skip)
+        .take(window.count)
+        .map(|user| ScimUser::new(user, &request))
+        .collect();
+    Ok(HttpResponse::Ok()
+        .content_type(SCIM_JSON)
+        .json(ScimUserList {
+            schemas: vec![String::from(LIST_RESPONSE_SCHEMA)],
+            total_results,
+            start_index: window.start_index,
+            items_per_page: resources.len(),
+            resources,
+        }))
+}
+
+#[utoipa::path(
+tag = "SCIM",
+context_path = "/scim/v2",
+params(
+("user_id" = Uuid, Path, description = "User identifier")
+),
+responses((status=200, description = "User read succeed, even inactive", body = ScimUser, content_type = "application/scim+json",
+headers(("ETag" = String, description = "Version of the user"))),
+(status=401, description = "Authentication required"),
+(status=403, description = "Permission user:restore required"),
+(status=404, description = "User not found")
+),
+security(("api_jwt_token" = []))
+)]
+#[get("/Users/{user_id}")]
+pub(super) async fn find_user(
+    request: HttpRequest,
+    user_id: Path<String>,
+    claims: Claims,
+    app_data: Data<Arc<AppState>>,
+) -> Result<HttpResponse, ScimError> {
+    let management: Management<_, _, _, _, _, _> = Management(Box::new(Authorized::new(
+        Box::new(claims.sub),
+        claims.tenant,
+        "user",
+        UserManagement::new(claims.tenant),
+    )));
+    let response = management
+        .0
+        .find(
+            Box::new(user_id.into_inner()),
+            true,
+            &app_data.db_connection,
+        )
+        .await
+        .map_err(|e| ScimError::from(e.get_core_error()))?;
+    Ok(respond(HttpResponse::Ok(), response, &request))
+}
+
+#[utoipa::path(
+tag = "SCIM",
+context_path = "/scim/v2",
+request_body(content = ScimUser, content_type = "application/scim+json"),
+responses((status=201, description = "User creation succeed, an inactive user is created deleted", body = ScimUser, content_type = "application/scim+json",
+headers(("ETag" = String, description = "Version of the user"), ("Location" = String, description = "URL of the user"))),
+(status=400, description = "Data sent not correct"),
+(status=401, description = "Authentication required"),
+(status=409, description = "userName already used"),
+(status=422, description = "Attributes not valid")
+),
+security(("api_jwt_token" = []))
+)]
+#[post("/Users")]
+pub(super) async fn create_user(
+    request: HttpRequest,
+    user: Json<ScimUser>,
+    claims: Claims,
+    app_data: Data<Arc<AppState>>,
+) -> Result<HttpResponse, ScimError> {
+    let user = user.into_inner();
+    let transaction = app_data
+        .db_connection
+        .begin()
+        .await
+        .map_err(|e| ScimError::from(CoreError::UnkownError(e.to_string())))?;
+    let mut response = insert(
+        claims.sub.to_owned(),
+        claims.tenant,
+        request_id(&request),
+        user.new_user()?,
+        &transaction,
+    )
+    .await?;
+    if !user.active {
+        let management: Management<_, _, _, _, _, _> = Management(Box::new(audited(
+            claims.sub,
+            claims.tenant,
+            request_id(&request),
+        )));
+        management
+            .0
+            .delete(response.get_id(), Precondition::Any, &transaction)
+            .await
+            .map_err(|e| ScimError::from(e.get_core_error()))?;
+        response = management
+            .0
+            .find(response.get_id(), true, &transaction)
+            .await
+            .map_err(|e| ScimError::from(e.get_core_error()))?;
+    }
+    transaction
+        .commit()
+        .await
+        .map_err(|e| ScimError::from(CoreError::UnkownError(e.to_string())))?;
+    let mut builder = HttpResponse::Created();
+    builder.insert_header((
+        LOCATION,
+        location(&request, format!("/Users/{}", response.get_id()).as_str()),
+    ));
+    Ok(respond(builder, response, &request))
+}
+
+#[utoipa::path(
+tag = "SCIM",
+context_path = "/scim/v2",
+params(
+("user_id" = Uuid, Path, description = "User identifier"),
+("If-Match" = Option<String>, Header, description = "ETag of the version to replace")
+),
+request_body(content = ScimUser, content_type = "application/scim+json"),
+responses((status=200, description = "User replace succeed", body = ScimUser, content_type = "application/scim+json",
+headers(("ETag" = String, description = "Version of the user"))),
+(status=400, description = "Data sent not correct"),
+(status=401, description = "Authentication required"),
+(status=403, description = "Permission user:update required to set a password"),
+(status=404, description = "User not found"),
+(status=409, description = "userName already used"),
+(status=412, description = "User changed since the version given by If-Match"),
+(status=422, description = "Attributes not valid")
+),
+security(("api_jwt_token" = []))
+)]
+#[put("/Users/{user_id}")]
+pub(super) async fn replace_user(
+    request: HttpRequest,
+    user_id: Path<String>,
+    user: Json<ScimUser>,
+    claims: Claims,
+    app_data: Data<Arc<AppState>>,
+) -> Result<HttpResponse, ScimError> {
+    let user = user.into_inner();
+    change(&request, user_id.into_inner(), claims, &app_data, |_| {
+        Ok(user)
+    })
+    .await
+}
+
+#[utoipa::path(
+tag = "SCIM",
+context_path = "/scim/v2",
+params(
+("user_id" = Uuid, Path, description = "User identifier"),
+("If-Match" = Option<String>, Header, description = "ETag of the version to change")
+),
+request_body(content = ScimPatch, content_type = "application/scim+json"),
+responses((status=200, description = "User patch succeed", body = ScimUser, content_type = "application/scim+json",
+headers(("ETag" = String, description = "Version of the user"))),
+(status=400, description = "Operations not correct, e.g. a remove or an unknown path"),
+(status=401, description = "Authentication required"),
+(status=403, description = "Permission user:update required to set a password"),
+(status=404, description = "User not found"),
+(status=409, description = "userName already used"),
+(status=412, description = "User changed since the version given by If-Match"),
+(status=422, description = "Attributes not valid")
+),
+security(("api_jwt_token" = []))
+)]
+#[patch("/Users/{user_id}")]
+pub(super) async fn patch_user(
+    request: HttpRequest,
+    user_id: Path<String>,
+    operations: Json<ScimPatch>,
+    claims: Claims,
+    app_data: Data<Arc<AppState>>,
+) -> Result<HttpResponse, ScimError> {
+    let operations = operations.into_inner();
+    if !operations
+        .schemas
+        .iter()
+        .any(|schema| schema == PATCH_OP_SCHEMA)
+    {
+        return Err(ScimError::new(
+            CoreError::DataError(format!("schemas must contain {}", PATCH_OP_SCHEMA)),
+            "invalidSyntax",
+        ));
+    }
+    change(
+        &request,
+        user_id.into_inner(),
+        claims,
+        &app_data,
+        |mut user| {
+            for operation in &operations.operations {
+                user.apply(operation)?;
+            }
+            Ok(user)
+        },
+    )
+    .await
+}
+
+#[utoipa::path(
+tag = "SCIM",
+context_path = "/scim/v2",
+params(
+("user_id" = Uuid, Path, description = "User identifier")
+),
+responses((status=204, description = "User removed for good"),
+(status=401, description = "Authentication required"),
+(status=403, description = "Permissions user:delete and user:purge required"),
+(status=404, description = "User not found")
+),
+security(("api_jwt_token" = []))
+)]
+#[delete("/Users/{user_id}")]
+pub(super) async fn delete_user(
+    request: HttpRequest,
+    user_id: Path<String>,
+    claims: Claims,
+    app_data: Data<Arc<AppState>>,
+) -> Result<HttpResponse, ScimError> {
+    let user_id = user_id.into_inner();
+    let transaction = app_data
+        .db_connection
+        .begin()
+        .await
+        .map_err(|e| ScimError::from(CoreError::UnkownError(e.to_string())))?;
+    {
+        let management: Management<_, _, _, _, _, _> = Management(Box::new(audited(
+            claims.sub,
+            claims.tenant,
+            request_id(&request),
+        )));
+        let current = management
+            .0
+            .find(Box::new(user_id.to_owned()), true, &transaction)
+            .await
+            .map_err(|e| ScimError::from(e.get_core_error()))?;
+        // Only deleted users are purged, an active one is deleted first.
+        if current.get_deleted_at().is_none() {
+            management
+                .0
+                .delete(
+                    Box::new(user_id.to_owned()),
+                    precondition(&request),
+                    &transaction,
+                )
+                .await
+                .map_err(|e| ScimError::from(e.get_core_error()))?;
+        }
+        management
+            .0
+            .purge(Box::new(user_id), &transaction)
+            .await
+            .map_err(|e| ScimError::from(e.get_core_error()))?;
+    }
+    transaction
+        .commit()
+        .await
+        .map_err(|e| ScimError::from(CoreError::UnkownError(e.to_string())))?;
+    Ok(HttpResponse::NoContent().finish())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::scim::user::{ScimUser, ScimUserList};
+    use crate::tests::{access_token, administrator_id, app_state, test_settings};
+    use actix_web::http::header::{AUTHORIZATION, CONTENT_TYPE, ETAG, IF_MATCH, LOCATION};
+    use actix_web::http::StatusCode;
+    use actix_web::web::Data;
+    use actix_web::{test, web, App};
+    use serde_json::{json, Value};
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn test_scim_user() {
+        let state = app_state(test_settings()).await;
+        let authorization = format!(
+            "Bearer {}",
+            access_token(&state.settings, &administrator_id(&state).await, 60)
+        );
+
+        let app = test::init_service(
+            App::new()
+                .app_data(Data::new(Arc::clone(&state)))
+                .service(web::scope("/scim/v2").configure(crate::scim::init)),
+        )
+        .await;
+
+        // test 201, the user is answered in the SCIM schema
+        let mut users = Vec::new();
+        for (given_name, user_name) in [
+            ("Ada", "ada@scim.example.com"),
+            ("Bea", "bea@scim.example.com"),
+            ("Cal", "cal@scim.example.com"),
+        ] {
+            let req = test::TestRequest::post()
+                .uri("/scim/v2/Users")
+                .insert_header((AUTHORIZATION, authorization.as_str()))
+                .insert_header((CONTENT_TYPE, "application/scim+json"))
+                .set_payload(
+                    json!({
+                        "schemas": ["urn:ietf:params:scim:schemas:core:2.0:User"],
+                        "externalId": given_name,
+                        "userName": user_name,
+                        "name": {"givenName": given_name, "familyName": "Scim"},
+                        "password": "Secret-password"
+                    })
+                    .to_string(),
+                )
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::CREATED);
+            assert_eq!(
+                resp.headers().get(CONTENT_TYPE).unwrap(),
+                "application/scim+json"
+            );
+            assert_eq!(resp.headers().get(ETAG).unwrap(), "\"1\"");
+            let location = resp.headers().get(LOCATION).unwrap().to_owned();
+            let user: Value = test::read_body_json(resp).await;
+            assert_eq!(user["userName"], user_name);
+            assert_eq!(user["emails"][0]["value"], user_name);
+            assert_eq!(user["active"], true);
+            assert!(user.get("password").is_none());
+            assert_eq!(user["meta"]["location"], location.to_str().unwrap());
+            users.push(serde_json::from_value::<ScimUser>(user).unwrap());
+        }
+        let user_id = users[0].id.unwrap();
+
+        // test 409, the userName is unique
+        {
+            let req = test::TestRequest::post()
+                .uri("/scim/v2/Users")
+                .insert_header((AUTHORIZATION, authorization.as_str()))
+                .set_json(json!({
+                    "userName": "ada@scim.example.com",
+                    "name": {"givenName": "Ada", "familyName": "Twice"}
+                }))
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::CONFLICT);
+            let error: Value = test::read_body_json(resp).await;
+            assert_eq!(
+                error["schemas"],
+                json!(["urn:ietf:params:scim:api:messages:2.0:Error"])
+            );
+            assert_eq!(error["status"], "409");
+            assert_eq!(error["scimType"], "uniqueness");
+        }
+
+        // test 200, a user is filtered by its userName
+        {
+            let req = test::TestRequest::get()
+                .uri("/scim/v2/Users?filter=userName%20eq%20%22ADA@scim.example.com%22")
+                .insert_header((AUTHORIZATION, authorization.as_str()))
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::OK);
+            let list: ScimUserList = test::read_body_json(resp).await;
+            assert_eq!(list.total_results, 1);
+            assert_eq!(list.resources[0].id, Some(user_id));
+        }
+
+        // test 200, startIndex and count are not bound to pages
+        {
+            let req = test::TestRequest::get()
+                .uri("/scim/v2/Users?filter=emails%20co%20%22@scim.example.com%22&sortBy=userName&startIndex=2&count=2")
+                .insert_header((AUTHORIZATION, authorization.as_str()))
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::OK);
+            let list: ScimUserList = test::read_body_json(resp).await;
+            assert_eq!(list.total_results, 3);
+            assert_eq!((list.start_index, list.items_per_page), (2, 2));
+            assert_eq!(
+                list.resources
+                    .iter()
+                    .map(|user| user.user_name.as_str())
+                    .collect::<Vec<_>>(),
+                vec!["bea@scim.example.com", "cal@scim.example.com"]
+            );
+        }
+
+        // test 400, only eq, co and sw are supported
+        {
+            let req = test::TestRequest::get()
+                .uri("/scim/v2/Users?filter=userName%20gt%20%22a%22")
+                .insert_header((AUTHORIZATION, authorization.as_str()))
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+            let error: Value = test::read_body_json(resp).await;
+            assert_eq!(error["scimType"], "invalidFilter");
+        }
+
+        // test 200, a user deactivated by PATCH is kept inactive
+        {
+            let req = test::TestRequest::patch()
+                .uri(format!("/scim/v2/Users/{}", user_id).as_str())
+                .insert_header((AUTHORIZATION, authorization.as_str()))
+                .insert_header((IF_MATCH, "\"1\""))
+                .set_json(json!({
+                    "schemas": ["urn:ietf:params:scim:api:messages:2.0:PatchOp"],
+                    "Operations": [
+                        {"op": "Replace", "path": "active", "value": "False"},
+                        {"op": "replace", "value": {"name.familyName": "Inactive"}}
+                    ]
+                }))
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::OK);
+            let user: ScimUser = test::read_body_json(resp).await;
+            assert!(!user.active);
+            assert_eq!(user.name.family_name, "Inactive");
+
+            let req = test::TestRequest::get()
+                .uri(format!("/scim/v2/Users/{}", user_id).as_str())
+                .insert_header((AUTHORIZATION, authorization.as_str()))
+                .to_request();
+            let user: ScimUser = test::call_and_read_body_json(&app, req).await;
+            assert!(!user.active);
+        }
+
+        // test 412, the version given by If-Match is outdated
+        {
+            let req = test::TestRequest::put()
+                .uri(format!("/scim/v2/Users/{}", user_id).as_str())
+                .insert_header((AUTHORIZATION, authorization.as_str()))
+                .insert_header((IF_MATCH, "\"1\""))
+                .set_json(json!({
+                    "userName": "ada@scim.example.com",
+                    "name": {"givenName": "Ada", "familyName": "Scim"}
+                }))
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::PRECONDITION_FAILED);
+        }
+
+        // test 200, PUT reactivates the user
+        {
+            let req = test::TestRequest::put()
+                .uri(format!("/scim/v2/Users/{}", user_id).as_str())
+                .insert_header((AUTHORIZATION, authorization.as_str()))
+                .set_json(json!({
+                    "userName": "ada@scim.example.com",
+                    "name": {"givenName": "Ada", "familyName": "Scim"}
+                }))
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::OK);
+            let user: ScimUser = test::read_body_json(resp).await;
+            assert!(user.active);
+            assert_eq!(user.name.family_name, "Scim");
+        }
+
+        // test 400, attributes are not removed
+        {
+            let req = test::TestRequest::patch()
+                .uri(format!("/scim/v2/Users/{}", user_id).as_str())
+                .insert_header((AUTHORIZATION, authorization.as_str()))
+                .set_json(json!({
+                    "schemas": ["urn:ietf:params:scim:api:messages:2.0:PatchOp"],
+                    "Operations": [{"op": "remove", "path": "name.givenName"}]
+                }))
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+            let error: Value = test::read_body_json(resp).await;
+            assert_eq!(error["scimType"], "mutability");
+        }
+
+        // test 204, DELETE removes the users for good
+        for user in &users {
+            let req = test::TestRequest::delete()
+                .uri(format!("/scim/v2/Users/{}", user.id.unwrap()).as_str())
+                .insert_header((AUTHORIZATION, authorization.as_str()))
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::NO_CONTENT);
+        }
+        {
+            let req = test::TestRequest::get()
+                .uri(format!("/scim/v2/Users/{}", user_id).as_str())
+                .insert_header((AUTHORIZATION, authorization.as_str()))
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+        }
+    }
+}
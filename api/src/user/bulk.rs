@@ -0,0 +1,380 @@
+use crate::audit::request_id;
+use crate::auth::Claims;
+use crate::error::{AsHttpError, PROBLEM_JSON};
+use crate::user::create::insert;
+use crate::user::{audited, NewUser, User};
+use crate::{AppState, Management};
+use actix_web::http::StatusCode;
+use actix_web::web::{Data, Json};
+use actix_web::{post, HttpRequest, HttpResponse, Responder, ResponseError};
+use common::error::CoreError;
+use common::management::Precondition;
+use sea_orm::{DatabaseTransaction, TransactionTrait};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::sync::Arc;
+use utoipa::ToSchema;
+use uuid::Uuid;
+use validator::Validate;
+
+/// How the operations of a bulk request are committed
+#[derive(Serialize, Deserialize, ToSchema, Default, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub(super) enum BulkMode {
+    /// Every operation is committed, or none of them when one fails
+    #[default]
+    Atomic,
+    /// Each operation is committed on its own, failures are reported with the results
+    BestEffort,
+}
+
+/// Operation of a bulk request, `version` is checked like an `If-Match` header
+#[derive(Serialize, Deserialize, ToSchema)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub(super) enum BulkOperation {
+    Create {
+        user: NewUser,
+    },
+    Update {
+        id: Uuid,
+        user: User,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        version: Option<i32>,
+    },
+    Delete {
+        id: Uuid,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        version: Option<i32>,
+    },
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub(super) struct BulkRequest {
+    #[serde(default)]
+    pub mode: BulkMode,
+    pub operations: Vec<BulkOperation>,
+}
+
+/// Outcome of one operation of a bulk request
+#[derive(Serialize, Deserialize, ToSchema, Debug)]
+pub(super) struct BulkResult {
+    /// Position of the operation in the request, starting from 0
+    pub index: usize,
+    /// Status the operation gets from its own endpoint
+    pub status: u16,
+    /// User created or updated
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub user: Option<User>,
+    /// Problem of a failed operation
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schema(value_type = Option<Object>)]
+    pub error: Option<serde_json::Value>,
+}
+
+#[derive(Serialize, Deserialize, ToSchema, Debug)]
+pub(super) struct BulkResponse {
+    pub mode: BulkMode,
+    pub succeeded: usize,
+    pub failed: usize,
+    pub results: Vec<BulkResult>,
+}
+
+/// Run `operation` in the organization `tenant` on behalf of `actor`, giving the status and the user it returns.
+async fn execute(
+    actor: String,
+    tenant: Uuid,
+    request_id: Option<String>,
+    operation: BulkOperation,
+    transaction: &DatabaseTransaction,
+) -> Result<(StatusCode, Option<User>), AsHttpError> {
+    let expected = |version: Option<i32>| {
+        version.map_or(Precondition::Any, |version| {
+            Precondition::Versions(vec![version])
+        })
+    };
+    match operation {
+        BulkOperation::Create { user } => insert(actor, tenant, request_id, user, transaction)
+            .await
+            .map(|user| (StatusCode::CREATED, Some(User::from(user)))),
+        BulkOperation::Update { id, user, version } => {
+            user.validate().map_err(AsHttpError::from)?;
+            let management: Management<_, _, _, _, _, _> =
+                Management(Box::new(audited(actor, tenant, request_id)));
+            management
+                .0
+                .update(
+                    Box::new(id.to_string()),
+                    Box::new(user),
+                    expected(version),
+                    transaction,
+                )
+                .await
+                .map(|user| (StatusCode::OK, Some(User::from(user))))
+                .map_err(|e| AsHttpError::from(e.get_core_error()))
+        }
+        BulkOperation::Delete { id, version } => {
+            let management: Management<_, _, _, _, _, _> =
+                Management(Box::new(audited(actor, tenant, request_id)));
+            management
+                .0
+                .delete(Box::new(id.to_string()), expected(version), transaction)
+                .await
+                .map(|_| (StatusCode::NO_CONTENT, None))
+                .map_err(|e| AsHttpError::from(e.get_core_error()))
+        }
+    }
+}
+
+#[utoipa::path(
+tag = "Bulk users",
+context_path = "/api/user",
+request_body = BulkRequest,
+responses((status=200, description = "Operations done, see each result for its status", body = BulkResponse),
+(status=400, description = "No operation or more than `application.bulk_max_operations`"),
+(status=401, description = "Authentication required"),
+(status=403, description = "An atomic operation not permitted, its index is given by `operation`"),
+(status=404, description = "An atomic operation on an unknown user, its index is given by `operation`"),
+(status=409, description = "An atomic operation on an email already used, its index is given by `operation`"),
+(status=412, description = "An atomic operation on a changed version, its index is given by `operation`"),
+(status=422, description = "An atomic operation with fields not valid, its index is given by `operation`")
+),
+security(("api_jwt_token" = []))
+)]
+#[post("/bulk")]
+pub(super) async fn bulk(
+    request: HttpRequest,
+    bulk_request: Json<BulkRequest>,
+    claims: Claims,
+    app_data: Data<Arc<AppState>>,
+) -> actix_web::Result<impl Responder> {
+    let BulkRequest { mode, operations } = bulk_request.into_inner();
+    let max_operations = app_data.settings.application.bulk_max_operations;
+    if operations.is_empty() || operations.len() > max_operations {
+        return Err(AsHttpError::from(CoreError::DataError(format!(
+            "A bulk request holds from 1 to {} operations",
+            max_operations
+        )))
+        .into());
+    }
+    let transaction = app_data
+        .db_connection
+        .begin()
+        .await
+        .map_err(|e| AsHttpError::from(CoreError::UnkownError(e.to_string())))?;
+    let mut results = Vec::with_capacity(operations.len());
+    for (index, operation) in operations.into_iter().enumerate() {
+        let outcome = match mode {
+            BulkMode::Atomic => {
+                execute(
+                    claims.sub.to_owned(),
+                    claims.tenant,
+                    request_id(&request),
+                    operation,
+                    &transaction,
+                )
+                .await
+            }
+            BulkMode::BestEffort => {
+                // A savepoint undoes the failed operation alone
+                let savepoint = transaction
+                    .begin()
+                    .await
+                    .map_err(|e| AsHttpError::from(CoreError::UnkownError(e.to_string())))?;
+                let outcome = execute(
+                    claims.sub.to_owned(),
+                    claims.tenant,
+                    request_id(&request),
+                    operation,
+                    &savepoint,
+                )
+                .await;
+                match outcome {
+                    Ok(_) => savepoint.commit().await,
+                    Err(_) => savepoint.rollback().await,
+                }
+                .map_err(|e| AsHttpError::from(CoreError::UnkownError(e.to_string())))?;
+                outcome
+            }
+        };
+        match outcome {
+            Ok((status, user)) => results.push(BulkResult {
+                index,
+                status: status.as_u16(),
+                user,
+                error: None,
+            }),
+            Err(error) if mode == BulkMode::Atomic => {
+                transaction
+                    .rollback()
+                    .await
+                    .map_err(|e| AsHttpError::from(CoreError::UnkownError(e.to_string())))?;
+                let mut problem = error.problem();
+                problem["operation"] = json!(index);
+                return Ok(HttpResponse::build(error.status_code())
+                    .content_type(PROBLEM_JSON)
+                    .json(problem));
+            }
+            Err(error) => results.push(BulkResult {
+                index,
+                status: error.status_code().as_u16(),
+                user: None,
+                error: Some(error.problem()),
+            }),
+        }
+    }
+    transaction
+        .commit()
+        .await
+        .map_err(|e| AsHttpError::from(CoreError::UnkownError(e.to_string())))?;
+    let succeeded = results
+        .iter()
+        .filter(|result| result.error.is_none())
+        .count();
+    Ok(HttpResponse::Ok().json(BulkResponse {
+        mode,
+        succeeded,
+        failed: results.len() - succeeded,
+        results,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{access_token, administrator_id, app_state, test_settings};
+    use crate::user::bulk::{bulk, BulkResponse};
+    use crate::user::read::read;
+    use crate::user::UserSearchResult;
+    use actix_web::http::header::AUTHORIZATION;
+    use actix_web::http::StatusCode;
+    use actix_web::web::Data;
+    use actix_web::{test, web, App};
+    use serde_json::{json, Value};
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn test_user_bulk() {
+        let state = app_state(test_settings()).await;
+        let authorization = format!(
+            "Bearer {}",
+            access_token(&state.settings, &administrator_id(&state).await, 60)
+        );
+
+        let app = test::init_service(
+            App::new()
+                .app_data(Data::new(Arc::clone(&state)))
+                .service(web::scope("/api/user").service(read).service(bulk)),
+        )
+        .await;
+
+        let create = |first_name: &str, email: &str| {
+            json!({
+                "op": "create",
+                "user": {"first_name": first_name, "last_name": "Bulk", "email": email}
+            })
+        };
+
+        // test 200, every operation of an atomic request is committed
+        let users = {
+            let req = test::TestRequest::post()
+                .uri("/api/user/bulk")
+                .insert_header((AUTHORIZATION, authorization.as_str()))
+                .set_json(json!({"operations": [
+                    create("Anne", "annebulk@example.com"),
+                    create("Basile", "basilebulk@example.com")
+                ]}))
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::OK);
+            let response: BulkResponse = test::read_body_json(resp).await;
+            assert_eq!(response.succeeded, 2);
+            assert_eq!(response.failed, 0);
+            assert!(response.results.iter().all(|result| result.status == 201));
+            response
+                .results
+                .into_iter()
+                .map(|result| result.user.unwrap())
+                .collect::<Vec<_>>()
+        };
+
+        // test 409, an atomic request stops at its first failure and commits nothing
+        {
+            let req = test::TestRequest::post()
+                .uri("/api/user/bulk")
+                .insert_header((AUTHORIZATION, authorization.as_str()))
+                .set_json(json!({"mode": "atomic", "operations": [
+                    create("Cyril", "cyrilbulk@example.com"),
+                    create("Anne", "annebulk@example.com")
+                ]}))
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::CONFLICT);
+            let problem: Value = test::read_body_json(resp).await;
+            assert_eq!(problem["operation"], 1);
+            assert_eq!(problem["code"], "conflict");
+
+            let req = test::TestRequest::get()
+                .uri("/api/user?pattern=cyrilbulk@example.com&match_mode=exact")
+                .insert_header((AUTHORIZATION, authorization.as_str()))
+                .to_request();
+            let found: UserSearchResult = test::call_and_read_body_json(&app, req).await;
+            assert!(found.result.is_empty());
+        }
+
+        // test 200, a best effort request reports the status of each operation
+        {
+            let req = test::TestRequest::post()
+                .uri("/api/user/bulk")
+                .insert_header((AUTHORIZATION, authorization.as_str()))
+                .set_json(json!({"mode": "best_effort", "operations": [
+                    create("Denis", "denisbulk@example.com"),
+                    create("", "emptybulk@example.com"),
+                    {
+                        "op": "update",
+                        "id": users[0].id,
+                        "user": {"first_name": "Anna", "last_name": "Bulk", "email": "annebulk@example.com"},
+                        "version": users[0].version
+                    },
+                    {"op": "delete", "id": users[1].id, "version": users[1].version + 1},
+                    {"op": "delete", "id": uuid::Uuid::new_v4()}
+                ]}))
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::OK);
+            let response: BulkResponse = test::read_body_json(resp).await;
+            assert_eq!(response.succeeded, 2);
+            assert_eq!(response.failed, 3);
+            let statuses: Vec<u16> = response
+                .results
+                .iter()
+                .map(|result| result.status)
+                .collect();
+            assert_eq!(statuses, vec![201, 422, 200, 412, 404]);
+            assert_eq!(
+                response.results[1].error.as_ref().unwrap()["code"],
+                "validation_failed"
+            );
+            assert_eq!(
+                response.results[2].user.as_ref().unwrap().first_name,
+                "Anna"
+            );
+
+            let req = test::TestRequest::get()
+                .uri("/api/user?pattern=denisbulk@example.com&match_mode=exact")
+                .insert_header((AUTHORIZATION, authorization.as_str()))
+                .to_request();
+            let found: UserSearchResult = test::call_and_read_body_json(&app, req).await;
+            assert_eq!(found.result.len(), 1);
+        }
+
+        // test 400, a bulk request holds at least one operation
+        {
+            let req = test::TestRequest::post()
+                .uri("/api/user/bulk")
+                .insert_header((AUTHORIZATION, authorization.as_str()))
+                .set_json(json!({"operations": []}))
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+        }
+    }
+}
@@ -1,68 +1,138 @@
+use crate::audit::request_id;
+use crate::auth::{Claims, DEFAULT_ROLE};
 use crate::error::AsHttpError;
-use crate::user::User;
-use crate::{AppState, Management};
+use crate::user::{audited, entity_tag, NewUser, User};
+use crate::{AppState, Authentication, Authorization, Management};
 use actix_web::web::{Data, Json};
-use actix_web::{post, HttpResponse, Responder};
+use actix_web::{post, HttpRequest, HttpResponse, Responder};
+use common::error::{CoreError, FieldError};
+use common::user::userable::Userable;
+use sea_orm::{DatabaseTransaction, TransactionTrait};
+use services_local::authorization::RoleManagement;
+use services_local::credential::CredentialManagement;
 use std::sync::Arc;
+use uuid::Uuid;
+use validator::Validate;
 
 #[utoipa::path(
 tag = "Create user",
 context_path = "/api/user",
 request_body = NewUser,
-responses((status=201, description = "User creation succeed"),
+responses((status=201, description = "User creation succeed", headers(("ETag" = String, description = "Version of the user"))),
 (status=400, description = "Data sent not correct"),
-(status=401, description = "Authentication required")
-)
+(status=401, description = "Authentication required"),
+(status=409, description = "Email already used, with the code `deleted` when its user is deleted and can be restored"),
+(status=422, description = "Fields not valid")
+),
+security(("api_jwt_token" = []))
 )]
 #[post("")]
 pub(super) async fn create(
-    user: Json<User>,
+    request: HttpRequest,
+    user: Json<NewUser>,
+    claims: Claims,
     app_data: Data<Arc<AppState>>,
 ) -> actix_web::Result<impl Responder> {
-    let management: Management<_, _, _, _, _> =
-        Management(Box::new(services_local::user::UserManagement));
+    let transaction = app_data
+        .db_connection
+        .begin()
+        .await
+        .map_err(|e| AsHttpError::from(CoreError::UnkownError(e.to_string())))?;
+    let response = insert(
+        claims.sub,
+        claims.tenant,
+        request_id(&request),
+        user.into_inner(),
+        &transaction,
+    )
+    .await?;
+    transaction
+        .commit()
+        .await
+        .map_err(|e| AsHttpError::from(CoreError::UnkownError(e.to_string())))?;
+    Ok(HttpResponse::Created()
+        .insert_header(entity_tag(response.get_version()))
+        .json(response))
+}
+
+/// Conflict on an email owned by a deleted user, it is restored rather than created again.
+pub(super) fn deleted_email() -> AsHttpError {
+    AsHttpError::from(CoreError::Conflict(FieldError::new(
+        "email",
+        "deleted",
+        "Email belongs to a deleted user, restore it instead",
+    )))
+}
+
+/// Create `user` in the organization `tenant` on behalf of `actor` with the default role and its password, if given.
+pub(crate) async fn insert(
+    actor: String,
+    tenant: Uuid,
+    request_id: Option<String>,
+    mut user: NewUser,
+    transaction: &DatabaseTransaction,
+) -> Result<Box<dyn Userable>, AsHttpError> {
+    user.validate().map_err(AsHttpError::from)?;
+    let authentication: Authentication<_, _, _> =
+        Authentication(Box::new(CredentialManagement::new(tenant)));
+    if authentication
+        .0
+        .identify(user.email.to_owned(), transaction)
+        .await
+        .map_err(|e| AsHttpError::from(e.get_core_error()))?
+        .is_some_and(|existing| existing.get_deleted_at().is_some())
+    {
+        return Err(deleted_email());
+    }
+    let password = user.password.take();
+    let management: Management<_, _, _, _, _, _> =
+        Management(Box::new(audited(actor, tenant, request_id)));
     let response = management
         .0
-        .create(Box::new(user.into_inner()), &app_data.db_connection)
+        .create(Box::new(User::from(user)), transaction)
         .await
         .map_err(|e| AsHttpError::from(e.get_core_error()))?;
-    Ok(HttpResponse::Created().json(response))
+    let authorization: Authorization<_, _> = Authorization(Box::new(RoleManagement::new(tenant)));
+    authorization
+        .0
+        .assign(response.get_id(), String::from(DEFAULT_ROLE), transaction)
+        .await
+        .map_err(|e| AsHttpError::from(e.get_core_error()))?;
+    if let Some(password) = password {
+        authentication
+            .0
+            .set_password(response.get_id(), password, transaction)
+            .await
+            .map_err(|e| AsHttpError::from(e.get_core_error()))?;
+    }
+    Ok(response)
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::tests::initialize_db;
+    use crate::error::extractor_errors;
+    use crate::tests::{access_token, administrator_id, app_state, test_settings};
     use crate::user::create::create;
     use crate::user::{NewUser, User};
-    use crate::AppState;
+    use actix_web::http::header::{AUTHORIZATION, CONTENT_TYPE, ETAG};
     use actix_web::http::StatusCode;
     use actix_web::web::Data;
     use actix_web::{test, web, App};
-    use sea_orm::{ConnectOptions, Database, DatabaseConnection};
     use serde_json::json;
-    use service_config::Settings;
     use std::sync::Arc;
 
     #[tokio::test]
     async fn test_user_create() {
-        initialize_db().await;
-        std::env::set_var("APP_ENVIRONMENT", "test");
-        let settings = Settings::load().expect("Error occurs when trying to load settings");
-
-        let database_url = settings.database.get_url();
-        let mut db_connection_opt = ConnectOptions::new(database_url.to_owned());
-        db_connection_opt.sqlx_logging_level(settings.application.loglevel.to_level_filter());
-
-        let db_connection: DatabaseConnection = Database::connect(db_connection_opt)
-            .await
-            .expect("Error occurs when trying to connect to database");
-
-        let state = AppState { db_connection };
-        let state = Arc::new(state);
+        let state = app_state(test_settings()).await;
+        let authorization = format!(
+            "Bearer {}",
+            access_token(&state.settings, &administrator_id(&state).await, 60)
+        );
 
         let app = test::init_service(
             App::new()
                 .app_data(Data::new(Arc::clone(&state)))
+                .configure(extractor_errors)
                 .service(web::scope("/api/user").service(create)),
         )
         .await;
@@ -73,37 +143,107 @@ mod tests {
                 first_name: "Jules".to_string(),
                 last_name: "RAKOTOBE".to_string(),
                 email: "jules.rak@example.com".to_string(),
+                password: None,
             };
             let req = test::TestRequest::post()
                 .uri("/api/user")
+                .insert_header((AUTHORIZATION, authorization.as_str()))
                 // .insert_header(ContentType::json())
                 .set_json(&new_user)
                 .to_request();
             let resp = test::call_service(&app, req).await;
-            println!("status code: {:?}", resp.status().to_string());
             assert_eq!(resp.status(), StatusCode::CREATED);
+            assert_eq!(resp.headers().get(ETAG).unwrap(), "\"1\"");
             let response = test::read_body(resp).await;
-            println!("response body: {:?}", response);
             let user: User = serde_json::from_slice(response.iter().as_slice()).unwrap();
+            assert_eq!(user.version, 1);
             assert_eq!(user.first_name, new_user.first_name);
             assert_eq!(user.last_name, new_user.last_name);
             assert_eq!(user.email, new_user.email);
         }
 
+        // test 422, every invalid field is reported
+        {
+            let req = test::TestRequest::post()
+                .uri("/api/user")
+                .insert_header((AUTHORIZATION, authorization.as_str()))
+                .set_json(json!({
+                    "first_name": "",
+                    "last_name": "L".repeat(101),
+                    "email": "not-an-email",
+                    "password": "short"
+                }))
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::UNPROCESSABLE_ENTITY);
+            let response: serde_json::Value = test::read_body_json(resp).await;
+            let errors: Vec<(&str, &str)> = response["errors"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|error| {
+                    assert!(error["message"].is_string());
+                    (
+                        error["field"].as_str().unwrap(),
+                        error["code"].as_str().unwrap(),
+                    )
+                })
+                .collect();
+            assert_eq!(
+                errors,
+                vec![
+                    ("email", "invalid_format"),
+                    ("first_name", "required"),
+                    ("last_name", "too_long"),
+                    ("password", "too_short"),
+                ]
+            );
+        }
+
         // test 400
         {
             let req = test::TestRequest::post()
                 .uri("/api/user")
+                .insert_header((AUTHORIZATION, authorization.as_str()))
                 // .insert_header(ContentType::json())
                 .set_json(json!({
                     "foo": "bar"
                 }))
                 .to_request();
             let resp = test::call_service(&app, req).await;
-            println!("status code: {:?}", resp.status().to_string());
             assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
-            let response = test::read_body(resp).await;
-            println!("response body: {:?}", response);
+            assert_eq!(
+                resp.headers().get(CONTENT_TYPE).unwrap(),
+                "application/problem+json"
+            );
+            let response: serde_json::Value = test::read_body_json(resp).await;
+            assert_eq!(response["status"], 400);
+            assert_eq!(response["code"], "invalid_data");
+            assert!(response["title"].is_string());
+            assert!(response["detail"].is_string());
+            assert!(response["instance"].is_string());
+        }
+
+        // test 409, database messages are not sent back
+        {
+            let req = test::TestRequest::post()
+                .uri("/api/user")
+                .insert_header((AUTHORIZATION, authorization.as_str()))
+                .set_json(NewUser {
+                    first_name: "Jules".to_string(),
+                    last_name: "RAKOTOBE".to_string(),
+                    email: "jules.rak@example.com".to_string(),
+                    password: None,
+                })
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::CONFLICT);
+            let response: serde_json::Value = test::read_body_json(resp).await;
+            assert_eq!(response["code"], "conflict");
+            assert_eq!(response["errors"][0]["field"], "email");
+            assert_eq!(response["errors"][0]["code"], "already_exists");
+            let detail = response["detail"].as_str().unwrap().to_lowercase();
+            assert!(!detail.contains("unique") && !detail.contains("user.email"));
         }
     }
 }
@@ -1,66 +1,79 @@
+use crate::audit::request_id;
+use crate::auth::Claims;
 use crate::error::AsHttpError;
+use crate::user::{audited, precondition};
 use crate::{AppState, Management};
 use actix_web::web::{Data, Path};
-use actix_web::{delete, HttpResponse, Responder};
+use actix_web::{delete, HttpRequest, HttpResponse, Responder};
+use common::error::CoreError;
+use sea_orm::TransactionTrait;
 use std::sync::Arc;
 
 #[utoipa::path(
 tag = "Delete user",
 context_path = "/api/user",
 params(
-("user_id" = Uuid, Path, description = "User identifier")
+("user_id" = Uuid, Path, description = "User identifier"),
+("If-Match" = Option<String>, Header, description = "ETag of the version to delete")
 ),
 responses((status=204, description = "User deletion succeed"),
-(status=401, description = "Authentication required")
-)
+(status=401, description = "Authentication required"),
+(status=412, description = "User changed since the version given by If-Match")
+),
+security(("api_jwt_token" = []))
 )]
 #[delete("/{user_id}")]
 pub(super) async fn delete(
+    request: HttpRequest,
     user_id: Path<String>,
+    claims: Claims,
     app_data: Data<Arc<AppState>>,
 ) -> actix_web::Result<impl Responder> {
     let user_id = user_id.into_inner();
-    let management: Management<_, _, _, _, _> =
-        Management(Box::new(services_local::user::UserManagement));
-    management
-        .0
-        .delete(Box::new(user_id), &app_data.db_connection)
+    let transaction = app_data
+        .db_connection
+        .begin()
         .await
-        .map_err(|e| AsHttpError::from(e.get_core_error()))?;
+        .map_err(|e| AsHttpError::from(CoreError::UnkownError(e.to_string())))?;
+    {
+        let management: Management<_, _, _, _, _, _> = Management(Box::new(audited(
+            claims.sub,
+            claims.tenant,
+            request_id(&request),
+        )));
+        management
+            .0
+            .delete(Box::new(user_id), precondition(&request), &transaction)
+            .await
+            .map_err(|e| AsHttpError::from(e.get_core_error()))?;
+    }
+    transaction
+        .commit()
+        .await
+        .map_err(|e| AsHttpError::from(CoreError::UnkownError(e.to_string())))?;
     Ok(HttpResponse::NoContent().finish())
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::tests::initialize_db;
+    use crate::tests::{access_token, administrator_id, app_state, test_settings};
     use crate::user::create::create;
     use crate::user::{NewUser, User};
-    use crate::AppState;
+    use actix_web::http::header::{AUTHORIZATION, IF_MATCH};
     use actix_web::http::StatusCode;
     use actix_web::web::Data;
     use actix_web::{test, web, App};
-    use sea_orm::{ConnectOptions, Database, DatabaseConnection};
     use std::sync::Arc;
 
     use crate::user::delete::delete;
-    use service_config::Settings;
 
     #[tokio::test]
     async fn test_delete_update() {
-        initialize_db().await;
-        std::env::set_var("APP_ENVIRONMENT", "test");
-        let settings = Settings::load().expect("Error occurs when trying to load settings");
-
-        let database_url = settings.database.get_url();
-        let mut db_connection_opt = ConnectOptions::new(database_url.to_owned());
-        db_connection_opt.sqlx_logging_level(settings.application.loglevel.to_level_filter());
-
-        let db_connection: DatabaseConnection = Database::connect(db_connection_opt)
-            .await
-            .expect("Error occurs when trying to connect to database");
-
-        let state = AppState { db_connection };
-        let state = Arc::new(state);
+        let state = app_state(test_settings()).await;
+        let authorization = format!(
+            "Bearer {}",
+            access_token(&state.settings, &administrator_id(&state).await, 60)
+        );
 
         let app = test::init_service(
             App::new()
@@ -73,24 +86,64 @@ mod tests {
             first_name: "Luc".to_string(),
             last_name: "Joseph".to_string(),
             email: "lucjoseph@example.com".to_string(),
+            password: None,
         };
         let req = test::TestRequest::post()
             .uri("/api/user")
+            .insert_header((AUTHORIZATION, authorization.as_str()))
             .set_json(&new_user)
             .to_request();
         let resp = test::call_service(&app, req).await;
         let user: User = test::read_body_json(resp).await;
-        println!("user id: {:?}", user.id);
 
         let mut path = String::from("/api/user/");
         path.push_str(&user.id.clone().to_string());
 
+        // test 403, only administrators can delete users
+        {
+            let req = test::TestRequest::delete()
+                .uri(path.as_str())
+                .insert_header((
+                    AUTHORIZATION,
+                    format!(
+                        "Bearer {}",
+                        access_token(&state.settings, &user.id.to_string(), 60)
+                    ),
+                ))
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+        }
+
+        // test 412, the user changed since the given version
+        {
+            let req = test::TestRequest::delete()
+                .uri(path.as_str())
+                .insert_header((AUTHORIZATION, authorization.as_str()))
+                .insert_header((IF_MATCH, format!("\"{}\"", user.version + 1)))
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::PRECONDITION_FAILED);
+        }
+
         // test 204
         {
-            let req = test::TestRequest::delete().uri(path.as_str()).to_request();
+            let req = test::TestRequest::delete()
+                .uri(path.as_str())
+                .insert_header((AUTHORIZATION, authorization.as_str()))
+                .to_request();
             let resp = test::call_service(&app, req).await;
-            println!("status code: {:?}", resp.status().to_string());
             assert_eq!(resp.status(), StatusCode::NO_CONTENT);
         }
+
+        // test 404, a deleted user can not be deleted again
+        {
+            let req = test::TestRequest::delete()
+                .uri(path.as_str())
+                .insert_header((AUTHORIZATION, authorization.as_str()))
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+        }
     }
 }
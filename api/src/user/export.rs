@@ -0,0 +1,395 @@
+use crate::auth::Claims;
+use crate::error::AsHttpError;
+use crate::user::read::{authorize_include_deleted, QuerySearch};
+use crate::user::User;
+use crate::{AppState, Management};
+use actix_web::http::header::{
+    Accept, ContentDisposition, DispositionParam, DispositionType, Header,
+};
+use actix_web::web::{Bytes, Data};
+use actix_web::{get, web, HttpRequest, HttpResponse, Responder};
+use common::error::CoreError;
+use futures::stream::{self, StreamExt};
+use services_local::authorization::Authorized;
+use services_local::user::UserManagement;
+use std::borrow::Cow;
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// Columns of a CSV export, in order
+const CSV_COLUMNS: [&str; 6] = [
+    "id",
+    "first_name",
+    "last_name",
+    "email",
+    "version",
+    "deleted_at",
+];
+
+/// Media types a user export is written in
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Format {
+    Csv,
+    Ndjson,
+}
+
+impl Format {
+    /// Format preferred by the `Accept` header, CSV when it is absent.
+    fn negotiate(request: &HttpRequest) -> Result<Self, AsHttpError> {
+        let Ok(accept) = Accept::parse(request) else {
+            return Ok(Self::Csv);
+        };
+        if accept.0.is_empty() {
+            return Ok(Self::Csv);
+        }
+        accept
+            .ranked()
+            .iter()
+            .find_map(
+                |mime| match (mime.type_().as_str(), mime.subtype().as_str()) {
+                    ("text", "csv") | ("text", "*") | ("*", "*") => Some(Self::Csv),
+                    ("application", "x-ndjson") | ("application", "*") => Some(Self::Ndjson),
+                    _ => None,
+                },
+            )
+            .ok_or_else(|| {
+                AsHttpError::from(CoreError::NotAcceptable(String::from(
+                    "Users are exported as text/csv or application/x-ndjson",
+                )))
+            })
+    }
+
+    fn content_type(&self) -> &'static str {
+        match self {
+            Self::Csv => "text/csv; charset=utf-8",
+            Self::Ndjson => "application/x-ndjson",
+        }
+    }
+
+    fn filename(&self) -> &'static str {
+        match self {
+            Self::Csv => "users.csv",
+            Self::Ndjson => "users.ndjson",
+        }
+    }
+
+    /// Lines written before the first user
+    fn header(&self) -> String {
+        match self {
+            Self::Csv => format!("{}\r\n", CSV_COLUMNS.join(",")),
+            Self::Ndjson => String::new(),
+        }
+    }
+
+    /// One line per user, each ended by a line break
+    fn write(&self, users: &[User]) -> Result<String, AsHttpError> {
+        let mut lines = String::new();
+        for user in users {
+            match self {
+                Self::Csv => {
+                    let row = [
+                        user.id.to_string(),
+                        user.first_name.to_owned(),
+                        user.last_name.to_owned(),
+                        user.email.to_owned(),
+                        user.version.to_string(),
+                        user.deleted_at
+                            .map(|deleted_at| deleted_at.to_rfc3339())
+                            .unwrap_or_default(),
+                    ];
+                    lines.push_str(
+                        row.iter()
+                            .map(|field| csv_field(field))
+                            .collect::<Vec<_>>()
+                            .join(",")
+                            .as_str(),
+                    );
+                    lines.push_str("\r\n");
+                }
+                Self::Ndjson => {
+                    lines.push_str(
+                        serde_json::to_string(user)
+                            .map_err(|e| AsHttpError::from(CoreError::UnkownError(e.to_string())))?
+                            .as_str(),
+                    );
+                    lines.push('\n');
+                }
+            }
+        }
+        Ok(lines)
+    }
+}
+
+/// Characters a spreadsheet reads a formula from when they start a cell
+const FORMULA_PREFIXES: [char; 6] = ['=', '+', '-', '@', '\t', '\r'];
+
+/// RFC 4180 field, quoted when it holds a separator, a quote or a line break.
+/// A value read as a formula by spreadsheets is escaped by a leading `'` and quoted.
+pub(super) fn csv_field(value: &str) -> Cow<'_, str> {
+    if value.starts_with(FORMULA_PREFIXES) {
+        Cow::Owned(format!("\"'{}\"", value.replace('"', "\"\"")))
+    } else if value.contains([',', '"', '\r', '\n']) {
+        Cow::Owned(format!("\"{}\"", value.replace('"', "\"\"")))
+    } else {
+        Cow::Borrowed(value)
+    }
+}
+
+/// Users of the organization `tenant` matching `query` following the cursor `after` readable by `actor`,
+/// with the cursor of the next ones if any.
+async fn chunk(
+    actor: &str,
+    tenant: Uuid,
+    mut query: QuerySearch,
+    after: String,
+    app_data: &AppState,
+) -> Result<(Vec<User>, Option<String>), AsHttpError> {
+    query.after = Some(after);
+    let management: Management<_, _, _, _, _, _> = Management(Box::new(Authorized::new(
+        Box::new(actor.to_owned()),
+        tenant,
+        "user",
+        UserManagement::new(tenant),
+    )));
+    let search_result = management
+        .0
+        .read(Box::new(query), &app_data.db_connection)
+        .await
+        .map_err(|e| AsHttpError::from(e.get_core_error()))?;
+    Ok((
+        search_result.get_result().map(User::from).collect(),
+        search_result.get_next_cursor(),
+    ))
+}
+
+#[utoipa::path(
+tag = "Export users",
+context_path = "/api/user",
+params(
+("Accept" = Option<String>, Header, description = "`text/csv` (default) or `application/x-ndjson`"),
+("per_page" = Option<usize>, Query, description = "Number of users read from the database at once")
+),
+responses((status=200, description = "Users export succeed, the users matching the filters of the list are streamed",
+content_type = ["text/csv", "application/x-ndjson"]),
+(status=400, description = "Filters not correct"),
+(status=401, description = "Authentication required"),
+(status=403, description = "Permission user:restore required by include_deleted"),
+(status=406, description = "Accept header allows neither text/csv nor application/x-ndjson")
+),
+security(("api_jwt_token" = []))
+)]
+#[get("/export")]
+pub(super) async fn export(
+    request: HttpRequest,
+    app_data: Data<Arc<AppState>>,
+    query: web::Query<QuerySearch>,
+    claims: Claims,
+) -> actix_web::Result<impl Responder> {
+    let format = Format::negotiate(&request)?;
+    authorize_include_deleted(&claims.sub, claims.tenant, &query, &app_data).await?;
+    // Users are read by chunks following the cursor of the list, whatever page is asked.
+    let mut query = query.into_inner();
+    query.before = None;
+    query.page = None;
+    query.default_per_page = app_data.settings.pagination.max_page_size;
+    query.max_per_page = app_data.settings.pagination.max_page_size;
+    // The first chunk is read before answering, so that a wrong search still gets its status.
+    let (users, after) = chunk(
+        &claims.sub,
+        claims.tenant,
+        query.clone(),
+        String::new(),
+        &app_data,
+    )
+    .await?;
+    let first = format.header() + format.write(&users)?.as_str();
+    let next = stream::unfold(after, move |after| {
+        let (actor, query, app_data) = (claims.sub.to_owned(), query.clone(), app_data.clone());
+        async move {
+            let after = after?;
+            let next = chunk(&actor, claims.tenant, query, after, &app_data)
+                .await
+                .and_then(|(users, after)| Ok((format.write(&users)?, after)));
+            Some(match next {
+                Ok((lines, after)) => (Ok(Bytes::from(lines)), after),
+                Err(error) => (Err(error), None),
+            })
+        }
+    });
+    Ok(HttpResponse::Ok()
+        .content_type(format.content_type())
+        .insert_header(ContentDisposition {
+            disposition: DispositionType::Attachment,
+            parameters: vec![DispositionParam::Filename(String::from(format.filename()))],
+        })
+        .streaming(stream::once(async move { Ok(Bytes::from(first)) }).chain(next)))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{access_token, administrator_id, app_state, test_settings};
+    use crate::user::export::{csv_field, Format};
+    use crate::user::{init, NewUser, User};
+    use actix_web::http::header::{ACCEPT, AUTHORIZATION, CONTENT_TYPE};
+    use actix_web::http::StatusCode;
+    use actix_web::web::Data;
+    use actix_web::{test, web, App};
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn test_user_export() {
+        let state = app_state(test_settings()).await;
+        let authorization = format!(
+            "Bearer {}",
+            access_token(&state.settings, &administrator_id(&state).await, 60)
+        );
+
+        let app = test::init_service(
+            App::new()
+                .app_data(Data::new(Arc::clone(&state)))
+                .service(web::scope("/api/user").configure(init)),
+        )
+        .await;
+
+        let mut users = Vec::new();
+        for (first_name, email) in [
+            ("Xavier", "xavier@export.example.com"),
+            ("Yves, \"Junior\"", "yves@export.example.com"),
+            ("Zoe", "zoe@export.example.com"),
+        ] {
+            let req = test::TestRequest::post()
+                .uri("/api/user")
+                .insert_header((AUTHORIZATION, authorization.as_str()))
+                .set_json(NewUser {
+                    first_name: first_name.to_string(),
+                    last_name: "Export".to_string(),
+                    email: email.to_string(),
+                    password: None,
+                })
+                .to_request();
+            let user: User = test::call_and_read_body_json(&app, req).await;
+            users.push(user);
+        }
+        let search = "/api/user/export?pattern=@export.example.com&sort=email&per_page=1";
+
+        // test 200, CSV is written by default, one chunk of users after another
+        {
+            let req = test::TestRequest::get()
+                .uri(search)
+                .insert_header((AUTHORIZATION, authorization.as_str()))
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::OK);
+            assert_eq!(
+                resp.headers().get(CONTENT_TYPE).unwrap(),
+                "text/csv; charset=utf-8"
+            );
+            let body = test::read_body(resp).await;
+            let lines: Vec<&str> = std::str::from_utf8(&body).unwrap().split("\r\n").collect();
+            assert_eq!(
+                lines,
+                vec![
+                    "id,first_name,last_name,email,version,deleted_at",
+                    format!("{},Xavier,Export,xavier@export.example.com,1,", users[0].id).as_str(),
+                    format!(
+                        "{},\"Yves, \"\"Junior\"\"\",Export,yves@export.example.com,1,",
+                        users[1].id
+                    )
+                    .as_str(),
+                    format!("{},Zoe,Export,zoe@export.example.com,1,", users[2].id).as_str(),
+                    ""
+                ]
+            );
+        }
+
+        // test 200, NDJSON holds one user per line
+        {
+            let req = test::TestRequest::get()
+                .uri(search)
+                .insert_header((AUTHORIZATION, authorization.as_str()))
+                .insert_header((ACCEPT, "application/x-ndjson"))
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::OK);
+            let body = test::read_body(resp).await;
+            let exported: Vec<User> = std::str::from_utf8(&body)
+                .unwrap()
+                .lines()
+                .map(|line| serde_json::from_str(line).unwrap())
+                .collect();
+            assert_eq!(
+                exported.iter().map(|user| user.id).collect::<Vec<_>>(),
+                users.iter().map(|user| user.id).collect::<Vec<_>>()
+            );
+        }
+
+        // test 406, only CSV and NDJSON are written
+        {
+            let req = test::TestRequest::get()
+                .uri(search)
+                .insert_header((AUTHORIZATION, authorization.as_str()))
+                .insert_header((ACCEPT, "application/xml"))
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::NOT_ACCEPTABLE);
+        }
+
+        // test 400, filters are checked like the list ones
+        {
+            let req = test::TestRequest::get()
+                .uri("/api/user/export?sort=password")
+                .insert_header((AUTHORIZATION, authorization.as_str()))
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_csv_field() {
+        assert_eq!(csv_field("Dupont"), "Dupont");
+        assert_eq!(csv_field("Dupont, Jean"), "\"Dupont, Jean\"");
+        assert_eq!(csv_field("Jean \"JJ\""), "\"Jean \"\"JJ\"\"\"");
+        assert_eq!(csv_field("a\r\nb"), "\"a\r\nb\"");
+        assert_eq!(csv_field("Jean-Luc"), "Jean-Luc");
+        // formulas are neutralized
+        assert_eq!(csv_field("=1+2"), "\"'=1+2\"");
+        assert_eq!(csv_field("+33 1"), "\"'+33 1\"");
+        assert_eq!(csv_field("-2"), "\"'-2\"");
+        assert_eq!(csv_field("@SUM(A1)"), "\"'@SUM(A1)\"");
+        assert_eq!(csv_field("\t=1"), "\"'\t=1\"");
+        assert_eq!(csv_field("\r=1"), "\"'\r=1\"");
+        assert_eq!(
+            csv_field("=HYPERLINK(\"http://x\")"),
+            "\"'=HYPERLINK(\"\"http://x\"\")\""
+        );
+    }
+
+    #[tokio::test]
+    async fn test_format_negotiate() {
+        let negotiate = |accept: Option<&str>| {
+            let mut request = test::TestRequest::default();
+            if let Some(accept) = accept {
+                request = request.insert_header((ACCEPT, accept));
+            }
+            Format::negotiate(&request.to_http_request())
+        };
+        assert_eq!(negotiate(None).unwrap(), Format::Csv);
+        assert_eq!(negotiate(Some("*/*")).unwrap(), Format::Csv);
+        assert_eq!(negotiate(Some("text/*")).unwrap(), Format::Csv);
+        assert_eq!(
+            negotiate(Some("application/x-ndjson")).unwrap(),
+            Format::Ndjson
+        );
+        // the preferred media type wins, the unsupported ones are skipped
+        assert_eq!(
+            negotiate(Some("text/csv;q=0.5, application/x-ndjson")).unwrap(),
+            Format::Ndjson
+        );
+        assert_eq!(
+            negotiate(Some("application/json, text/csv;q=0.1")).unwrap(),
+            Format::Csv
+        );
+        assert!(negotiate(Some("application/json")).is_err());
+    }
+}
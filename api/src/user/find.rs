@@ -0,0 +1,169 @@
+use crate::auth::Claims;
+use crate::cache::Representation;
+use crate::error::AsHttpError;
+use crate::user::{entity_tag, User};
+use crate::{AppState, Management};
+use actix_web::web::{Data, Path};
+use actix_web::{get, HttpRequest, HttpResponse, Responder};
+use services_local::authorization::Authorized;
+use services_local::user::UserManagement;
+use std::sync::Arc;
+
+#[utoipa::path(
+tag = "Read user",
+context_path = "/api/user",
+params(
+("user_id" = Uuid, Path, description = "User identifier")
+),
+responses((status=200, description = "User read succeed", body = User,
+headers(("ETag" = String, description = "Version of the user"),
+("Last-Modified" = String, description = "Date of the last change of the user"),
+("Cache-Control" = String, description = "Private, revalidated after `application.cache_max_age` seconds"))),
+(status=304, description = "The user did not change since the ETag or the date of the client copy"),
+(status=400, description = "User identifier not correct"),
+(status=401, description = "Authentication required"),
+(status=404, description = "User not found")
+),
+security(("api_jwt_token" = []))
+)]
+#[get("/{user_id}")]
+pub(super) async fn find(
+    request: HttpRequest,
+    user_id: Path<String>,
+    claims: Claims,
+    app_data: Data<Arc<AppState>>,
+) -> actix_web::Result<impl Responder> {
+    let user_id = user_id.into_inner();
+    let management: Management<_, _, _, _, _, _> = Management(Box::new(Authorized::new(
+        Box::new(claims.sub),
+        claims.tenant,
+        "user",
+        UserManagement::new(claims.tenant),
+    )));
+    let response = management
+        .0
+        .find(Box::new(user_id), false, &app_data.db_connection)
+        .await
+        .map_err(|e| AsHttpError::from(e.get_core_error()))?;
+    let last_modified = response.get_last_modified();
+    let response = User::from(response);
+    Ok(Representation::new(&response, last_modified)?
+        .with_etag(entity_tag(response.version).0)
+        .respond(&request, &app_data.settings.application, HttpResponse::Ok()))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{access_token, administrator_id, app_state, test_settings};
+    use crate::user::create::create;
+    use crate::user::find::find;
+    use crate::user::{NewUser, User};
+    use actix_web::http::header::{AUTHORIZATION, ETAG, IF_NONE_MATCH, LAST_MODIFIED};
+    use actix_web::http::StatusCode;
+    use actix_web::web::Data;
+    use actix_web::{test, web, App};
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn test_user_find() {
+        let state = app_state(test_settings()).await;
+        let authorization = format!(
+            "Bearer {}",
+            access_token(&state.settings, &administrator_id(&state).await, 60)
+        );
+
+        let app = test::init_service(
+            App::new()
+                .app_data(Data::new(Arc::clone(&state)))
+                .service(web::scope("/api/user").service(create).service(find)),
+        )
+        .await;
+
+        let new_user = NewUser {
+            first_name: "Paul".to_string(),
+            last_name: "Henri".to_string(),
+            email: "paulhenri@example.com".to_string(),
+            password: None,
+        };
+        let req = test::TestRequest::post()
+            .uri("/api/user")
+            .insert_header((AUTHORIZATION, authorization.as_str()))
+            .set_json(&new_user)
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        let user: User = test::read_body_json(resp).await;
+
+        let path = format!("/api/user/{}", user.id);
+
+        // test 200, a user can read another one
+        {
+            let req = test::TestRequest::get()
+                .uri(format!("/api/user/{}", administrator_id(&state).await).as_str())
+                .insert_header((
+                    AUTHORIZATION,
+                    format!(
+                        "Bearer {}",
+                        access_token(&state.settings, &user.id.to_string(), 60)
+                    ),
+                ))
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::OK);
+            let administrator: User = test::read_body_json(resp).await;
+            assert_eq!(administrator.email, "admin@example.com");
+        }
+
+        // test 200 then 304 with the ETag of the version
+        {
+            let req = test::TestRequest::get()
+                .uri(path.as_str())
+                .insert_header((AUTHORIZATION, authorization.as_str()))
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::OK);
+            assert_eq!(resp.headers().get(ETAG).unwrap(), "\"1\"");
+            assert!(resp.headers().contains_key(LAST_MODIFIED));
+            let found_user: User = test::read_body_json(resp).await;
+            assert_eq!(found_user.id, user.id);
+            assert_eq!(found_user.first_name, user.first_name);
+            assert_eq!(found_user.email, user.email);
+
+            let req = test::TestRequest::get()
+                .uri(path.as_str())
+                .insert_header((AUTHORIZATION, authorization.as_str()))
+                .insert_header((IF_NONE_MATCH, "\"1\""))
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::NOT_MODIFIED);
+        }
+
+        // test 404
+        {
+            let req = test::TestRequest::get()
+                .uri(format!("/api/user/{}", uuid::Uuid::new_v4()).as_str())
+                .insert_header((AUTHORIZATION, authorization.as_str()))
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+            let response: serde_json::Value = test::read_body_json(resp).await;
+            assert_eq!(response["code"], "not_found");
+        }
+
+        // test 400
+        {
+            let req = test::TestRequest::get()
+                .uri("/api/user/not-an-id")
+                .insert_header((AUTHORIZATION, authorization.as_str()))
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+        }
+
+        // test 401
+        {
+            let req = test::TestRequest::get().uri(path.as_str()).to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+        }
+    }
+}
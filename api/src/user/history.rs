@@ -0,0 +1,149 @@
+use crate::audit::read::{search, QueryAudit};
+use crate::auth::Claims;
+use crate::AppState;
+use actix_web::web::{Data, Path, Query};
+use actix_web::{get, HttpResponse, Responder};
+use serde::Deserialize;
+use std::sync::Arc;
+
+#[derive(Deserialize)]
+struct QueryHistory {
+    page: Option<usize>,
+    per_page: Option<usize>,
+}
+
+#[utoipa::path(
+tag = "Audit",
+context_path = "/api/user",
+params(
+("user_id" = Uuid, Path, description = "User identifier"),
+("page" = Option<usize>, Query, description = "Page number, starting from 1"),
+("per_page" = Option<usize>, Query, description = "Number of events per page")
+),
+responses((status=200, description = "User history succeed", body = AuditSearchResult),
+(status=401, description = "Authentication required"),
+(status=403, description = "Permission audit:read required")
+),
+security(("api_jwt_token" = []))
+)]
+#[get("/{user_id}/history")]
+pub(super) async fn history(
+    user_id: Path<String>,
+    query: Query<QueryHistory>,
+    claims: Claims,
+    app_data: Data<Arc<AppState>>,
+) -> actix_web::Result<impl Responder> {
+    let user_id = user_id.into_inner();
+    let query = query.into_inner();
+    let response = search(
+        claims.sub,
+        claims.tenant,
+        Some(Box::new(user_id.to_owned())),
+        QueryAudit {
+            resource: Some(String::from("user")),
+            entity_id: Some(user_id),
+            page: query.page,
+            per_page: query.per_page,
+            ..Default::default()
+        },
+        &app_data,
+    )
+    .await?;
+    Ok(HttpResponse::Ok().json(response))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::audit::AuditSearchResult;
+    use crate::tests::{access_token, administrator_id, app_state, test_settings};
+    use crate::user::create::create;
+    use crate::user::history::history;
+    use crate::user::update::update;
+    use crate::user::{NewUser, User};
+    use actix_web::http::header::AUTHORIZATION;
+    use actix_web::http::StatusCode;
+    use actix_web::web::Data;
+    use actix_web::{test, web, App};
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn test_user_history() {
+        let state = app_state(test_settings()).await;
+        let administrator = administrator_id(&state).await;
+        let authorization = format!(
+            "Bearer {}",
+            access_token(&state.settings, &administrator, 60)
+        );
+
+        let app = test::init_service(
+            App::new().app_data(Data::new(Arc::clone(&state))).service(
+                web::scope("/api/user")
+                    .service(create)
+                    .service(update)
+                    .service(history),
+            ),
+        )
+        .await;
+
+        let new_user = NewUser {
+            first_name: "Hector".to_string(),
+            last_name: "Story".to_string(),
+            email: "hectorstory@example.com".to_string(),
+            password: None,
+        };
+        let req = test::TestRequest::post()
+            .uri("/api/user")
+            .insert_header((AUTHORIZATION, authorization.as_str()))
+            .set_json(&new_user)
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        let user: User = test::read_body_json(resp).await;
+        let path = format!("/api/user/{}", user.id);
+
+        let req = test::TestRequest::put()
+            .uri(path.as_str())
+            .insert_header((AUTHORIZATION, authorization.as_str()))
+            .insert_header(("X-Request-Id", "history-update"))
+            .set_json(User {
+                email: "hector.story@example.com".to_string(),
+                ..user.clone()
+            })
+            .to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), StatusCode::OK);
+
+        // test 200, latest change first with the fields it changed
+        {
+            let req = test::TestRequest::get()
+                .uri(format!("{}/history", path).as_str())
+                .insert_header((AUTHORIZATION, authorization.as_str()))
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::OK);
+            let events: AuditSearchResult = test::read_body_json(resp).await;
+            assert_eq!(events.total_items, 2);
+            let event = &events.result[0];
+            assert_eq!(event.action, "update");
+            assert_eq!(event.actor, administrator);
+            assert_eq!(event.entity_id, user.id.to_string());
+            assert_eq!(event.request_id.as_deref(), Some("history-update"));
+            assert_eq!(event.changes["email"]["before"], "hectorstory@example.com");
+            assert_eq!(event.changes["email"]["after"], "hector.story@example.com");
+            assert!(event.changes.get("first_name").is_none());
+            assert_eq!(events.result[1].action, "create");
+        }
+
+        // test 403, the history is reserved to auditors
+        {
+            let user_authorization = format!(
+                "Bearer {}",
+                access_token(&state.settings, &user.id.to_string(), 60)
+            );
+            let req = test::TestRequest::get()
+                .uri(format!("{}/history", path).as_str())
+                .insert_header((AUTHORIZATION, user_authorization.as_str()))
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+        }
+    }
+}
@@ -0,0 +1,924 @@
+use crate::audit::request_id;
+use crate::auth::Claims;
+use crate::error::AsHttpError;
+use crate::user::create::{deleted_email, insert};
+use crate::user::export::csv_field;
+use crate::user::{audited, NewUser, User};
+use crate::{AppState, Authentication, Authorization, Import, Management};
+use actix_web::http::header::{ContentDisposition, DispositionParam, DispositionType, LOCATION};
+use actix_web::web::{Data, Path, Payload, Query};
+use actix_web::{get, post, HttpMessage, HttpRequest, HttpResponse, Responder};
+use chrono::{DateTime, Utc};
+use common::error::{CoreError, FieldError};
+use common::import::{ImportProgress, RowError, RowOutcome};
+use common::management::Precondition;
+use futures::StreamExt;
+use log::error;
+use sea_orm::{DatabaseTransaction, TransactionTrait};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use services_local::authorization::RoleManagement;
+use services_local::credential::CredentialManagement;
+use services_local::import::ImportManagement;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use utoipa::ToSchema;
+use uuid::Uuid;
+use validator::Validate;
+
+/// Fields of `NewUser` a column can be mapped to
+const FIELDS: [&str; 4] = ["first_name", "last_name", "email", "password"];
+
+/// Fields every CSV import must hold a column for
+const REQUIRED_FIELDS: [&str; 3] = ["first_name", "last_name", "email"];
+
+#[derive(Deserialize)]
+pub(super) struct QueryImport {
+    /// Check and apply every row, then undo them all
+    #[serde(default)]
+    dry_run: bool,
+    /// Update the user already owning the email of a row instead of failing
+    #[serde(default)]
+    upsert: bool,
+    /// Comma separated `column:field` pairs, e.g. `Mail:email,Surname:last_name`
+    columns: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, ToSchema, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub(super) enum ImportStatus {
+    Running,
+    Completed,
+    /// Stopped before the last row, the rows already imported are kept
+    Failed,
+}
+
+impl From<common::import::ImportStatus> for ImportStatus {
+    fn from(value: common::import::ImportStatus) -> Self {
+        match value {
+            common::import::ImportStatus::Running => Self::Running,
+            common::import::ImportStatus::Completed => Self::Completed,
+            common::import::ImportStatus::Failed => Self::Failed,
+        }
+    }
+}
+
+/// Progress of an import
+#[derive(Serialize, Deserialize, ToSchema, Debug, Clone)]
+pub(super) struct ImportJob {
+    pub id: Uuid,
+    pub status: ImportStatus,
+    pub dry_run: bool,
+    pub upsert: bool,
+    pub total_rows: usize,
+    pub processed_rows: usize,
+    pub created: usize,
+    pub updated: usize,
+    pub failed: usize,
+    pub created_at: DateTime<Utc>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub finished_at: Option<DateTime<Utc>>,
+}
+
+impl From<Box<dyn ImportProgress>> for ImportJob {
+    fn from(value: Box<dyn ImportProgress>) -> Self {
+        Self {
+            id: Uuid::parse_str(value.get_id().as_str()).unwrap_or_default(),
+            status: ImportStatus::from(value.get_status()),
+            dry_run: value.get_dry_run(),
+            upsert: value.get_upsert(),
+            total_rows: value.get_total_rows(),
+            processed_rows: value.get_processed_rows(),
+            created: value.get_created(),
+            updated: value.get_updated(),
+            failed: value.get_failed(),
+            created_at: value.get_created_at(),
+            finished_at: value.get_finished_at(),
+        }
+    }
+}
+
+/// Row of the file read as a user, or the reason it could not be read
+struct Row {
+    line: u64,
+    user: Result<NewUser, AsHttpError>,
+}
+
+enum Imported {
+    Created,
+    Updated,
+}
+
+/// Media types an import is read from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Format {
+    Csv,
+    Ndjson,
+}
+
+impl Format {
+    fn from_request(request: &HttpRequest) -> Result<Self, AsHttpError> {
+        let unsupported = || {
+            AsHttpError::from(CoreError::UnsupportedMediaType(String::from(
+                "Users are imported from text/csv or application/x-ndjson",
+            )))
+        };
+        match request.mime_type().map_err(|_| unsupported())? {
+            Some(mime) if mime.essence_str() == "text/csv" => Ok(Self::Csv),
+            Some(mime) if mime.essence_str() == "application/x-ndjson" => Ok(Self::Ndjson),
+            _ => Err(unsupported()),
+        }
+    }
+
+    fn rows(
+        &self,
+        body: &[u8],
+        mapping: &HashMap<String, String>,
+    ) -> Result<Vec<Row>, AsHttpError> {
+        match self {
+            Self::Csv => csv_rows(body, mapping),
+            Self::Ndjson => ndjson_rows(body, mapping),
+        }
+    }
+}
+
+/// Columns given by `columns`, by the field they are mapped to
+fn mapping(columns: Option<&str>) -> Result<HashMap<String, String>, AsHttpError> {
+    columns
+        .unwrap_or_default()
+        .split(',')
+        .filter(|pair| !pair.trim().is_empty())
+        .map(|pair| match pair.split_once(':') {
+            Some((column, field)) if FIELDS.contains(&field.trim()) => {
+                Ok((column.trim().to_owned(), field.trim().to_owned()))
+            }
+            _ => Err(AsHttpError::from(CoreError::DataError(format!(
+                "{} is not a column:field pair, fields are {}",
+                pair,
+                FIELDS.join(", ")
+            )))),
+        })
+        .collect()
+}
+
+/// Field `column` is mapped to, by `mapping` or by its name
+fn field<'f>(mapping: &'f HashMap<String, String>, column: &str) -> Option<&'f str> {
+    match mapping.get(column.trim()) {
+        Some(field) => Some(field.as_str()),
+        None => FIELDS
+            .iter()
+            .find(|field| field.eq_ignore_ascii_case(column.trim()))
+            .copied(),
+    }
+}
+
+fn new_user<'v>(values: impl Iterator<Item = (&'v str, String)>) -> NewUser {
+    values.fold(NewUser::default(), |mut user, (field, value)| {
+        match field {
+            "first_name" => user.first_name = value,
+            "last_name" => user.last_name = value,
+            "email" => user.email = value,
+            _ => user.password = Some(value).filter(|password| !password.is_empty()),
+        }
+        user
+    })
+}
+
+fn csv_rows(body: &[u8], mapping: &HashMap<String, String>) -> Result<Vec<Row>, AsHttpError> {
+    let mut reader = csv::ReaderBuilder::new()
+        .trim(csv::Trim::All)
+        .from_reader(body);
+    let fields: Vec<Option<&str>> = reader
+        .headers()
+        .map_err(|e| AsHttpError::from(CoreError::DataError(e.to_string())))?
+        .iter()
+        .map(|column| field(mapping, column))
+        .collect();
+    if let Some(missing) = REQUIRED_FIELDS
+        .iter()
+        .find(|required| !fields.contains(&Some(**required)))
+    {
+        return Err(AsHttpError::from(CoreError::DataError(format!(
+            "No column is mapped to {}",
+            missing
+        ))));
+    }
+    // Lines are counted from the byte offsets, those of the reader drift with CRLF line breaks
+    // which also leave the offset of a record on the `\n` ending the previous one.
+    let mut counted = (0, 1);
+    let mut line = |position: Option<&csv::Position>| {
+        let byte = position.map_or(counted.0, |position| {
+            (position.byte() as usize + 1).min(body.len())
+        });
+        counted.1 += body[counted.0..byte]
+            .iter()
+            .filter(|byte| **byte == b'\n')
+            .count() as u64;
+        counted.0 = byte;
+        counted.1
+    };
+    Ok(reader
+        .records()
+        .map(|record| match record {
+            Ok(record) => Row {
+                line: line(record.position()),
+                user: Ok(new_user(fields.iter().zip(record.iter()).filter_map(
+                    |(field, value)| field.map(|field| (field, value.to_owned())),
+                ))),
+            },
+            Err(e) => Row {
+                line: line(e.position()),
+                user: Err(AsHttpError::from(CoreError::DataError(e.to_string()))),
+            },
+        })
+        .collect())
+}
+
+fn ndjson_rows(body: &[u8], mapping: &HashMap<String, String>) -> Result<Vec<Row>, AsHttpError> {
+    let body = std::str::from_utf8(body)
+        .map_err(|e| AsHttpError::from(CoreError::DataError(e.to_string())))?;
+    Ok(body
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(index, line)| Row {
+            line: index as u64 + 1,
+            user: serde_json::from_str::<serde_json::Map<String, Value>>(line)
+                .map(|object| {
+                    new_user(object.iter().filter_map(|(key, value)| {
+                        let value = match value {
+                            Value::Null => return None,
+                            Value::String(value) => value.trim().to_owned(),
+                            value => value.to_string(),
+                        };
+                        field(mapping, key).map(|field| (field, value))
+                    }))
+                })
+                .map_err(|e| AsHttpError::from(CoreError::DataError(e.to_string()))),
+        })
+        .collect())
+}
+
+/// Lines of the report explaining why the row at `line` failed
+fn report(line: u64, error: &AsHttpError) -> Vec<RowError> {
+    let problem = error.problem();
+    let status = problem["status"].as_u64().unwrap_or_default() as u16;
+    let code = problem["code"].as_str().unwrap_or_default().to_owned();
+    match problem["errors"].as_array() {
+        Some(errors) => errors
+            .iter()
+            .map(|field_error| {
+                RowError::new(
+                    line,
+                    status,
+                    field_error["code"].as_str().unwrap_or(code.as_str()),
+                    field_error["field"].as_str(),
+                    field_error["message"].as_str().unwrap_or_default(),
+                )
+            })
+            .collect(),
+        None => vec![RowError::new(
+            line,
+            status,
+            code.as_str(),
+            None,
+            problem["detail"].as_str().unwrap_or_default(),
+        )],
+    }
+}
+
+/// Create `user` in the organization `tenant`, or update the user owning its email there when `upsert` is set.
+async fn import_user(
+    actor: String,
+    tenant: Uuid,
+    request_id: Option<String>,
+    mut user: NewUser,
+    upsert: bool,
+    transaction: &DatabaseTransaction,
+) -> Result<Imported, AsHttpError> {
+    let authentication: Authentication<_, _, _> =
+        Authentication(Box::new(CredentialManagement::new(tenant)));
+    let existing = match upsert {
+        true => authentication
+            .0
+            .identify(user.email.to_owned(), transaction)
+            .await
+            .map_err(|e| AsHttpError::from(e.get_core_error()))?,
+        false => None,
+    };
+    let Some(existing) = existing else {
+        return insert(actor, tenant, request_id, user, transaction)
+            .await
+            .map(|_| Imported::Created);
+    };
+    user.validate().map_err(AsHttpError::from)?;
+    if existing.get_deleted_at().is_some() {
+        return Err(deleted_email());
+    }
+    let password = user.password.take();
+    let management: Management<_, _, _, _, _, _> =
+        Management(Box::new(audited(actor, tenant, request_id)));
+    let updated = management
+        .0
+        .update(
+            existing.get_id(),
+            Box::new(User {
+                first_name: user.first_name,
+                last_name: user.last_name,
+                email: user.email,
+                ..User::from(existing)
+            }),
+            Precondition::Any,
+            transaction,
+        )
+        .await
+        .map_err(|e| AsHttpError::from(e.get_core_error()))?;
+    if let Some(password) = password {
+        authentication
+            .0
+            .set_password(updated.get_id(), password, transaction)
+            .await
+            .map_err(|e| AsHttpError::from(e.get_core_error()))?;
+    }
+    Ok(Imported::Updated)
+}
+
+/// Import `rows` in the organization `tenant` on behalf of `actor`, recording the progress in the job `job_id`.
+/// Each row runs in its own short transaction, committed unless `dry_run` rolls it back.
+async fn process(
+    job_id: Uuid,
+    actor: String,
+    tenant: Uuid,
+    request_id: Option<String>,
+    rows: Vec<Row>,
+    query: QueryImport,
+    app_data: &AppState,
+) -> Result<(), AsHttpError> {
+    let imports: Import<_, _> = Import(Box::new(ImportManagement::new(tenant)));
+    // Emails of the rows a dry run accepted, the database does not see them to refuse the duplicates.
+    let mut accepted = HashSet::new();
+    for row in rows {
+        let outcome = match row.user {
+            Ok(user) => {
+                let email = user.email.to_owned();
+                let transaction = app_data
+                    .db_connection
+                    .begin()
+                    .await
+                    .map_err(|e| AsHttpError::from(CoreError::UnkownError(e.to_string())))?;
+                let outcome = import_user(
+                    actor.to_owned(),
+                    tenant,
+                    request_id.to_owned(),
+                    user,
+                    query.upsert,
+                    &transaction,
+                )
+                .await;
+                match outcome.is_ok() && !query.dry_run {
+                    true => transaction.commit().await,
+                    false => transaction.rollback().await,
+                }
+                .map_err(|e| AsHttpError::from(CoreError::UnkownError(e.to_string())))?;
+                let duplicate = query.dry_run && outcome.is_ok() && !accepted.insert(email);
+                match outcome {
+                    Ok(_) if duplicate && query.upsert => Ok(Imported::Updated),
+                    Ok(_) if duplicate => Err(AsHttpError::from(CoreError::Conflict(
+                        FieldError::new("email", "already_exists", "email is already used"),
+                    ))),
+                    outcome => outcome,
+                }
+            }
+            Err(error) => Err(error),
+        };
+        let outcome = match outcome {
+            Ok(Imported::Created) => RowOutcome::Created,
+            Ok(Imported::Updated) => RowOutcome::Updated,
+            Err(error) => RowOutcome::Failed(report(row.line, &error)),
+        };
+        imports
+            .0
+            .progress(job_id, outcome, &app_data.db_connection)
+            .await
+            .map_err(|e| AsHttpError::from(e.get_core_error()))?;
+    }
+    Ok(())
+}
+
+/// Run `process` and record how it ended
+async fn run(
+    job_id: Uuid,
+    actor: String,
+    tenant: Uuid,
+    request_id: Option<String>,
+    rows: Vec<Row>,
+    query: QueryImport,
+    app_data: Arc<AppState>,
+) {
+    let outcome = process(job_id, actor, tenant, request_id, rows, query, &app_data).await;
+    let status = match &outcome {
+        Ok(_) => common::import::ImportStatus::Completed,
+        Err(e) => {
+            error!("import {} stopped: {}", job_id, e);
+            common::import::ImportStatus::Failed
+        }
+    };
+    let imports: Import<_, _> = Import(Box::new(ImportManagement::new(tenant)));
+    // Left running, the job is failed once polled after its rows stopped being counted.
+    if let Err(e) = imports
+        .0
+        .finish(job_id, status, &app_data.db_connection)
+        .await
+    {
+        error!("import {} not finished: {}", job_id, e.get_core_error());
+    }
+}
+
+/// Job `job_id` of the organization `tenant` if it was started by `actor`
+async fn job(
+    job_id: Uuid,
+    actor: String,
+    tenant: Uuid,
+    app_data: &AppState,
+) -> Result<ImportJob, AsHttpError> {
+    let imports: Import<_, _> = Import(Box::new(ImportManagement::new(tenant)));
+    imports
+        .0
+        .find(job_id, actor, &app_data.db_connection)
+        .await
+        .map(ImportJob::from)
+        .map_err(|e| AsHttpError::from(e.get_core_error()))
+}
+
+/// Read the uploaded file, refused once larger than `max_size` bytes
+async fn body(mut payload: Payload, max_size: usize) -> Result<Vec<u8>, AsHttpError> {
+    let mut body = Vec::new();
+    while let Some(chunk) = payload.next().await {
+        let chunk = chunk.map_err(|e| AsHttpError::from(CoreError::DataError(e.to_string())))?;
+        if body.len() + chunk.len() > max_size {
+            return Err(AsHttpError::from(CoreError::DataError(format!(
+                "An import holds at most {} bytes",
+                max_size
+            ))));
+        }
+        body.extend_from_slice(&chunk);
+    }
+    Ok(body)
+}
+
+#[utoipa::path(
+tag = "Import users",
+context_path = "/api/user",
+params(
+("dry_run" = Option<bool>, Query, description = "Check every row without keeping any user"),
+("upsert" = Option<bool>, Query, description = "Update the user already owning the email of a row"),
+("columns" = Option<String>, Query, description = "Comma separated `column:field` pairs, columns named after a field are mapped to it")
+),
+request_body(content = String, description = "Users with the columns first_name, last_name, email and password, \
+or as application/x-ndjson with one object holding these keys per line", content_type = "text/csv"),
+responses((status=200, description = "Import done", body = ImportJob, headers(("Location" = String, description = "Status of the import"))),
+(status=202, description = "Import running in the background", body = ImportJob, headers(("Location" = String, description = "Status of the import"))),
+(status=400, description = "File not readable, larger than `application.import_max_size` or columns not correct"),
+(status=401, description = "Authentication required"),
+(status=403, description = "Permission user:create required"),
+(status=415, description = "Content-Type neither text/csv nor application/x-ndjson")
+),
+security(("api_jwt_token" = []))
+)]
+#[post("/import")]
+pub(super) async fn import(
+    request: HttpRequest,
+    payload: Payload,
+    query: Query<QueryImport>,
+    claims: Claims,
+    app_data: Data<Arc<AppState>>,
+) -> actix_web::Result<impl Responder> {
+    let query = query.into_inner();
+    let format = Format::from_request(&request)?;
+    let mapping = mapping(query.columns.as_deref())?;
+    let authorization: Authorization<_, _> =
+        Authorization(Box::new(RoleManagement::new(claims.tenant)));
+    authorization
+        .0
+        .authorize(
+            Box::new(claims.sub.to_owned()),
+            String::from("user:create"),
+            None,
+            &app_data.db_connection,
+        )
+        .await
+        .map_err(|e| AsHttpError::from(e.get_core_error()))?;
+    let body = body(payload, app_data.settings.application.import_max_size).await?;
+    let rows = format.rows(&body, &mapping)?;
+    let imports: Import<_, _> = Import(Box::new(ImportManagement::new(claims.tenant)));
+    let job = imports
+        .0
+        .start(
+            claims.sub.to_owned(),
+            query.dry_run,
+            query.upsert,
+            rows.len(),
+            &app_data.db_connection,
+        )
+        .await
+        .map(ImportJob::from)
+        .map_err(|e| AsHttpError::from(e.get_core_error()))?;
+    let location = format!("/api/user/import/{}", job.id);
+    let background = rows.len() > app_data.settings.application.import_max_sync_rows;
+    let task = run(
+        job.id,
+        claims.sub.to_owned(),
+        claims.tenant,
+        request_id(&request),
+        rows,
+        query,
+        Arc::clone(&app_data),
+    );
+    if background {
+        tokio::spawn(task);
+        return Ok(HttpResponse::Accepted()
+            .insert_header((LOCATION, location))
+            .json(job));
+    }
+    task.await;
+    Ok(HttpResponse::Ok()
+        .insert_header((LOCATION, location))
+        .json(self::job(job.id, claims.sub, claims.tenant, &app_data).await?))
+}
+
+#[utoipa::path(
+tag = "Import users",
+context_path = "/api/user",
+params(("job_id" = Uuid, Path, description = "Import identifier")),
+responses((status=200, description = "Import status succeed", body = ImportJob),
+(status=401, description = "Authentication required"),
+(status=404, description = "Import not found, expired or started by another user")
+),
+security(("api_jwt_token" = []))
+)]
+#[get("/import/{job_id}")]
+pub(super) async fn import_status(
+    job_id: Path<Uuid>,
+    claims: Claims,
+    app_data: Data<Arc<AppState>>,
+) -> actix_web::Result<impl Responder> {
+    Ok(HttpResponse::Ok()
+        .json(job(job_id.into_inner(), claims.sub, claims.tenant, &app_data).await?))
+}
+
+#[utoipa::path(
+tag = "Import users",
+context_path = "/api/user",
+params(("job_id" = Uuid, Path, description = "Import identifier")),
+responses((status=200, description = "CSV report of the failed rows, with the columns line, status, code, field and message",
+content_type = "text/csv"),
+(status=401, description = "Authentication required"),
+(status=404, description = "Import not found, expired or started by another user")
+),
+security(("api_jwt_token" = []))
+)]
+#[get("/import/{job_id}/errors")]
+pub(super) async fn import_errors(
+    job_id: Path<Uuid>,
+    claims: Claims,
+    app_data: Data<Arc<AppState>>,
+) -> actix_web::Result<impl Responder> {
+    let job_id = job_id.into_inner();
+    let imports: Import<_, _> = Import(Box::new(ImportManagement::new(claims.tenant)));
+    let errors = imports
+        .0
+        .errors(job_id, claims.sub, &app_data.db_connection)
+        .await
+        .map_err(|e| AsHttpError::from(e.get_core_error()))?;
+    let mut lines = String::from("line,status,code,field,message\r\n");
+    for error in errors {
+        lines.push_str(
+            format!(
+                "{},{},{},{},{}\r\n",
+                error.get_line(),
+                error.get_status(),
+                csv_field(error.get_code()),
+                csv_field(error.get_field().unwrap_or_default()),
+                csv_field(error.get_message())
+            )
+            .as_str(),
+        );
+    }
+    Ok(HttpResponse::Ok()
+        .content_type("text/csv; charset=utf-8")
+        .insert_header(ContentDisposition {
+            disposition: DispositionType::Attachment,
+            parameters: vec![DispositionParam::Filename(format!(
+                "import-{}-errors.csv",
+                job_id
+            ))],
+        })
+        .body(lines))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{access_token, administrator_id, app_state, test_settings};
+    use crate::user::import::{ImportJob, ImportStatus};
+    use crate::user::{init, NewUser, User, UserSearchResult};
+    use crate::{AppState, Import};
+    use actix_web::http::header::{AUTHORIZATION, CONTENT_TYPE, LOCATION};
+    use actix_web::http::StatusCode;
+    use actix_web::web::Data;
+    use actix_web::{test, web, App};
+    use chrono::{Duration, Utc};
+    use sea_orm::sea_query::{Alias, Expr, Query};
+    use sea_orm::ConnectionTrait;
+    use services_local::import::ImportManagement;
+    use services_local::organization::DEFAULT_ORGANIZATION_ID;
+    use std::sync::Arc;
+    use uuid::Uuid;
+
+    async fn state(import_max_sync_rows: usize) -> Arc<AppState> {
+        let mut settings = test_settings();
+        settings.application.import_max_sync_rows = import_max_sync_rows;
+        app_state(settings).await
+    }
+
+    #[tokio::test]
+    async fn test_user_import() {
+        let state = state(100).await;
+        let authorization = format!(
+            "Bearer {}",
+            access_token(&state.settings, &administrator_id(&state).await, 60)
+        );
+
+        let app = test::init_service(
+            App::new()
+                .app_data(Data::new(Arc::clone(&state)))
+                .service(web::scope("/api/user").configure(init)),
+        )
+        .await;
+
+        let req = test::TestRequest::post()
+            .uri("/api/user")
+            .insert_header((AUTHORIZATION, authorization.as_str()))
+            .set_json(NewUser {
+                first_name: "Ines".to_string(),
+                last_name: "Import".to_string(),
+                email: "ines@import.example.com".to_string(),
+                password: None,
+            })
+            .to_request();
+        let ines: User = test::call_and_read_body_json(&app, req).await;
+        let ines_authorization = format!(
+            "Bearer {}",
+            access_token(&state.settings, &ines.id.to_string(), 60)
+        );
+        let csv = "first_name,last_name,Mail,password\r\n\
+            Hugo,Import,hugo@import.example.com,\r\n\
+            ,Import,empty@import.example.com,\r\n\
+            Ines,Updated,ines@import.example.com,\r\n";
+        let find = |email: &str| {
+            test::TestRequest::get()
+                .uri(format!("/api/user?pattern={}&match_mode=exact", email).as_str())
+                .insert_header((AUTHORIZATION, authorization.as_str()))
+                .to_request()
+        };
+
+        // test 200, a dry run reports every row and keeps nothing
+        {
+            let req = test::TestRequest::post()
+                .uri("/api/user/import?dry_run=true&upsert=true&columns=Mail:email")
+                .insert_header((AUTHORIZATION, authorization.as_str()))
+                .insert_header((CONTENT_TYPE, "text/csv"))
+                .set_payload(csv)
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::OK);
+            let job: ImportJob = test::read_body_json(resp).await;
+            assert_eq!(job.status, ImportStatus::Completed);
+            assert_eq!(
+                (job.total_rows, job.created, job.updated, job.failed),
+                (3, 1, 1, 1)
+            );
+
+            let found: UserSearchResult =
+                test::call_and_read_body_json(&app, find("hugo@import.example.com")).await;
+            assert!(found.result.is_empty());
+            let found: UserSearchResult =
+                test::call_and_read_body_json(&app, find("ines@import.example.com")).await;
+            assert_eq!(found.result[0].last_name, "Import");
+        }
+
+        // test 200, a dry run reports the rows repeating an email like the database would
+        {
+            let repeated = "first_name,last_name,email\r\n\
+                Lise,Twice,lise@import.example.com\r\n\
+                Lise,Again,lise@import.example.com\r\n";
+            for (upsert, (created, updated, failed)) in [(false, (1, 0, 1)), (true, (1, 1, 0))] {
+                let req = test::TestRequest::post()
+                    .uri(format!("/api/user/import?dry_run=true&upsert={}", upsert).as_str())
+                    .insert_header((AUTHORIZATION, authorization.as_str()))
+                    .insert_header((CONTENT_TYPE, "text/csv"))
+                    .set_payload(repeated)
+                    .to_request();
+                let job: ImportJob = test::call_and_read_body_json(&app, req).await;
+                assert_eq!(
+                    (job.created, job.updated, job.failed),
+                    (created, updated, failed)
+                );
+            }
+            let found: UserSearchResult =
+                test::call_and_read_body_json(&app, find("lise@import.example.com")).await;
+            assert!(found.result.is_empty());
+        }
+
+        // test 200, the valid rows are kept and the others reported
+        {
+            let req = test::TestRequest::post()
+                .uri("/api/user/import?upsert=true&columns=Mail:email")
+                .insert_header((AUTHORIZATION, authorization.as_str()))
+                .insert_header((CONTENT_TYPE, "text/csv"))
+                .set_payload(csv)
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::OK);
+            let location = resp
+                .headers()
+                .get(LOCATION)
+                .unwrap()
+                .to_str()
+                .unwrap()
+                .to_owned();
+            let job: ImportJob = test::read_body_json(resp).await;
+            assert_eq!((job.created, job.updated, job.failed), (1, 1, 1));
+
+            let found: UserSearchResult =
+                test::call_and_read_body_json(&app, find("hugo@import.example.com")).await;
+            assert_eq!(found.result.len(), 1);
+            let found: UserSearchResult =
+                test::call_and_read_body_json(&app, find("ines@import.example.com")).await;
+            assert_eq!(found.result[0].last_name, "Updated");
+
+            let req = test::TestRequest::get()
+                .uri(format!("{}/errors", location).as_str())
+                .insert_header((AUTHORIZATION, authorization.as_str()))
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::OK);
+            let report = test::read_body(resp).await;
+            assert_eq!(
+                std::str::from_utf8(&report).unwrap(),
+                "line,status,code,field,message\r\n\
+                3,422,required,first_name,First name must not be empty\r\n"
+            );
+
+            // test 404, a job is only followed by the user who started it
+            let req = test::TestRequest::get()
+                .uri(location.as_str())
+                .insert_header((AUTHORIZATION, ines_authorization.as_str()))
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+        }
+
+        // test 400, a CSV file holds a column for every required field
+        {
+            let req = test::TestRequest::post()
+                .uri("/api/user/import")
+                .insert_header((AUTHORIZATION, authorization.as_str()))
+                .insert_header((CONTENT_TYPE, "text/csv"))
+                .set_payload("first_name,last_name\r\nJohn,Doe\r\n")
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+        }
+
+        // test 415, only CSV and NDJSON are read
+        {
+            let req = test::TestRequest::post()
+                .uri("/api/user/import")
+                .insert_header((AUTHORIZATION, authorization.as_str()))
+                .insert_header((CONTENT_TYPE, "application/json"))
+                .set_payload("[]")
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::UNSUPPORTED_MEDIA_TYPE);
+        }
+
+        // test 403, importing requires the user:create permission
+        {
+            let req = test::TestRequest::post()
+                .uri("/api/user/import")
+                .insert_header((AUTHORIZATION, ines_authorization.as_str()))
+                .insert_header((CONTENT_TYPE, "text/csv"))
+                .set_payload(csv)
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_user_import_background() {
+        let state = state(1).await;
+        let authorization = format!(
+            "Bearer {}",
+            access_token(&state.settings, &administrator_id(&state).await, 60)
+        );
+
+        let app = test::init_service(
+            App::new()
+                .app_data(Data::new(Arc::clone(&state)))
+                .service(web::scope("/api/user").configure(init)),
+        )
+        .await;
+
+        let req = test::TestRequest::post()
+            .uri("/api/user/import")
+            .insert_header((AUTHORIZATION, authorization.as_str()))
+            .insert_header((CONTENT_TYPE, "application/x-ndjson"))
+            .set_payload(
+                "{\"first_name\": \"Jules\", \"last_name\": \"Background\", \"email\": \"jules@import.example.com\"}\n\
+                {\"first_name\": \"Karl\", \"last_name\": \"Background\", \"email\": \"karl@import.example.com\"}\n",
+            )
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::ACCEPTED);
+        let location = resp
+            .headers()
+            .get(LOCATION)
+            .unwrap()
+            .to_str()
+            .unwrap()
+            .to_owned();
+
+        // test 200, the progress is polled until the job is done
+        let mut job: ImportJob = test::read_body_json(resp).await;
+        for _ in 0..50 {
+            if job.status != ImportStatus::Running {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+            let req = test::TestRequest::get()
+                .uri(location.as_str())
+                .insert_header((AUTHORIZATION, authorization.as_str()))
+                .to_request();
+            job = test::call_and_read_body_json(&app, req).await;
+        }
+        assert_eq!(job.status, ImportStatus::Completed);
+        assert_eq!((job.processed_rows, job.created, job.failed), (2, 2, 0));
+    }
+
+    #[tokio::test]
+    async fn test_user_import_interrupted() {
+        let state = state(100).await;
+        let administrator = administrator_id(&state).await;
+        let authorization = format!(
+            "Bearer {}",
+            access_token(&state.settings, &administrator, 60)
+        );
+
+        let app = test::init_service(
+            App::new()
+                .app_data(Data::new(Arc::clone(&state)))
+                .service(web::scope("/api/user").configure(init)),
+        )
+        .await;
+
+        // A job started by an instance stopped since then, it no longer counts any row.
+        let imports: Import<_, _> =
+            Import(Box::new(ImportManagement::new(DEFAULT_ORGANIZATION_ID)));
+        let job = imports
+            .0
+            .start(administrator, false, false, 10, &state.db_connection)
+            .await
+            .unwrap();
+        let job_id = Uuid::parse_str(job.get_id().as_str()).unwrap();
+        let location = format!("/api/user/import/{}", job_id);
+        let poll = || {
+            test::TestRequest::get()
+                .uri(location.as_str())
+                .insert_header((AUTHORIZATION, authorization.as_str()))
+                .to_request()
+        };
+
+        // test 200, a job counting its rows is running
+        let job: ImportJob = test::call_and_read_body_json(&app, poll()).await;
+        assert_eq!(job.status, ImportStatus::Running);
+
+        // test 200, a job left untouched is failed
+        let backend = state.db_connection.get_database_backend();
+        state
+            .db_connection
+            .execute(
+                backend.build(
+                    Query::update()
+                        .table(Alias::new("import_job"))
+                        .value(Alias::new("updated_at"), Utc::now() - Duration::hours(1))
+                        .and_where(Expr::col(Alias::new("id")).eq(job_id)),
+                ),
+            )
+            .await
+            .unwrap();
+        let job: ImportJob = test::call_and_read_body_json(&app, poll()).await;
+        assert_eq!(job.status, ImportStatus::Failed);
+        assert!(job.finished_at.is_some());
+    }
+}
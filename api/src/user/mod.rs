@@ -1,47 +1,304 @@
 use crate::swagger::SecurityAddon;
+use actix_web::http::header::{ETag, EntityTag, Header, IfMatch, IF_MATCH};
 use actix_web::web::{scope, ServiceConfig};
+use actix_web::HttpRequest;
+use chrono::{DateTime, Utc};
+use services_local::audit::Audited;
+use services_local::authorization::Authorized;
+use services_local::user::UserManagement;
 
+use crate::user::bulk::bulk as bulk_user;
 use crate::user::create::create as create_user;
 use crate::user::delete::delete as delete_user;
+use crate::user::export::export as export_user;
+use crate::user::find::find as find_user;
+use crate::user::history::history as user_history;
+use crate::user::import::{import as import_user, import_errors, import_status};
+use crate::user::patch::patch as patch_user;
+use crate::user::purge::purge as purge_user;
 use crate::user::read::read as read_user;
+use crate::user::restore::restore as restore_user;
 use crate::user::update::update as update_user;
-use common::management::SearchResult;
+use common::management::{Precondition, SearchResult};
+use common::user::patchable::Patchable;
 use common::user::userable::Userable;
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize};
+use std::borrow::Cow;
 use std::fmt::Display;
 use std::str::FromStr;
 use utoipa::{OpenApi, ToSchema};
 use uuid::Uuid;
+use validator::{Validate, ValidationError, ValidationErrors};
 
+mod bulk;
 mod create;
 mod delete;
+mod export;
+mod find;
+mod history;
+mod import;
+mod patch;
+mod purge;
 mod read;
+mod restore;
+mod role;
 mod update;
 
+pub(crate) use crate::user::create::insert;
+
 pub(crate) fn init(cfg: &mut ServiceConfig) {
     cfg.service(
         scope("")
             .service(read_user)
+            .service(export_user)
+            .service(import_user)
+            .service(import_status)
+            .service(import_errors)
+            .service(find_user)
             .service(create_user)
+            .service(bulk_user)
             .service(update_user)
-            .service(delete_user),
+            .service(patch_user)
+            .service(delete_user)
+            .service(restore_user)
+            .service(purge_user)
+            .service(user_history)
+            .configure(role::init),
     );
 }
 
-#[derive(Default, Serialize, Deserialize, ToSchema, Debug, Clone)]
+#[derive(Default, Serialize, Deserialize, ToSchema, Validate, Debug, Clone)]
 pub struct User {
     #[serde(default)]
     pub id: Uuid,
+    #[validate(custom = "validate_first_name")]
     pub first_name: String,
+    #[validate(custom = "validate_last_name")]
     pub last_name: String,
+    #[validate(custom = "validate_email")]
     pub email: String,
+    /// Incremented on every change, sent back as the `ETag` of the user
+    #[serde(default)]
+    #[schema(read_only)]
+    pub version: i32,
+    /// Set while the user is deleted, only listed with `include_deleted`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schema(read_only)]
+    pub deleted_at: Option<DateTime<Utc>>,
 }
 
-#[derive(Default, Serialize, Deserialize, ToSchema)]
+#[derive(Default, Serialize, Deserialize, ToSchema, Validate)]
 pub(super) struct NewUser {
+    #[validate(custom = "validate_first_name")]
     pub first_name: String,
+    #[validate(custom = "validate_last_name")]
     pub last_name: String,
+    #[validate(custom = "validate_email")]
     pub email: String,
+    /// Password used to log in, the user cannot log in until one is set
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[validate(
+        length(
+            min = 8,
+            code = "too_short",
+            message = "Password must contain at least 8 characters"
+        ),
+        length(
+            max = 1024,
+            code = "too_long",
+            message = "Password must contain at most 1024 characters"
+        )
+    )]
+    pub password: Option<String>,
+}
+
+/// RFC 7396 merge patch of a user, the fields left out keep their value.
+/// A field can not be removed, `null` is refused.
+#[derive(Default, Serialize, Deserialize, ToSchema, Validate)]
+#[serde(deny_unknown_fields)]
+pub(super) struct UserPatch {
+    #[serde(
+        default,
+        deserialize_with = "deserialize_present",
+        skip_serializing_if = "Option::is_none"
+    )]
+    #[schema(value_type = Option<String>)]
+    #[validate(custom = "validate_first_name")]
+    pub first_name: Option<Option<String>>,
+    #[serde(
+        default,
+        deserialize_with = "deserialize_present",
+        skip_serializing_if = "Option::is_none"
+    )]
+    #[schema(value_type = Option<String>)]
+    #[validate(custom = "validate_last_name")]
+    pub last_name: Option<Option<String>>,
+    #[serde(
+        default,
+        deserialize_with = "deserialize_present",
+        skip_serializing_if = "Option::is_none"
+    )]
+    #[schema(value_type = Option<String>)]
+    #[validate(custom = "validate_email")]
+    pub email: Option<Option<String>>,
+}
+
+/// Longest first or last name of a user, in characters
+const NAME_MAX_LENGTH: usize = 100;
+/// Longest email of a user, in characters
+const EMAIL_MAX_LENGTH: usize = 254;
+
+/// Field error `code` described by `message`
+fn field_error(code: &'static str, message: String) -> ValidationError {
+    let mut error = ValidationError::new(code);
+    error.message = Some(Cow::from(message));
+    error
+}
+
+/// Rules shared by the first and last names, `label` names the field in the messages
+fn validate_name(value: &str, label: &str) -> Result<(), ValidationError> {
+    if value.is_empty() {
+        return Err(field_error(
+            "required",
+            format!("{} must not be empty", label),
+        ));
+    }
+    if value.chars().count() > NAME_MAX_LENGTH {
+        return Err(field_error(
+            "too_long",
+            format!(
+                "{} must contain at most {} characters",
+                label, NAME_MAX_LENGTH
+            ),
+        ));
+    }
+    Ok(())
+}
+
+fn validate_first_name(value: &str) -> Result<(), ValidationError> {
+    validate_name(value, "First name")
+}
+
+fn validate_last_name(value: &str) -> Result<(), ValidationError> {
+    validate_name(value, "Last name")
+}
+
+fn validate_email(value: &str) -> Result<(), ValidationError> {
+    if !validator::validate_email(value) {
+        return Err(field_error(
+            "invalid_format",
+            String::from("Email is not a valid email address"),
+        ));
+    }
+    if value.chars().count() > EMAIL_MAX_LENGTH {
+        return Err(field_error(
+            "too_long",
+            format!("Email must contain at most {} characters", EMAIL_MAX_LENGTH),
+        ));
+    }
+    Ok(())
+}
+
+/// Tell a field sent as `null` (`Some(None)`) from a field left out (`None`).
+pub(crate) fn deserialize_present<'de, D, T>(deserializer: D) -> Result<Option<Option<T>>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    Option::deserialize(deserializer).map(Some)
+}
+
+impl UserPatch {
+    /// Validate the fields given and refuse the ones sent as `null`.
+    fn check(&self) -> Result<(), ValidationErrors> {
+        let mut errors = self.validate().err().unwrap_or_default();
+        for (field, value) in [
+            ("first_name", &self.first_name),
+            ("last_name", &self.last_name),
+            ("email", &self.email),
+        ] {
+            if matches!(value, Some(None)) {
+                errors.add(
+                    field,
+                    field_error("required", format!("{} can not be removed", field)),
+                );
+            }
+        }
+        match errors.is_empty() {
+            true => Ok(()),
+            false => Err(errors),
+        }
+    }
+}
+
+impl Patchable for UserPatch {
+    fn get_first_name(&self) -> Option<String> {
+        self.first_name.clone().flatten()
+    }
+
+    fn get_lastname(&self) -> Option<String> {
+        self.last_name.clone().flatten()
+    }
+
+    fn get_email(&self) -> Option<String> {
+        self.email.clone().flatten()
+    }
+}
+
+impl From<NewUser> for User {
+    fn from(value: NewUser) -> Self {
+        Self {
+            id: Default::default(),
+            first_name: value.first_name,
+            last_name: value.last_name,
+            email: value.email,
+            version: Default::default(),
+            deleted_at: None,
+        }
+    }
+}
+
+/// Users of the organization `tenant` managed on behalf of `actor`,
+/// checking the permissions and recording the changes
+pub(super) fn audited(
+    actor: String,
+    tenant: Uuid,
+    request_id: Option<String>,
+) -> Authorized<Audited<UserManagement>> {
+    Authorized::new(
+        Box::new(actor.to_owned()),
+        tenant,
+        "user",
+        Audited::new(
+            Box::new(actor),
+            tenant,
+            request_id,
+            "user",
+            UserManagement::new(tenant),
+        ),
+    )
+}
+
+/// Strong entity tag of a version of a user
+pub(super) fn entity_tag(version: i32) -> ETag {
+    ETag(EntityTag::new_strong(version.to_string()))
+}
+
+/// Versions allowed by the `If-Match` header, weak or malformed tags never match.
+pub(super) fn precondition(request: &HttpRequest) -> Precondition {
+    if !request.headers().contains_key(IF_MATCH) {
+        return Precondition::Any;
+    }
+    match IfMatch::parse(request) {
+        Ok(IfMatch::Any) => Precondition::Any,
+        Ok(IfMatch::Items(tags)) => Precondition::Versions(
+            tags.iter()
+                .filter(|tag| !tag.weak)
+                .filter_map(|tag| tag.tag().parse::<i32>().ok())
+                .collect(),
+        ),
+        Err(_) => Precondition::Versions(Vec::new()),
+    }
 }
 
 impl Userable for User {
@@ -60,6 +317,18 @@ impl Userable for User {
     fn get_email(&self) -> String {
         self.email.to_owned()
     }
+
+    fn get_version(&self) -> i32 {
+        self.version
+    }
+
+    fn get_last_modified(&self) -> Option<DateTime<Utc>> {
+        None
+    }
+
+    fn get_deleted_at(&self) -> Option<DateTime<Utc>> {
+        self.deleted_at
+    }
 }
 
 impl From<Box<dyn Userable>> for User {
@@ -69,23 +338,65 @@ impl From<Box<dyn Userable>> for User {
             first_name: value.get_first_name(),
             last_name: value.get_lastname(),
             email: value.get_email(),
+            version: value.get_version(),
+            deleted_at: value.get_deleted_at(),
         }
     }
 }
 
-#[derive(Serialize, Deserialize, Clone)]
+#[derive(Serialize, Deserialize, ToSchema, Clone)]
 pub struct UserSearchResult {
-    pub num_pages: usize,
+    /// Absent when the page is read from a cursor
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub num_pages: Option<usize>,
+    /// Absent when the page is read from a cursor
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub total_items: Option<usize>,
+    /// Absent when the page is read from a cursor
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub page: Option<usize>,
+    pub per_page: usize,
     pub result: Vec<User>,
+    /// Cursor of the following page, given as `after`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub next_cursor: Option<String>,
+    /// Cursor of the preceding page, given as `before`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub prev_cursor: Option<String>,
 }
 
 impl SearchResult for UserSearchResult {
     type Result = User;
 
-    fn get_num_pages(&self) -> usize {
+    fn get_num_pages(&self) -> Option<usize> {
         self.num_pages.to_owned()
     }
 
+    fn get_total_items(&self) -> Option<usize> {
+        self.total_items.to_owned()
+    }
+
+    fn get_page(&self) -> Option<usize> {
+        self.page.to_owned()
+    }
+
+    fn get_per_page(&self) -> usize {
+        self.per_page.to_owned()
+    }
+
+    fn get_next_cursor(&self) -> Option<String> {
+        self.next_cursor.to_owned()
+    }
+
+    fn get_prev_cursor(&self) -> Option<String> {
+        self.prev_cursor.to_owned()
+    }
+
+    /// The dates of the users are not part of their representation
+    fn get_last_modified(&self) -> Option<DateTime<Utc>> {
+        None
+    }
+
     fn get_result(&self) -> Box<dyn Iterator<Item = Self::Result>> {
         Box::new(self.result.clone().into_iter())
     }
@@ -94,12 +405,14 @@ impl SearchResult for UserSearchResult {
 pub fn api_docs() -> utoipa::openapi::OpenApi {
     #[derive(OpenApi)]
     #[openapi(
-    paths(crate::user::create::create,crate::user::read::read,crate::user::update::update,crate::user::delete::delete),
-    components(schemas(crate::user::NewUser,crate::user::User)),
+    paths(crate::user::create::create,crate::user::read::read,crate::user::find::find,crate::user::update::update,crate::user::patch::patch,crate::user::delete::delete,crate::user::restore::restore,crate::user::purge::purge,crate::user::history::history,crate::user::bulk::bulk,crate::user::export::export,crate::user::import::import,crate::user::import::import_status,crate::user::import::import_errors),
+    components(schemas(crate::user::NewUser,crate::user::User,crate::user::UserPatch,crate::user::UserSearchResult,crate::user::bulk::BulkMode,crate::user::bulk::BulkOperation,crate::user::bulk::BulkRequest,crate::user::bulk::BulkResult,crate::user::bulk::BulkResponse,crate::user::import::ImportStatus,crate::user::import::ImportJob)),
     modifiers(&SecurityAddon)
     )]
     struct ApiDocs;
-    ApiDocs::openapi()
+    let mut api_docs = ApiDocs::openapi();
+    api_docs.merge(role::api_docs());
+    api_docs
 }
 
 #[cfg(test)]
@@ -111,6 +424,7 @@ mod tests {
     use futures::FutureExt;
     use log::Level;
     use sea_orm::{ConnectOptions, Database, DatabaseConnection};
+    use services_local::organization::DEFAULT_ORGANIZATION_ID;
     use services_local::user::UserManagement;
 
     fn initialize_user<'a>(db_url: String, loglevel: Level) -> BoxFuture<'a, ()> {
@@ -127,8 +441,10 @@ mod tests {
                 first_name: String::from("John"),
                 last_name: String::from("Doe"),
                 email: String::from("johndoe@example.com"),
+                version: Default::default(),
+                deleted_at: None,
             };
-            let user_management = UserManagement;
+            let user_management = UserManagement::new(DEFAULT_ORGANIZATION_ID);
             user_management
                 .create(Box::new(new_user), &db_connection)
                 .await
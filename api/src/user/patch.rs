@@ -0,0 +1,194 @@
+use crate::audit::request_id;
+use crate::auth::Claims;
+use crate::error::AsHttpError;
+use crate::user::{audited, entity_tag, precondition, User, UserPatch};
+use crate::{AppState, Management};
+use actix_web::web::{Data, Json, Path};
+use actix_web::{patch, HttpRequest, HttpResponse, Responder};
+use common::error::CoreError;
+use sea_orm::TransactionTrait;
+use std::sync::Arc;
+
+#[utoipa::path(
+tag = "Update user",
+context_path = "/api/user",
+params(
+("user_id" = Uuid, Path, description = "User identifier"),
+("If-Match" = Option<String>, Header, description = "ETag of the version to update")
+),
+request_body(content = UserPatch, content_type = "application/merge-patch+json"),
+responses((status=200, description = "User update succeed", body = User, headers(("ETag" = String, description = "Version of the user"))),
+(status=400, description = "Sent data not correct"),
+(status=401, description = "Authentication required"),
+(status=404, description = "User not found"),
+(status=409, description = "Email already used"),
+(status=412, description = "User changed since the version given by If-Match"),
+(status=422, description = "Fields not valid")
+),
+security(("api_jwt_token" = []))
+)]
+#[patch("/{user_id}")]
+pub(super) async fn patch(
+    request: HttpRequest,
+    user_id: Path<String>,
+    user_patch: Json<UserPatch>,
+    claims: Claims,
+    app_data: Data<Arc<AppState>>,
+) -> actix_web::Result<impl Responder> {
+    let user_id = user_id.into_inner();
+    let user_patch = user_patch.into_inner();
+    user_patch.check().map_err(AsHttpError::from)?;
+    let transaction = app_data
+        .db_connection
+        .begin()
+        .await
+        .map_err(|e| AsHttpError::from(CoreError::UnkownError(e.to_string())))?;
+    let response = {
+        let management: Management<_, _, _, _, _, _> = Management(Box::new(audited(
+            claims.sub,
+            claims.tenant,
+            request_id(&request),
+        )));
+        management
+            .0
+            .patch(
+                Box::new(user_id),
+                Box::new(user_patch),
+                precondition(&request),
+                &transaction,
+            )
+            .await
+            .map(User::from)
+            .map_err(|e| AsHttpError::from(e.get_core_error()))?
+    };
+    transaction
+        .commit()
+        .await
+        .map_err(|e| AsHttpError::from(CoreError::UnkownError(e.to_string())))?;
+    Ok(HttpResponse::Ok()
+        .insert_header(entity_tag(response.version))
+        .json(response))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{access_token, administrator_id, app_state, test_settings};
+    use crate::user::create::create;
+    use crate::user::patch::patch;
+    use crate::user::{NewUser, User, UserPatch};
+    use actix_web::http::header::{AUTHORIZATION, CONTENT_TYPE};
+    use actix_web::http::StatusCode;
+    use actix_web::web::Data;
+    use actix_web::{test, web, App};
+    use serde_json::json;
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn test_user_patch() {
+        let state = app_state(test_settings()).await;
+        let authorization = format!(
+            "Bearer {}",
+            access_token(&state.settings, &administrator_id(&state).await, 60)
+        );
+
+        let app = test::init_service(
+            App::new()
+                .app_data(Data::new(Arc::clone(&state)))
+                .service(web::scope("/api/user").service(create).service(patch)),
+        )
+        .await;
+
+        let new_user = NewUser {
+            first_name: "Marc".to_string(),
+            last_name: "Antoine".to_string(),
+            email: "marcantoine@example.com".to_string(),
+            password: None,
+        };
+        let req = test::TestRequest::post()
+            .uri("/api/user")
+            .insert_header((AUTHORIZATION, authorization.as_str()))
+            .set_json(&new_user)
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        let user: User = test::read_body_json(resp).await;
+
+        let path = format!("/api/user/{}", user.id);
+
+        // test 200, only the given fields change
+        {
+            let req = test::TestRequest::patch()
+                .uri(path.as_str())
+                .insert_header((AUTHORIZATION, authorization.as_str()))
+                .insert_header((CONTENT_TYPE, "application/merge-patch+json"))
+                .set_payload(json!({ "first_name": "Marcel" }).to_string())
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::OK);
+            let patched_user: User = test::read_body_json(resp).await;
+            assert_eq!(patched_user.id, user.id);
+            assert_eq!(patched_user.first_name, "Marcel");
+            assert_eq!(patched_user.last_name, user.last_name);
+            assert_eq!(patched_user.email, user.email);
+        }
+
+        // test 200, a user can patch his own record
+        {
+            let req = test::TestRequest::patch()
+                .uri(path.as_str())
+                .insert_header((
+                    AUTHORIZATION,
+                    format!(
+                        "Bearer {}",
+                        access_token(&state.settings, &user.id.to_string(), 60)
+                    ),
+                ))
+                .set_json(UserPatch {
+                    last_name: Some(Some(String::from("Antoinette"))),
+                    ..Default::default()
+                })
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::OK);
+            let patched_user: User = test::read_body_json(resp).await;
+            assert_eq!(patched_user.first_name, "Marcel");
+            assert_eq!(patched_user.last_name, "Antoinette");
+        }
+
+        // test 422, fields can not be removed
+        {
+            let req = test::TestRequest::patch()
+                .uri(path.as_str())
+                .insert_header((AUTHORIZATION, authorization.as_str()))
+                .set_json(json!({ "first_name": null, "email": "not-an-email" }))
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::UNPROCESSABLE_ENTITY);
+            let response: serde_json::Value = test::read_body_json(resp).await;
+            assert_eq!(response["errors"][0]["field"], "email");
+            assert_eq!(response["errors"][1]["field"], "first_name");
+            assert_eq!(response["errors"][1]["code"], "required");
+        }
+
+        // test 400, unknown fields are refused
+        {
+            let req = test::TestRequest::patch()
+                .uri(path.as_str())
+                .insert_header((AUTHORIZATION, authorization.as_str()))
+                .set_json(json!({ "id": user.id }))
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+        }
+
+        // test 404
+        {
+            let req = test::TestRequest::patch()
+                .uri(format!("/api/user/{}", uuid::Uuid::new_v4()).as_str())
+                .insert_header((AUTHORIZATION, authorization.as_str()))
+                .set_json(json!({ "first_name": "Nobody" }))
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+        }
+    }
+}
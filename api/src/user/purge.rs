@@ -0,0 +1,156 @@
+use crate::audit::request_id;
+use crate::auth::Claims;
+use crate::error::AsHttpError;
+use crate::user::audited;
+use crate::{AppState, Management};
+use actix_web::web::{Data, Path};
+use actix_web::{post, HttpRequest, HttpResponse, Responder};
+use common::error::CoreError;
+use sea_orm::TransactionTrait;
+use std::sync::Arc;
+
+#[utoipa::path(
+tag = "Delete user",
+context_path = "/api/user",
+params(
+("user_id" = Uuid, Path, description = "User identifier")
+),
+responses((status=204, description = "User purge succeed"),
+(status=400, description = "User not deleted"),
+(status=401, description = "Authentication required"),
+(status=403, description = "Permission user:purge required"),
+(status=404, description = "User not found")
+),
+security(("api_jwt_token" = []))
+)]
+#[post("/{user_id}/purge")]
+pub(super) async fn purge(
+    request: HttpRequest,
+    user_id: Path<String>,
+    claims: Claims,
+    app_data: Data<Arc<AppState>>,
+) -> actix_web::Result<impl Responder> {
+    let user_id = user_id.into_inner();
+    let transaction = app_data
+        .db_connection
+        .begin()
+        .await
+        .map_err(|e| AsHttpError::from(CoreError::UnkownError(e.to_string())))?;
+    {
+        let management: Management<_, _, _, _, _, _> = Management(Box::new(audited(
+            claims.sub,
+            claims.tenant,
+            request_id(&request),
+        )));
+        management
+            .0
+            .purge(Box::new(user_id), &transaction)
+            .await
+            .map_err(|e| AsHttpError::from(e.get_core_error()))?;
+    }
+    transaction
+        .commit()
+        .await
+        .map_err(|e| AsHttpError::from(CoreError::UnkownError(e.to_string())))?;
+    Ok(HttpResponse::NoContent().finish())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{access_token, administrator_id, app_state, test_settings};
+    use crate::user::create::create;
+    use crate::user::delete::delete;
+    use crate::user::purge::purge;
+    use crate::user::restore::restore;
+    use crate::user::{NewUser, User};
+    use actix_web::http::header::AUTHORIZATION;
+    use actix_web::http::StatusCode;
+    use actix_web::web::Data;
+    use actix_web::{test, web, App};
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn test_user_purge() {
+        let state = app_state(test_settings()).await;
+        let authorization = format!(
+            "Bearer {}",
+            access_token(&state.settings, &administrator_id(&state).await, 60)
+        );
+
+        let app = test::init_service(
+            App::new().app_data(Data::new(Arc::clone(&state))).service(
+                web::scope("/api/user")
+                    .service(create)
+                    .service(delete)
+                    .service(restore)
+                    .service(purge),
+            ),
+        )
+        .await;
+
+        let new_user = NewUser {
+            first_name: "Pierre".to_string(),
+            last_name: "Paul".to_string(),
+            email: "pierrepaul@example.com".to_string(),
+            password: None,
+        };
+        let req = test::TestRequest::post()
+            .uri("/api/user")
+            .insert_header((AUTHORIZATION, authorization.as_str()))
+            .set_json(&new_user)
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        let user: User = test::read_body_json(resp).await;
+        let user_authorization = format!(
+            "Bearer {}",
+            access_token(&state.settings, &user.id.to_string(), 60)
+        );
+        let path = format!("/api/user/{}", user.id);
+
+        // test 400, a user must be deleted before being purged
+        {
+            let req = test::TestRequest::post()
+                .uri(format!("{}/purge", path).as_str())
+                .insert_header((AUTHORIZATION, authorization.as_str()))
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+        }
+
+        let req = test::TestRequest::delete()
+            .uri(path.as_str())
+            .insert_header((AUTHORIZATION, authorization.as_str()))
+            .to_request();
+        assert_eq!(
+            test::call_service(&app, req).await.status(),
+            StatusCode::NO_CONTENT
+        );
+
+        // test 403, only administrators purge users
+        {
+            let req = test::TestRequest::post()
+                .uri(format!("{}/purge", path).as_str())
+                .insert_header((AUTHORIZATION, user_authorization.as_str()))
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+        }
+
+        // test 204, a purged user can not be restored
+        {
+            let req = test::TestRequest::post()
+                .uri(format!("{}/purge", path).as_str())
+                .insert_header((AUTHORIZATION, authorization.as_str()))
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::NO_CONTENT);
+
+            let req = test::TestRequest::post()
+                .uri(format!("{}/restore", path).as_str())
+                .insert_header((AUTHORIZATION, authorization.as_str()))
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+        }
+    }
+}
@@ -1,23 +1,51 @@
+use crate::auth::Claims;
+use crate::cache::Representation;
 use crate::error::AsHttpError;
 use crate::user::{User, UserSearchResult};
-use crate::{AppState, Management};
-use actix_web::web::{Data, Json};
-use actix_web::{get, web, Responder};
+use crate::{AppState, Authorization, Management};
+use actix_web::http::header::LINK;
+use actix_web::web::Data;
+use actix_web::{get, web, HttpRequest, HttpResponse, Responder};
 use chrono::{DateTime, Utc};
-use common::management::Searchable;
+use common::error::CoreError;
+use common::management::{
+    Cursor, DateField, DateRange, Filter, MatchMode, Pagination, Searchable, Sort,
+};
 use serde::{Deserialize, Serialize};
+use services_local::authorization::{Authorized, RoleManagement};
+use services_local::user::UserManagement;
 use std::fmt::Display;
+use std::str::FromStr;
 use std::sync::Arc;
 use uuid::Uuid;
 
-#[derive(Default, Serialize, Deserialize)]
-struct QuerySearch {
+#[derive(Default, Serialize, Deserialize, Clone)]
+pub(super) struct QuerySearch {
     id: Option<Uuid>,
     pattern: Option<String>,
+    /// Comparison of `pattern`: `exact`, `prefix` or `contains` (default), ignoring case
+    match_mode: Option<String>,
     start_date: Option<DateTime<Utc>>,
     end_date: Option<DateTime<Utc>>,
-    page: Option<usize>,
-    per_page: Option<usize>,
+    /// Date filtered by `start_date` and `end_date`: `created_at` (default) or `updated_at`
+    date_field: Option<String>,
+    /// Comma separated fields to sort on, prefixed by `-` for descending order, e.g. `last_name,-created_at`
+    sort: Option<String>,
+    /// Cursor read from `next_cursor`, an empty one starts from the first user
+    pub(super) after: Option<String>,
+    /// Cursor read from `prev_cursor`, an empty one starts from the last user
+    pub(super) before: Option<String>,
+    pub(super) page: Option<usize>,
+    pub(super) per_page: Option<usize>,
+    /// Also list the deleted users, requires the `user:restore` permission
+    include_deleted: Option<bool>,
+    /// Only list the members of this group
+    group: Option<Uuid>,
+    /// Bounds of `per_page`, read from the settings
+    #[serde(skip)]
+    pub(super) default_per_page: usize,
+    #[serde(skip)]
+    pub(super) max_per_page: usize,
 }
 
 impl Searchable for QuerySearch {
@@ -33,84 +61,235 @@ impl Searchable for QuerySearch {
             .map(|value| Box::new(value) as Box<dyn Display + Sync + Send>)
     }
 
-    fn get_date_range(
-        &self,
-    ) -> Option<(chrono::DateTime<chrono::Utc>, chrono::DateTime<chrono::Utc>)> {
-        if self.start_date.is_some() && self.end_date.is_some() {
-            Some((self.start_date.unwrap(), self.end_date.unwrap()))
-        } else {
-            None
+    fn get_match_mode(&self) -> Result<MatchMode, CoreError> {
+        match &self.match_mode {
+            Some(match_mode) => MatchMode::from_str(match_mode.as_str()),
+            None => Ok(MatchMode::default()),
         }
     }
 
-    fn get_page(&self) -> usize {
-        self.page.map_or(1, |p| p)
+    fn get_filters(&self) -> Vec<Filter> {
+        self.group
+            .iter()
+            .map(|group| Filter::new("group", MatchMode::Exact, group.to_string().as_str()))
+            .collect()
     }
-    fn get_per_page(&self) -> usize {
-        self.per_page.map_or(25, |p| p)
+
+    fn get_date_range(&self) -> Result<Option<DateRange>, CoreError> {
+        let field = match &self.date_field {
+            Some(field) => DateField::from_str(field.as_str())?,
+            None => DateField::default(),
+        };
+        match (self.start_date, self.end_date) {
+            (Some(start_date), Some(end_date)) => {
+                DateRange::new(field, start_date, end_date).map(Some)
+            }
+            (None, None) => Ok(None),
+            _ => Err(CoreError::DataError(String::from(
+                "start_date and end_date must be given together",
+            ))),
+        }
+    }
+
+    fn get_sort(&self) -> Result<Vec<Sort>, CoreError> {
+        match &self.sort {
+            Some(sort) => sort.split(',').map(Sort::from_str).collect(),
+            None => Ok(Vec::new()),
+        }
+    }
+
+    fn get_cursor(&self) -> Result<Option<Cursor>, CoreError> {
+        match (&self.after, &self.before, self.page) {
+            (None, None, _) => Ok(None),
+            (Some(_), Some(_), _) => Err(CoreError::DataError(String::from(
+                "after and before can not be given together",
+            ))),
+            (_, _, Some(_)) => Err(CoreError::DataError(String::from(
+                "page can not be given with a cursor",
+            ))),
+            (Some(after), None, None) => Ok(Some(Cursor::After(after.to_owned()))),
+            (None, Some(before), None) => Ok(Some(Cursor::Before(before.to_owned()))),
+        }
+    }
+
+    fn get_pagination(&self) -> Result<Pagination, CoreError> {
+        Pagination::new(
+            self.page,
+            self.per_page,
+            self.default_per_page,
+            self.max_per_page,
+        )
+    }
+
+    fn get_include_deleted(&self) -> bool {
+        self.include_deleted.unwrap_or_default()
+    }
+}
+
+/// Check the `user:restore` permission of `actor` when `query` includes the deleted users.
+pub(super) async fn authorize_include_deleted(
+    actor: &str,
+    tenant: Uuid,
+    query: &QuerySearch,
+    app_data: &AppState,
+) -> Result<(), AsHttpError> {
+    if query.include_deleted.unwrap_or_default() {
+        let authorization: Authorization<_, _> =
+            Authorization(Box::new(RoleManagement::new(tenant)));
+        authorization
+            .0
+            .authorize(
+                Box::new(actor.to_owned()),
+                String::from("user:restore"),
+                None,
+                &app_data.db_connection,
+            )
+            .await
+            .map_err(|e| AsHttpError::from(e.get_core_error()))?;
     }
+    Ok(())
 }
 
 pub(crate) struct Search(Box<dyn Searchable<Id = Box<dyn Display + Sync + Send>>>);
 
+/// Link to the same search where the position in the list is replaced by `position`
+fn link(request: &HttpRequest, position: &str, rel: &str) -> String {
+    let query = request
+        .query_string()
+        .split('&')
+        .filter(|pair| {
+            !pair.is_empty() && !matches!(pair.split('=').next(), Some("page" | "after" | "before"))
+        })
+        .chain(std::iter::once(position))
+        .collect::<Vec<_>>()
+        .join("&");
+    format!("<{}?{}>; rel=\"{}\"", request.path(), query, rel)
+}
+
+/// RFC 8288 `Link` header of the first, previous, next and last pages
+fn links(request: &HttpRequest, search_result: &UserSearchResult) -> String {
+    let mut links = Vec::new();
+    match search_result.page {
+        Some(page) => {
+            let last_page = search_result.num_pages.unwrap_or_default().max(1);
+            links.push(link(request, "page=1", "first"));
+            if page > 1 {
+                let prev_page = format!("page={}", (page - 1).min(last_page));
+                links.push(link(request, prev_page.as_str(), "prev"));
+            }
+            if page < last_page {
+                let next_page = format!("page={}", page + 1);
+                links.push(link(request, next_page.as_str(), "next"));
+            }
+            links.push(link(
+                request,
+                format!("page={}", last_page).as_str(),
+                "last",
+            ));
+        }
+        None => {
+            links.push(link(request, "after=", "first"));
+            if let Some(prev_cursor) = &search_result.prev_cursor {
+                let before = format!("before={}", prev_cursor);
+                links.push(link(request, before.as_str(), "prev"));
+            }
+            if let Some(next_cursor) = &search_result.next_cursor {
+                let after = format!("after={}", next_cursor);
+                links.push(link(request, after.as_str(), "next"));
+            }
+            links.push(link(request, "before=", "last"));
+        }
+    }
+    links.join(", ")
+}
+
 #[utoipa::path(
 tag = "List users",
 context_path = "/api/user",
-responses((status=200, description = "Users list succeed"),
-(status=401, description = "Authentication required")
-)
+responses((status=200, description = "Users list succeed", body = UserSearchResult,
+headers(("Link" = String, description = "RFC 8288 links to the first, prev, next and last pages"),
+("ETag" = String, description = "Digest of the page"),
+("Last-Modified" = String, description = "Latest change among the users of the organization, absent when filtered by group"),
+("Cache-Control" = String, description = "Private, revalidated after `application.cache_max_age` seconds"))),
+(status=304, description = "The page did not change since the ETag or the date of the client copy"),
+(status=401, description = "Authentication required"),
+(status=403, description = "Permission user:restore required by include_deleted")
+),
+security(("api_jwt_token" = []))
 )]
 #[get("")]
 pub(super) async fn read(
+    request: HttpRequest,
     app_data: Data<Arc<AppState>>,
     query: web::Query<QuerySearch>,
+    claims: Claims,
 ) -> actix_web::Result<impl Responder> {
-    let (management, search): (Management<_, _, _, _, _>, Search) = (
-        Management(Box::new(services_local::user::UserManagement)),
-        Search(Box::new(query.into_inner())),
+    authorize_include_deleted(&claims.sub, claims.tenant, &query, &app_data).await?;
+    let (management, search): (Management<_, _, _, _, _, _>, Search) = (
+        Management(Box::new(Authorized::new(
+            Box::new(claims.sub),
+            claims.tenant,
+            "user",
+            UserManagement::new(claims.tenant),
+        ))),
+        Search(Box::new(QuerySearch {
+            default_per_page: app_data.settings.pagination.default_page_size,
+            max_per_page: app_data.settings.pagination.max_page_size,
+            ..query.into_inner()
+        })),
     );
-    let response: UserSearchResult = management
+    let search_result = management
         .0
         .read(search.0, &app_data.db_connection)
         .await
-        .map(|x| UserSearchResult {
-            num_pages: x.get_num_pages(),
-            result: x.get_result().map(User::from).collect(),
-        })
         .map_err(|e| AsHttpError::from(e.get_core_error()))?;
-    Ok(Json(response))
+    let response = UserSearchResult {
+        num_pages: search_result.get_num_pages(),
+        total_items: search_result.get_total_items(),
+        page: search_result.get_page(),
+        per_page: search_result.get_per_page(),
+        result: search_result.get_result().map(User::from).collect(),
+        next_cursor: search_result.get_next_cursor(),
+        prev_cursor: search_result.get_prev_cursor(),
+    };
+    let mut builder = HttpResponse::Ok();
+    builder.insert_header((LINK, links(&request, &response)));
+    Ok(
+        Representation::new(&response, search_result.get_last_modified())?.respond(
+            &request,
+            &app_data.settings.application,
+            builder,
+        ),
+    )
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::tests::initialize_db;
-    use crate::AppState;
+    use crate::tests::{access_token, administrator_id, app_state, test_settings};
+    use actix_web::http::header::{
+        AUTHORIZATION, CACHE_CONTROL, ETAG, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED, LINK,
+    };
     use actix_web::http::StatusCode;
     use actix_web::web::Data;
     use actix_web::{test, web, App};
-    use sea_orm::{ConnectOptions, Database, DatabaseConnection};
     use std::sync::Arc;
 
-    use crate::user::read::read;
-    use crate::user::UserSearchResult;
-    use service_config::Settings;
+    use crate::user::read::{links, read};
+    use crate::user::{User, UserSearchResult};
+    use common::management::{Manageable, Precondition};
+    use services_local::audit::Audited;
+    use services_local::organization::DEFAULT_ORGANIZATION_ID;
+    use services_local::user::UserManagement;
+    use std::time::Duration;
+    use uuid::Uuid;
 
     #[tokio::test]
     async fn test_user_read() {
-        initialize_db().await;
-        std::env::set_var("APP_ENVIRONMENT", "test");
-        let settings = Settings::load().expect("Error occurs when trying to load settings");
-
-        let database_url = settings.database.get_url();
-        let mut db_connection_opt = ConnectOptions::new(database_url.to_owned());
-        db_connection_opt.sqlx_logging_level(settings.application.loglevel.to_level_filter());
-
-        let db_connection: DatabaseConnection = Database::connect(db_connection_opt)
-            .await
-            .expect("Error occurs when trying to connect to database");
-
-        let state = AppState { db_connection };
-        let state = Arc::new(state);
+        let state = app_state(test_settings()).await;
+        let authorization = format!(
+            "Bearer {}",
+            access_token(&state.settings, &administrator_id(&state).await, 60)
+        );
 
         let app = test::init_service(
             App::new()
@@ -121,14 +300,502 @@ mod tests {
 
         // test 200
         {
-            let req = test::TestRequest::default().uri("/api/user").to_request();
+            let req = test::TestRequest::default()
+                .uri("/api/user")
+                .insert_header((AUTHORIZATION, authorization.as_str()))
+                .to_request();
             let resp = test::call_service(&app, req).await;
-            println!("status code: {:?}", resp.status().to_string());
             assert_eq!(resp.status(), StatusCode::OK);
             let response = test::read_body(resp).await;
-            println!("response body: {:?}", response);
             let _user: UserSearchResult =
                 serde_json::from_slice(response.iter().as_slice()).unwrap();
         }
+
+        // test 200 with the pagination metadata and links
+        {
+            let req = test::TestRequest::default()
+                .uri("/api/user?sort=email&per_page=1&page=2")
+                .insert_header((AUTHORIZATION, authorization.as_str()))
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::OK);
+            let link = resp
+                .headers()
+                .get(LINK)
+                .unwrap()
+                .to_str()
+                .unwrap()
+                .to_owned();
+            let users: UserSearchResult = test::read_body_json(resp).await;
+            assert_eq!(users.page, Some(2));
+            assert_eq!(users.per_page, 1);
+            let total_items = users.total_items.unwrap();
+            assert!(total_items >= 2);
+            assert_eq!(users.num_pages, Some(total_items));
+            assert!(link.contains("</api/user?sort=email&per_page=1&page=1>; rel=\"first\""));
+            assert!(link.contains("</api/user?sort=email&per_page=1&page=1>; rel=\"prev\""));
+            assert!(link.contains(
+                format!(
+                    "</api/user?sort=email&per_page=1&page={}>; rel=\"last\"",
+                    total_items
+                )
+                .as_str()
+            ));
+
+            let req = test::TestRequest::default()
+                .uri("/api/user?after=&per_page=1")
+                .insert_header((AUTHORIZATION, authorization.as_str()))
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            let link = resp
+                .headers()
+                .get(LINK)
+                .unwrap()
+                .to_str()
+                .unwrap()
+                .to_owned();
+            let users: UserSearchResult = test::read_body_json(resp).await;
+            assert!(users.total_items.is_none() && users.page.is_none());
+            assert!(link.contains(
+                format!(
+                    "</api/user?per_page=1&after={}>; rel=\"next\"",
+                    users.next_cursor.unwrap()
+                )
+                .as_str()
+            ));
+            assert!(!link.contains("rel=\"prev\""));
+        }
+
+        // test 400 with an incorrect pagination
+        {
+            for query in [
+                "page=0",
+                "per_page=0",
+                "per_page=101",
+                "page=18446744073709551615",
+                "page=-1",
+            ] {
+                let req = test::TestRequest::default()
+                    .uri(format!("/api/user?{}", query).as_str())
+                    .insert_header((AUTHORIZATION, authorization.as_str()))
+                    .to_request();
+                let resp = test::call_service(&app, req).await;
+                assert_eq!(resp.status(), StatusCode::BAD_REQUEST, "{}", query);
+            }
+        }
+
+        // test 200 with a pattern, ignoring case
+        {
+            for (query, found) in [
+                ("pattern=DMIN%40example", true),
+                ("pattern=admin&match_mode=exact", true),
+                ("pattern=adm&match_mode=exact", false),
+                ("pattern=ADM&match_mode=prefix", true),
+                ("pattern=dmin&match_mode=prefix", false),
+                ("pattern=%25&match_mode=contains", false),
+            ] {
+                let req = test::TestRequest::default()
+                    .uri(format!("/api/user?{}", query).as_str())
+                    .insert_header((AUTHORIZATION, authorization.as_str()))
+                    .to_request();
+                let resp = test::call_service(&app, req).await;
+                assert_eq!(resp.status(), StatusCode::OK);
+                let users: UserSearchResult = test::read_body_json(resp).await;
+                assert_eq!(
+                    users
+                        .result
+                        .iter()
+                        .any(|user| user.email == "admin@example.com"),
+                    found,
+                    "{}",
+                    query
+                );
+            }
+
+            let req = test::TestRequest::default()
+                .uri("/api/user?pattern=admin&match_mode=regex")
+                .insert_header((AUTHORIZATION, authorization.as_str()))
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+        }
+
+        // test 200 with a sort, the id breaks ties
+        {
+            let req = test::TestRequest::default()
+                .uri("/api/user?sort=last_name,-created_at&per_page=100")
+                .insert_header((AUTHORIZATION, authorization.as_str()))
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::OK);
+            let users: UserSearchResult = test::read_body_json(resp).await;
+            assert!(users
+                .result
+                .windows(2)
+                .all(|pair| pair[0].last_name <= pair[1].last_name));
+
+            let req = test::TestRequest::default()
+                .uri("/api/user?sort=-email&per_page=100")
+                .insert_header((AUTHORIZATION, authorization.as_str()))
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::OK);
+            let users: UserSearchResult = test::read_body_json(resp).await;
+            assert!(users
+                .result
+                .windows(2)
+                .all(|pair| pair[0].email >= pair[1].email));
+        }
+
+        // test 400 with an incorrect sort
+        {
+            for query in ["sort=password", "sort=last_name,", "sort=-"] {
+                let req = test::TestRequest::default()
+                    .uri(format!("/api/user?{}", query).as_str())
+                    .insert_header((AUTHORIZATION, authorization.as_str()))
+                    .to_request();
+                let resp = test::call_service(&app, req).await;
+                assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+            }
+        }
+
+        // test 200 with a date range
+        {
+            for date_field in ["created_at", "updated_at"] {
+                let uri = format!(
+                    "/api/user?start_date=2000-01-01T00:00:00Z&end_date=2999-12-31T23:59:59Z&date_field={}",
+                    date_field
+                );
+                let req = test::TestRequest::default()
+                    .uri(uri.as_str())
+                    .insert_header((AUTHORIZATION, authorization.as_str()))
+                    .to_request();
+                let resp = test::call_service(&app, req).await;
+                assert_eq!(resp.status(), StatusCode::OK);
+                let users: UserSearchResult = test::read_body_json(resp).await;
+                // users never updated have no `updated_at`
+                assert!(date_field == "updated_at" || !users.result.is_empty());
+            }
+
+            let req = test::TestRequest::default()
+                .uri("/api/user?start_date=2000-01-01T00:00:00Z&end_date=2000-12-31T23:59:59Z")
+                .insert_header((AUTHORIZATION, authorization.as_str()))
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::OK);
+            let users: UserSearchResult = test::read_body_json(resp).await;
+            assert!(users.result.is_empty());
+        }
+
+        // test 400 with an incorrect date range
+        {
+            for query in [
+                "start_date=2000-01-01T00:00:00Z",
+                "start_date=2001-01-01T00:00:00Z&end_date=2000-01-01T00:00:00Z",
+                "start_date=2000-01-01T00:00:00Z&end_date=2001-01-01T00:00:00Z&date_field=deleted_at",
+            ] {
+                let req = test::TestRequest::default()
+                    .uri(format!("/api/user?{}", query).as_str())
+                    .insert_header((AUTHORIZATION, authorization.as_str()))
+                    .to_request();
+                let resp = test::call_service(&app, req).await;
+                assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+            }
+        }
+
+        // test 200 with cursors, walking forward then backward
+        {
+            for (i, first_name) in ["Ann", "Bob", "Bob", "Cid", "Cid"].iter().enumerate() {
+                UserManagement::new(DEFAULT_ORGANIZATION_ID)
+                    .create(
+                        Box::new(User {
+                            id: Default::default(),
+                            first_name: first_name.to_string(),
+                            last_name: String::from("Walker"),
+                            email: format!("cursor.walk{}@example.com", i),
+                            version: Default::default(),
+                            deleted_at: None,
+                        }),
+                        &state.db_connection,
+                    )
+                    .await
+                    .unwrap();
+            }
+            let search = "/api/user?pattern=cursor.walk&sort=first_name,-created_at";
+
+            let req = test::TestRequest::default()
+                .uri(format!("{}&per_page=10", search).as_str())
+                .insert_header((AUTHORIZATION, authorization.as_str()))
+                .to_request();
+            let expected: UserSearchResult =
+                test::read_body_json(test::call_service(&app, req).await).await;
+            let expected: Vec<_> = expected.result.iter().map(|user| user.id).collect();
+            assert_eq!(expected.len(), 5);
+
+            let mut walked = Vec::new();
+            let mut after = Some(String::new());
+            while let Some(cursor) = after {
+                let req = test::TestRequest::default()
+                    .uri(format!("{}&per_page=2&after={}", search, cursor).as_str())
+                    .insert_header((AUTHORIZATION, authorization.as_str()))
+                    .to_request();
+                let resp = test::call_service(&app, req).await;
+                assert_eq!(resp.status(), StatusCode::OK);
+                let users: UserSearchResult = test::read_body_json(resp).await;
+                assert!(users.num_pages.is_none());
+                assert_eq!(users.prev_cursor.is_some(), !cursor.is_empty());
+                walked.extend(users.result.iter().map(|user| user.id));
+                after = users.next_cursor;
+            }
+            assert_eq!(walked, expected);
+
+            let mut walked = Vec::new();
+            let mut before = Some(String::new());
+            while let Some(cursor) = before {
+                let req = test::TestRequest::default()
+                    .uri(format!("{}&per_page=2&before={}", search, cursor).as_str())
+                    .insert_header((AUTHORIZATION, authorization.as_str()))
+                    .to_request();
+                let resp = test::call_service(&app, req).await;
+                assert_eq!(resp.status(), StatusCode::OK);
+                let users: UserSearchResult = test::read_body_json(resp).await;
+                walked.splice(0..0, users.result.iter().map(|user| user.id));
+                before = users.prev_cursor;
+            }
+            assert_eq!(walked, expected);
+        }
+
+        // test 400 with an incorrect cursor
+        {
+            for query in [
+                "after=&before=",
+                "after=&page=1",
+                "after=not-a-cursor",
+                "after=&sort=updated_at",
+            ] {
+                let req = test::TestRequest::default()
+                    .uri(format!("/api/user?{}", query).as_str())
+                    .insert_header((AUTHORIZATION, authorization.as_str()))
+                    .to_request();
+                let resp = test::call_service(&app, req).await;
+                assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+            }
+
+            // a cursor is bound to the sort it was issued for
+            let req = test::TestRequest::default()
+                .uri("/api/user?after=&per_page=1&sort=email")
+                .insert_header((AUTHORIZATION, authorization.as_str()))
+                .to_request();
+            let users: UserSearchResult =
+                test::read_body_json(test::call_service(&app, req).await).await;
+            let req = test::TestRequest::default()
+                .uri(format!("/api/user?per_page=1&after={}", users.next_cursor.unwrap()).as_str())
+                .insert_header((AUTHORIZATION, authorization.as_str()))
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+        }
+
+        // test 304 when the page did not change, 200 otherwise
+        {
+            let uri = "/api/user?sort=created_at&per_page=2";
+            let req = test::TestRequest::default()
+                .uri(uri)
+                .insert_header((AUTHORIZATION, authorization.as_str()))
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::OK);
+            let etag = resp.headers().get(ETAG).unwrap().to_owned();
+            let last_modified = resp.headers().get(LAST_MODIFIED).unwrap().to_owned();
+            assert_eq!(
+                resp.headers().get(CACHE_CONTROL).unwrap(),
+                "private, no-cache"
+            );
+
+            let req = test::TestRequest::default()
+                .uri(uri)
+                .insert_header((AUTHORIZATION, authorization.as_str()))
+                .insert_header((IF_NONE_MATCH, etag.to_owned()))
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::NOT_MODIFIED);
+            assert_eq!(resp.headers().get(ETAG), Some(&etag));
+            assert!(test::read_body(resp).await.is_empty());
+
+            let req = test::TestRequest::default()
+                .uri(uri)
+                .insert_header((AUTHORIZATION, authorization.as_str()))
+                .insert_header((IF_MODIFIED_SINCE, last_modified))
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::NOT_MODIFIED);
+
+            let req = test::TestRequest::default()
+                .uri("/api/user?sort=created_at&per_page=1")
+                .insert_header((AUTHORIZATION, authorization.as_str()))
+                .insert_header((IF_NONE_MATCH, etag))
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::OK);
+        }
+
+        // test no Last-Modified on the members of a group, joining a group is not dated
+        {
+            let req = test::TestRequest::default()
+                .uri(format!("/api/user?group={}", Uuid::new_v4()).as_str())
+                .insert_header((AUTHORIZATION, authorization.as_str()))
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::OK);
+            assert!(resp.headers().get(ETAG).is_some());
+            assert!(resp.headers().get(LAST_MODIFIED).is_none());
+        }
+
+        // test 200 when a user of the page is deleted since the client copy
+        {
+            let management = UserManagement::new(DEFAULT_ORGANIZATION_ID);
+            let user = management
+                .create(
+                    Box::new(User {
+                        id: Default::default(),
+                        first_name: String::from("Stale"),
+                        last_name: String::from("Copy"),
+                        email: String::from("stale.copy@example.com"),
+                        version: Default::default(),
+                        deleted_at: None,
+                    }),
+                    &state.db_connection,
+                )
+                .await
+                .unwrap();
+            let uri = "/api/user?pattern=stale.copy";
+            let req = test::TestRequest::default()
+                .uri(uri)
+                .insert_header((AUTHORIZATION, authorization.as_str()))
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            let etag = resp.headers().get(ETAG).unwrap().to_owned();
+            let last_modified = resp.headers().get(LAST_MODIFIED).unwrap().to_owned();
+            let users: UserSearchResult = test::read_body_json(resp).await;
+            assert_eq!(users.result.len(), 1);
+
+            // HTTP dates have no fraction of second
+            tokio::time::sleep(Duration::from_secs(1)).await;
+            management
+                .delete(user.get_id(), Precondition::Any, &state.db_connection)
+                .await
+                .unwrap();
+            let req = test::TestRequest::default()
+                .uri(uri)
+                .insert_header((AUTHORIZATION, authorization.as_str()))
+                .insert_header((IF_MODIFIED_SINCE, last_modified))
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::OK);
+            let deleted_at = resp.headers().get(LAST_MODIFIED).unwrap().to_owned();
+
+            // a purge leaves no row behind, its event dates the list
+            tokio::time::sleep(Duration::from_secs(1)).await;
+            Audited::new(
+                Box::new(administrator_id(&state).await),
+                DEFAULT_ORGANIZATION_ID,
+                None,
+                "user",
+                UserManagement::new(DEFAULT_ORGANIZATION_ID),
+            )
+            .purge(user.get_id(), &state.db_connection)
+            .await
+            .unwrap();
+            let req = test::TestRequest::default()
+                .uri(uri)
+                .insert_header((AUTHORIZATION, authorization.as_str()))
+                .insert_header((IF_MODIFIED_SINCE, deleted_at))
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::OK);
+
+            let req = test::TestRequest::default()
+                .uri(uri)
+                .insert_header((AUTHORIZATION, authorization.as_str()))
+                .insert_header((IF_NONE_MATCH, etag))
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::OK);
+            let users: UserSearchResult = test::read_body_json(resp).await;
+            assert!(users.result.is_empty());
+        }
+
+        // test 401
+        {
+            let req = test::TestRequest::default().uri("/api/user").to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+
+            let req = test::TestRequest::default()
+                .uri("/api/user")
+                .insert_header((AUTHORIZATION, "Bearer not.a.token"))
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_links() {
+        let page = |page, num_pages| UserSearchResult {
+            num_pages: Some(num_pages),
+            total_items: Some(num_pages * 10),
+            page: Some(page),
+            per_page: 10,
+            result: Vec::new(),
+            next_cursor: None,
+            prev_cursor: None,
+        };
+        let request = test::TestRequest::default()
+            .uri("/api/user?pattern=jean&page=2&per_page=10")
+            .to_http_request();
+        assert_eq!(
+            links(&request, &page(2, 3)),
+            "</api/user?pattern=jean&per_page=10&page=1>; rel=\"first\", \
+            </api/user?pattern=jean&per_page=10&page=1>; rel=\"prev\", \
+            </api/user?pattern=jean&per_page=10&page=3>; rel=\"next\", \
+            </api/user?pattern=jean&per_page=10&page=3>; rel=\"last\""
+        );
+        // past the last page, the previous page is the last one
+        let request = test::TestRequest::default()
+            .uri("/api/user?page=5")
+            .to_http_request();
+        assert_eq!(
+            links(&request, &page(5, 3)),
+            "</api/user?page=1>; rel=\"first\", \
+            </api/user?page=3>; rel=\"prev\", \
+            </api/user?page=3>; rel=\"last\""
+        );
+        // an empty list has a single page
+        assert_eq!(
+            links(&request, &page(1, 0)),
+            "</api/user?page=1>; rel=\"first\", </api/user?page=1>; rel=\"last\""
+        );
+
+        // pages read from a cursor link to the neighbouring cursors
+        let request = test::TestRequest::default()
+            .uri("/api/user?sort=-email&after=abc")
+            .to_http_request();
+        let cursor_page = UserSearchResult {
+            num_pages: None,
+            total_items: None,
+            page: None,
+            per_page: 10,
+            result: Vec::new(),
+            next_cursor: Some(String::from("next")),
+            prev_cursor: Some(String::from("prev")),
+        };
+        assert_eq!(
+            links(&request, &cursor_page),
+            "</api/user?sort=-email&after=>; rel=\"first\", \
+            </api/user?sort=-email&before=prev>; rel=\"prev\", \
+            </api/user?sort=-email&after=next>; rel=\"next\", \
+            </api/user?sort=-email&before=>; rel=\"last\""
+        );
     }
 }
@@ -0,0 +1,214 @@
+use crate::audit::request_id;
+use crate::auth::Claims;
+use crate::error::AsHttpError;
+use crate::user::{audited, entity_tag, User};
+use crate::{AppState, Management};
+use actix_web::web::{Data, Path};
+use actix_web::{post, HttpRequest, HttpResponse, Responder};
+use common::error::CoreError;
+use sea_orm::TransactionTrait;
+use std::sync::Arc;
+
+#[utoipa::path(
+tag = "Delete user",
+context_path = "/api/user",
+params(
+("user_id" = Uuid, Path, description = "User identifier")
+),
+responses((status=200, description = "User restoration succeed", body = User,
+headers(("ETag" = String, description = "Version of the user"))),
+(status=401, description = "Authentication required"),
+(status=403, description = "Permission user:restore required"),
+(status=404, description = "User not found")
+),
+security(("api_jwt_token" = []))
+)]
+#[post("/{user_id}/restore")]
+pub(super) async fn restore(
+    request: HttpRequest,
+    user_id: Path<String>,
+    claims: Claims,
+    app_data: Data<Arc<AppState>>,
+) -> actix_web::Result<impl Responder> {
+    let user_id = user_id.into_inner();
+    let transaction = app_data
+        .db_connection
+        .begin()
+        .await
+        .map_err(|e| AsHttpError::from(CoreError::UnkownError(e.to_string())))?;
+    let response = {
+        let management: Management<_, _, _, _, _, _> = Management(Box::new(audited(
+            claims.sub,
+            claims.tenant,
+            request_id(&request),
+        )));
+        management
+            .0
+            .restore(Box::new(user_id), &transaction)
+            .await
+            .map(User::from)
+            .map_err(|e| AsHttpError::from(e.get_core_error()))?
+    };
+    transaction
+        .commit()
+        .await
+        .map_err(|e| AsHttpError::from(CoreError::UnkownError(e.to_string())))?;
+    Ok(HttpResponse::Ok()
+        .insert_header(entity_tag(response.version))
+        .json(response))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{access_token, administrator_id, app_state, test_settings};
+    use crate::user::create::create;
+    use crate::user::delete::delete;
+    use crate::user::find::find;
+    use crate::user::purge::purge;
+    use crate::user::read::read;
+    use crate::user::restore::restore;
+    use crate::user::{NewUser, User, UserSearchResult};
+    use actix_web::http::header::AUTHORIZATION;
+    use actix_web::http::StatusCode;
+    use actix_web::web::Data;
+    use actix_web::{test, web, App};
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn test_user_restore() {
+        let state = app_state(test_settings()).await;
+        let authorization = format!(
+            "Bearer {}",
+            access_token(&state.settings, &administrator_id(&state).await, 60)
+        );
+
+        let app = test::init_service(
+            App::new().app_data(Data::new(Arc::clone(&state))).service(
+                web::scope("/api/user")
+                    .service(read)
+                    .service(create)
+                    .service(find)
+                    .service(delete)
+                    .service(restore)
+                    .service(purge),
+            ),
+        )
+        .await;
+
+        let new_user = NewUser {
+            first_name: "Rose".to_string(),
+            last_name: "Marie".to_string(),
+            email: "rosemarie@example.com".to_string(),
+            password: None,
+        };
+        let req = test::TestRequest::post()
+            .uri("/api/user")
+            .insert_header((AUTHORIZATION, authorization.as_str()))
+            .set_json(&new_user)
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        let user: User = test::read_body_json(resp).await;
+        let user_authorization = format!(
+            "Bearer {}",
+            access_token(&state.settings, &user.id.to_string(), 60)
+        );
+        let path = format!("/api/user/{}", user.id);
+
+        let req = test::TestRequest::delete()
+            .uri(path.as_str())
+            .insert_header((AUTHORIZATION, authorization.as_str()))
+            .to_request();
+        assert_eq!(
+            test::call_service(&app, req).await.status(),
+            StatusCode::NO_CONTENT
+        );
+        let search = format!("/api/user?pattern={}&match_mode=exact", user.email);
+
+        // test 200, a deleted user is only listed with include_deleted
+        {
+            let req = test::TestRequest::get()
+                .uri(search.as_str())
+                .insert_header((AUTHORIZATION, authorization.as_str()))
+                .to_request();
+            let users: UserSearchResult = test::call_and_read_body_json(&app, req).await;
+            assert!(users.result.is_empty());
+
+            let req = test::TestRequest::get()
+                .uri(format!("{}&include_deleted=true", search).as_str())
+                .insert_header((AUTHORIZATION, authorization.as_str()))
+                .to_request();
+            let users: UserSearchResult = test::call_and_read_body_json(&app, req).await;
+            assert_eq!(users.result.len(), 1);
+            assert!(users.result[0].deleted_at.is_some());
+
+            let req = test::TestRequest::get()
+                .uri(path.as_str())
+                .insert_header((AUTHORIZATION, authorization.as_str()))
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+        }
+
+        // test 403, only administrators see and restore deleted users
+        {
+            let req = test::TestRequest::get()
+                .uri(format!("{}&include_deleted=true", search).as_str())
+                .insert_header((AUTHORIZATION, user_authorization.as_str()))
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+
+            let req = test::TestRequest::post()
+                .uri(format!("{}/restore", path).as_str())
+                .insert_header((AUTHORIZATION, user_authorization.as_str()))
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+        }
+
+        // test 409, the email of a deleted user is not given to a new one
+        {
+            let req = test::TestRequest::post()
+                .uri("/api/user")
+                .insert_header((AUTHORIZATION, authorization.as_str()))
+                .set_json(&new_user)
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::CONFLICT);
+            let problem: serde_json::Value = test::read_body_json(resp).await;
+            assert_eq!(problem["errors"][0]["field"], "email");
+            assert_eq!(problem["errors"][0]["code"], "deleted");
+        }
+
+        // test 200, the restored user is found again
+        {
+            let req = test::TestRequest::post()
+                .uri(format!("{}/restore", path).as_str())
+                .insert_header((AUTHORIZATION, authorization.as_str()))
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::OK);
+            let restored_user: User = test::read_body_json(resp).await;
+            assert_eq!(restored_user.id, user.id);
+            assert!(restored_user.deleted_at.is_none());
+            assert!(restored_user.version > user.version);
+
+            let req = test::TestRequest::get()
+                .uri(path.as_str())
+                .insert_header((AUTHORIZATION, authorization.as_str()))
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::OK);
+        }
+
+        // test 404
+        {
+            let req = test::TestRequest::post()
+                .uri(format!("/api/user/{}/restore", uuid::Uuid::new_v4()).as_str())
+                .insert_header((AUTHORIZATION, authorization.as_str()))
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+        }
+    }
+}
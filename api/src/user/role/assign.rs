@@ -0,0 +1,203 @@
+use crate::auth::Claims;
+use crate::error::AsHttpError;
+use crate::user::role::ensure_user;
+use crate::{AppState, Authorization};
+use actix_web::web::{Data, Path};
+use actix_web::{put, HttpResponse, Responder};
+use services_local::authorization::RoleManagement;
+use std::sync::Arc;
+
+#[utoipa::path(
+tag = "User roles",
+context_path = "/api/user",
+params(
+("user_id" = Uuid, Path, description = "User identifier"),
+("role" = String, Path, description = "Role name")
+),
+responses((status=204, description = "Role assignment succeed"),
+(status=401, description = "Authentication required"),
+(status=403, description = "Permission role:update required"),
+(status=404, description = "User or role not found")
+),
+security(("api_jwt_token" = []))
+)]
+#[put("/{user_id}/role/{role}")]
+pub(super) async fn assign(
+    path: Path<(String, String)>,
+    claims: Claims,
+    app_data: Data<Arc<AppState>>,
+) -> actix_web::Result<impl Responder> {
+    let (user_id, role) = path.into_inner();
+    let authorization: Authorization<_, _> =
+        Authorization(Box::new(RoleManagement::new(claims.tenant)));
+    authorization
+        .0
+        .authorize(
+            Box::new(claims.sub.to_owned()),
+            String::from("role:update"),
+            None,
+            &app_data.db_connection,
+        )
+        .await
+        .map_err(|e| AsHttpError::from(e.get_core_error()))?;
+    ensure_user(&user_id, claims.tenant, &app_data).await?;
+    authorization
+        .0
+        .assign(Box::new(user_id), role, &app_data.db_connection)
+        .await
+        .map_err(|e| AsHttpError::from(e.get_core_error()))?;
+    Ok(HttpResponse::NoContent().finish())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{
+        access_token, administrator_id, app_state, tenant_access_token, test_settings,
+    };
+    use crate::user::create::create;
+    use crate::user::delete::delete;
+    use crate::user::role::assign::assign;
+    use crate::user::role::read::read;
+    use crate::user::{NewUser, User};
+    use actix_web::http::header::AUTHORIZATION;
+    use actix_web::http::StatusCode;
+    use actix_web::web::Data;
+    use actix_web::{test, web, App};
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn test_role_assign() {
+        let state = app_state(test_settings()).await;
+        let authorization = format!(
+            "Bearer {}",
+            access_token(&state.settings, &administrator_id(&state).await, 60)
+        );
+
+        let app = test::init_service(
+            App::new().app_data(Data::new(Arc::clone(&state))).service(
+                web::scope("/api/user")
+                    .service(create)
+                    .service(read)
+                    .service(assign)
+                    .service(delete),
+            ),
+        )
+        .await;
+
+        let new_user = NewUser {
+            first_name: "Hugo".to_string(),
+            last_name: "Bernard".to_string(),
+            email: "hugo.bernard@example.com".to_string(),
+            password: None,
+        };
+        let req = test::TestRequest::post()
+            .uri("/api/user")
+            .insert_header((AUTHORIZATION, authorization.as_str()))
+            .set_json(&new_user)
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        let user: User = test::read_body_json(resp).await;
+        let user_authorization = format!(
+            "Bearer {}",
+            access_token(&state.settings, &user.id.to_string(), 60)
+        );
+
+        // test 200, a new user holds the default role
+        {
+            let req = test::TestRequest::get()
+                .uri(format!("/api/user/{}/role", user.id).as_str())
+                .insert_header((AUTHORIZATION, user_authorization.as_str()))
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::OK);
+            let roles: Vec<String> = test::read_body_json(resp).await;
+            assert_eq!(roles, vec![String::from("user")]);
+        }
+
+        // test 403, a user cannot grant himself a role
+        {
+            let req = test::TestRequest::put()
+                .uri(format!("/api/user/{}/role/admin", user.id).as_str())
+                .insert_header((AUTHORIZATION, user_authorization.as_str()))
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+        }
+
+        // test 404
+        {
+            let req = test::TestRequest::put()
+                .uri(format!("/api/user/{}/role/unknown", user.id).as_str())
+                .insert_header((AUTHORIZATION, authorization.as_str()))
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+        }
+
+        // test 204
+        {
+            let req = test::TestRequest::put()
+                .uri(format!("/api/user/{}/role/admin", user.id).as_str())
+                .insert_header((AUTHORIZATION, authorization.as_str()))
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::NO_CONTENT);
+
+            let req = test::TestRequest::get()
+                .uri(format!("/api/user/{}/role", user.id).as_str())
+                .insert_header((AUTHORIZATION, authorization.as_str()))
+                .to_request();
+            let mut roles: Vec<String> = test::call_and_read_body_json(&app, req).await;
+            roles.sort();
+            assert_eq!(roles, vec![String::from("admin"), String::from("user")]);
+        }
+
+        // test 204, the new administrator holds the role:update permission
+        {
+            let req = test::TestRequest::put()
+                .uri(format!("/api/user/{}/role/admin", user.id).as_str())
+                .insert_header((AUTHORIZATION, user_authorization.as_str()))
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::NO_CONTENT);
+        }
+
+        // test 403, the roles of a user are not granted in another organization
+        {
+            let req = test::TestRequest::put()
+                .uri(format!("/api/user/{}/role/admin", user.id).as_str())
+                .insert_header((
+                    AUTHORIZATION,
+                    format!(
+                        "Bearer {}",
+                        tenant_access_token(
+                            &state.settings,
+                            &user.id.to_string(),
+                            uuid::Uuid::new_v4(),
+                            60
+                        )
+                    ),
+                ))
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+        }
+
+        // test 403, the token of a deleted administrator grants nothing
+        {
+            let req = test::TestRequest::delete()
+                .uri(format!("/api/user/{}", user.id).as_str())
+                .insert_header((AUTHORIZATION, authorization.as_str()))
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::NO_CONTENT);
+
+            let req = test::TestRequest::put()
+                .uri(format!("/api/user/{}/role/admin", user.id).as_str())
+                .insert_header((AUTHORIZATION, user_authorization.as_str()))
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+        }
+    }
+}
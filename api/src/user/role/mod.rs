@@ -0,0 +1,44 @@
+use crate::error::AsHttpError;
+use crate::swagger::SecurityAddon;
+use crate::{AppState, Management};
+use actix_web::web::ServiceConfig;
+use services_local::user::UserManagement;
+use utoipa::OpenApi;
+use uuid::Uuid;
+
+use crate::user::role::assign::assign as assign_role;
+use crate::user::role::read::read as read_roles;
+use crate::user::role::unassign::unassign as unassign_role;
+
+mod assign;
+mod read;
+mod unassign;
+
+pub(super) fn init(cfg: &mut ServiceConfig) {
+    cfg.service(read_roles)
+        .service(assign_role)
+        .service(unassign_role);
+}
+
+/// Fail with `ResourceNotFound` unless `user_id` is a user of the organization `tenant`,
+/// the roles being shared by every organization.
+async fn ensure_user(user_id: &str, tenant: Uuid, app_data: &AppState) -> Result<(), AsHttpError> {
+    let management: Management<_, _, _, _, _, _> =
+        Management(Box::new(UserManagement::new(tenant)));
+    management
+        .0
+        .find(Box::new(user_id.to_owned()), true, &app_data.db_connection)
+        .await
+        .map(|_| ())
+        .map_err(|e| AsHttpError::from(e.get_core_error()))
+}
+
+pub(super) fn api_docs() -> utoipa::openapi::OpenApi {
+    #[derive(OpenApi)]
+    #[openapi(
+    paths(crate::user::role::read::read,crate::user::role::assign::assign,crate::user::role::unassign::unassign),
+    modifiers(&SecurityAddon)
+    )]
+    struct ApiDocs;
+    ApiDocs::openapi()
+}
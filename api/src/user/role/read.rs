@@ -0,0 +1,49 @@
+use crate::auth::Claims;
+use crate::error::AsHttpError;
+use crate::user::role::ensure_user;
+use crate::{AppState, Authorization};
+use actix_web::web::{Data, Json, Path};
+use actix_web::{get, Responder};
+use services_local::authorization::RoleManagement;
+use std::sync::Arc;
+
+#[utoipa::path(
+tag = "User roles",
+context_path = "/api/user",
+params(
+("user_id" = Uuid, Path, description = "User identifier")
+),
+responses((status=200, description = "User roles list succeed", body = [String]),
+(status=401, description = "Authentication required"),
+(status=403, description = "Permission role:read required"),
+(status=404, description = "User not found")
+),
+security(("api_jwt_token" = []))
+)]
+#[get("/{user_id}/role")]
+pub(super) async fn read(
+    user_id: Path<String>,
+    claims: Claims,
+    app_data: Data<Arc<AppState>>,
+) -> actix_web::Result<impl Responder> {
+    let user_id = user_id.into_inner();
+    let authorization: Authorization<_, _> =
+        Authorization(Box::new(RoleManagement::new(claims.tenant)));
+    authorization
+        .0
+        .authorize(
+            Box::new(claims.sub.to_owned()),
+            String::from("role:read"),
+            Some(Box::new(user_id.to_owned())),
+            &app_data.db_connection,
+        )
+        .await
+        .map_err(|e| AsHttpError::from(e.get_core_error()))?;
+    ensure_user(&user_id, claims.tenant, &app_data).await?;
+    let roles = authorization
+        .0
+        .get_roles(Box::new(user_id), &app_data.db_connection)
+        .await
+        .map_err(|e| AsHttpError::from(e.get_core_error()))?;
+    Ok(Json(roles))
+}
@@ -0,0 +1,130 @@
+use crate::auth::Claims;
+use crate::error::AsHttpError;
+use crate::user::role::ensure_user;
+use crate::{AppState, Authorization};
+use actix_web::web::{Data, Path};
+use actix_web::{delete, HttpResponse, Responder};
+use services_local::authorization::RoleManagement;
+use std::sync::Arc;
+
+#[utoipa::path(
+tag = "User roles",
+context_path = "/api/user",
+params(
+("user_id" = Uuid, Path, description = "User identifier"),
+("role" = String, Path, description = "Role name")
+),
+responses((status=204, description = "Role removal succeed"),
+(status=401, description = "Authentication required"),
+(status=403, description = "Permission role:delete required"),
+(status=404, description = "User not found or role not held by the user")
+),
+security(("api_jwt_token" = []))
+)]
+#[delete("/{user_id}/role/{role}")]
+pub(super) async fn unassign(
+    path: Path<(String, String)>,
+    claims: Claims,
+    app_data: Data<Arc<AppState>>,
+) -> actix_web::Result<impl Responder> {
+    let (user_id, role) = path.into_inner();
+    let authorization: Authorization<_, _> =
+        Authorization(Box::new(RoleManagement::new(claims.tenant)));
+    authorization
+        .0
+        .authorize(
+            Box::new(claims.sub.to_owned()),
+            String::from("role:delete"),
+            None,
+            &app_data.db_connection,
+        )
+        .await
+        .map_err(|e| AsHttpError::from(e.get_core_error()))?;
+    ensure_user(&user_id, claims.tenant, &app_data).await?;
+    authorization
+        .0
+        .unassign(Box::new(user_id), role, &app_data.db_connection)
+        .await
+        .map_err(|e| AsHttpError::from(e.get_core_error()))?;
+    Ok(HttpResponse::NoContent().finish())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{access_token, administrator_id, app_state, test_settings};
+    use crate::user::create::create;
+    use crate::user::role::unassign::unassign;
+    use crate::user::{NewUser, User};
+    use actix_web::http::header::AUTHORIZATION;
+    use actix_web::http::StatusCode;
+    use actix_web::web::Data;
+    use actix_web::{test, web, App};
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn test_role_unassign() {
+        let state = app_state(test_settings()).await;
+        let authorization = format!(
+            "Bearer {}",
+            access_token(&state.settings, &administrator_id(&state).await, 60)
+        );
+
+        let app = test::init_service(
+            App::new()
+                .app_data(Data::new(Arc::clone(&state)))
+                .service(web::scope("/api/user").service(create).service(unassign)),
+        )
+        .await;
+
+        let new_user = NewUser {
+            first_name: "Ines".to_string(),
+            last_name: "Garnier".to_string(),
+            email: "ines.garnier@example.com".to_string(),
+            password: None,
+        };
+        let req = test::TestRequest::post()
+            .uri("/api/user")
+            .insert_header((AUTHORIZATION, authorization.as_str()))
+            .set_json(&new_user)
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        let user: User = test::read_body_json(resp).await;
+        let path = format!("/api/user/{}/role/user", user.id);
+
+        // test 403
+        {
+            let req = test::TestRequest::delete()
+                .uri(path.as_str())
+                .insert_header((
+                    AUTHORIZATION,
+                    format!(
+                        "Bearer {}",
+                        access_token(&state.settings, &user.id.to_string(), 60)
+                    ),
+                ))
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+        }
+
+        // test 204
+        {
+            let req = test::TestRequest::delete()
+                .uri(path.as_str())
+                .insert_header((AUTHORIZATION, authorization.as_str()))
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::NO_CONTENT);
+        }
+
+        // test 404 once removed
+        {
+            let req = test::TestRequest::delete()
+                .uri(path.as_str())
+                .insert_header((AUTHORIZATION, authorization.as_str()))
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+        }
+    }
+}
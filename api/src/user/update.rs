@@ -1,74 +1,94 @@
+use crate::audit::request_id;
+use crate::auth::Claims;
 use crate::error::AsHttpError;
-use crate::user::User;
+use crate::user::{audited, entity_tag, precondition, User};
 use crate::{AppState, Management};
 use actix_web::web::{Data, Json, Path};
-use actix_web::{put, Responder};
+use actix_web::{put, HttpRequest, HttpResponse, Responder};
+use common::error::CoreError;
+use sea_orm::TransactionTrait;
 use std::sync::Arc;
+use validator::Validate;
 
 #[utoipa::path(
 tag = "Update user",
 context_path = "/api/user",
 params(
-("user_id" = Uuid, Path, description = "User identifier")
+("user_id" = Uuid, Path, description = "User identifier"),
+("If-Match" = Option<String>, Header, description = "ETag of the version to update")
 ),
 request_body = User,
-responses((status=200, description = "User update succeed"),
+responses((status=200, description = "User update succeed", headers(("ETag" = String, description = "Version of the user"))),
 (status=400, description = "Sent data not correct"),
-(status=401, description = "Authentication required")
-)
+(status=401, description = "Authentication required"),
+(status=409, description = "Email already used"),
+(status=412, description = "User changed since the version given by If-Match"),
+(status=422, description = "Fields not valid")
+),
+security(("api_jwt_token" = []))
 )]
 #[put("/{user_id}")]
 pub(super) async fn update(
+    request: HttpRequest,
     user_id: Path<String>,
     user: Json<User>,
+    claims: Claims,
     app_data: Data<Arc<AppState>>,
 ) -> actix_web::Result<impl Responder> {
     let user_id = user_id.into_inner();
-    let management: Management<_, _, _, _, _> =
-        Management(Box::new(services_local::user::UserManagement));
-    let response = management
-        .0
-        .update(
-            Box::new(user_id),
-            Box::new(user.into_inner()),
-            &app_data.db_connection,
-        )
+    let user = user.into_inner();
+    user.validate().map_err(AsHttpError::from)?;
+    let transaction = app_data
+        .db_connection
+        .begin()
+        .await
+        .map_err(|e| AsHttpError::from(CoreError::UnkownError(e.to_string())))?;
+    let response = {
+        let management: Management<_, _, _, _, _, _> = Management(Box::new(audited(
+            claims.sub,
+            claims.tenant,
+            request_id(&request),
+        )));
+        management
+            .0
+            .update(
+                Box::new(user_id),
+                Box::new(user),
+                precondition(&request),
+                &transaction,
+            )
+            .await
+            .map_err(|e| AsHttpError::from(e.get_core_error()))?
+    };
+    transaction
+        .commit()
         .await
-        .map_err(|e| AsHttpError::from(e.get_core_error()))?;
-    Ok(Json(response))
+        .map_err(|e| AsHttpError::from(CoreError::UnkownError(e.to_string())))?;
+    Ok(HttpResponse::Ok()
+        .insert_header(entity_tag(response.get_version()))
+        .json(response))
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::tests::initialize_db;
+    use crate::tests::{access_token, administrator_id, app_state, test_settings};
     use crate::user::create::create;
     use crate::user::update::update;
     use crate::user::{NewUser, User};
-    use crate::AppState;
+    use actix_web::http::header::{AUTHORIZATION, ETAG, IF_MATCH};
     use actix_web::http::StatusCode;
     use actix_web::web::Data;
     use actix_web::{test, web, App};
-    use sea_orm::{ConnectOptions, Database, DatabaseConnection};
     use serde_json::json;
-    use service_config::Settings;
     use std::sync::Arc;
 
     #[tokio::test]
     async fn test_user_update() {
-        initialize_db().await;
-        std::env::set_var("APP_ENVIRONMENT", "test");
-        let settings = Settings::load().expect("Error occurs when trying to load settings");
-
-        let database_url = settings.database.get_url();
-        let mut db_connection_opt = ConnectOptions::new(database_url.to_owned());
-        db_connection_opt.sqlx_logging_level(settings.application.loglevel.to_level_filter());
-
-        let db_connection: DatabaseConnection = Database::connect(db_connection_opt)
-            .await
-            .expect("Error occurs when trying to connect to database");
-
-        let state = AppState { db_connection };
-        let state = Arc::new(state);
+        let state = app_state(test_settings()).await;
+        let authorization = format!(
+            "Bearer {}",
+            access_token(&state.settings, &administrator_id(&state).await, 60)
+        );
 
         let app = test::init_service(
             App::new()
@@ -81,31 +101,31 @@ mod tests {
             first_name: "Jean".to_string(),
             last_name: "Luc".to_string(),
             email: "jeanluc@example.com".to_string(),
+            password: None,
         };
         let req = test::TestRequest::post()
             .uri("/api/user")
+            .insert_header((AUTHORIZATION, authorization.as_str()))
             .set_json(&new_user)
             .to_request();
         let resp = test::call_service(&app, req).await;
         let user: User = test::read_body_json(resp).await;
-        println!("user id: {:?}", user.id);
 
         let mut path = String::from("/api/user/");
         path.push_str(&user.id.clone().to_string());
 
         // test 200
         {
-            let mut update_user = user;
+            let mut update_user = user.clone();
             update_user.first_name = String::from("Jane");
             let req = test::TestRequest::put()
                 .uri(path.as_str())
+                .insert_header((AUTHORIZATION, authorization.as_str()))
                 .set_json(&update_user)
                 .to_request();
             let resp = test::call_service(&app, req).await;
-            println!("status code: {:?}", resp.status().to_string());
             assert_eq!(resp.status(), StatusCode::OK);
             let response = test::read_body(resp).await;
-            println!("response body: {:?}", response);
             let updated_user: User = serde_json::from_slice(response.iter().as_slice()).unwrap();
             assert_eq!(updated_user.first_name, update_user.first_name);
             assert_eq!(updated_user.last_name, update_user.last_name);
@@ -113,19 +133,111 @@ mod tests {
             assert_eq!(updated_user.id, update_user.id);
         }
 
+        // test 200 and 403, a user can only update his own record
+        {
+            let user_authorization = format!(
+                "Bearer {}",
+                access_token(&state.settings, &user.id.to_string(), 60)
+            );
+            let mut update_user = user.clone();
+            update_user.last_name = String::from("Lucas");
+            let req = test::TestRequest::put()
+                .uri(path.as_str())
+                .insert_header((AUTHORIZATION, user_authorization.as_str()))
+                .set_json(&update_user)
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::OK);
+
+            let req = test::TestRequest::put()
+                .uri(format!("/api/user/{}", administrator_id(&state).await).as_str())
+                .insert_header((AUTHORIZATION, user_authorization.as_str()))
+                .set_json(&update_user)
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+        }
+
+        // test 422
+        {
+            let mut update_user = user.clone();
+            update_user.email = String::from("jeanluc.example.com");
+            let req = test::TestRequest::put()
+                .uri(path.as_str())
+                .insert_header((AUTHORIZATION, authorization.as_str()))
+                .set_json(&update_user)
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::UNPROCESSABLE_ENTITY);
+            let response: serde_json::Value = test::read_body_json(resp).await;
+            assert_eq!(response["errors"][0]["field"], "email");
+            assert_eq!(response["errors"][0]["code"], "invalid_format");
+        }
+
+        // test 409
+        {
+            let mut update_user = user.clone();
+            update_user.email = String::from("admin@example.com");
+            let req = test::TestRequest::put()
+                .uri(path.as_str())
+                .insert_header((AUTHORIZATION, authorization.as_str()))
+                .set_json(&update_user)
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::CONFLICT);
+            let response: serde_json::Value = test::read_body_json(resp).await;
+            assert_eq!(response["errors"][0]["field"], "email");
+        }
+
+        // test 200 and 412, If-Match must hold the current ETag
+        {
+            let req = test::TestRequest::put()
+                .uri(path.as_str())
+                .insert_header((AUTHORIZATION, authorization.as_str()))
+                .set_json(&user)
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::OK);
+            let etag = resp.headers().get(ETAG).unwrap().to_owned();
+
+            let req = test::TestRequest::put()
+                .uri(path.as_str())
+                .insert_header((AUTHORIZATION, authorization.as_str()))
+                .insert_header((IF_MATCH, etag.to_owned()))
+                .set_json(&user)
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::OK);
+            assert_ne!(resp.headers().get(ETAG), Some(&etag));
+            let updated_user: User = test::read_body_json(resp).await;
+            assert_eq!(
+                format!("\"{}\"", updated_user.version - 1).as_str(),
+                etag.to_str().unwrap()
+            );
+
+            let req = test::TestRequest::put()
+                .uri(path.as_str())
+                .insert_header((AUTHORIZATION, authorization.as_str()))
+                .insert_header((IF_MATCH, etag))
+                .set_json(&user)
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::PRECONDITION_FAILED);
+            let response: serde_json::Value = test::read_body_json(resp).await;
+            assert_eq!(response["code"], "precondition_failed");
+        }
+
         // test 400
         {
             let req = test::TestRequest::put()
                 .uri(path.as_str())
+                .insert_header((AUTHORIZATION, authorization.as_str()))
                 .set_json(json!({
                     "foo": "bar"
                 }))
                 .to_request();
             let resp = test::call_service(&app, req).await;
-            println!("status code: {:?}", resp.status().to_string());
             assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
-            let response = test::read_body(resp).await;
-            println!("response body: {:?}", response);
         }
     }
 }
@@ -0,0 +1,50 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use erased_serde::serialize_trait_object;
+
+use crate::error::CoreError;
+use crate::management::{DateRange, Pagination};
+
+/// Change of a record, written in the same transaction as the change itself.
+pub trait AuditEvent: erased_serde::Serialize + Sync + Send {
+    fn get_id(&self) -> String;
+    /// Id of the user who made the change
+    fn get_actor(&self) -> String;
+    /// Operation of `Manageable`, e.g. `create`, `update` or `delete`
+    fn get_action(&self) -> String;
+    fn get_resource(&self) -> String;
+    fn get_entity_id(&self) -> String;
+    /// JSON object of the changed fields, each with its value `before` and `after` the change
+    fn get_changes(&self) -> String;
+    /// Identifier given by the client in `X-Request-Id`
+    fn get_request_id(&self) -> Option<String>;
+    fn get_created_at(&self) -> DateTime<Utc>;
+}
+
+serialize_trait_object!(AuditEvent);
+
+/// Filters of the audit events, the events match every filter given.
+pub trait AuditSearch: Sync + Send {
+    fn get_actor(&self) -> Option<String>;
+    fn get_action(&self) -> Option<String>;
+    fn get_resource(&self) -> Option<String>;
+    fn get_entity_id(&self) -> Option<String>;
+    /// Fail with `CoreError::DataError` when the range is incomplete or invalid.
+    fn get_date_range(&self) -> Result<Option<DateRange>, CoreError>;
+    /// Fail with `CoreError::DataError` when the page or its size is out of bounds.
+    fn get_pagination(&self) -> Result<Pagination, CoreError>;
+}
+
+#[async_trait]
+pub trait Auditable<'m, B>: Sync + Send {
+    type Search;
+
+    type Result;
+
+    /// Events matching `search`, the latest first.
+    async fn search(
+        &self,
+        search: Self::Search,
+        backend: &'m B,
+    ) -> Result<Self::Result, Box<dyn crate::error::Error>>;
+}
@@ -0,0 +1,38 @@
+use async_trait::async_trait;
+
+/// Manage the roles of the users and check the permissions they grant.
+///
+/// A permission is named `<resource>:<operation>`, e.g. `user:delete`.
+/// The same name suffixed by `:own` grants the operation only on the records owned by the actor.
+#[async_trait]
+pub trait Authorizable<'m, B>: Sync + Send {
+    type Id;
+
+    /// Fail with `CoreError::OperationForbiden` unless `actor` holds `permission`,
+    /// or holds `<permission>:own` and is the `owner` of the record.
+    async fn authorize(
+        &self,
+        actor: Self::Id,
+        permission: String,
+        owner: Option<Self::Id>,
+        backend: &'m B,
+    ) -> Result<(), Box<dyn crate::error::Error>>;
+    async fn get_roles(
+        &self,
+        id: Self::Id,
+        backend: &'m B,
+    ) -> Result<Vec<String>, Box<dyn crate::error::Error>>;
+    /// Grant `role` to `id`, granting a role already held does nothing.
+    async fn assign(
+        &self,
+        id: Self::Id,
+        role: String,
+        backend: &'m B,
+    ) -> Result<(), Box<dyn crate::error::Error>>;
+    async fn unassign(
+        &self,
+        id: Self::Id,
+        role: String,
+        backend: &'m B,
+    ) -> Result<(), Box<dyn crate::error::Error>>;
+}
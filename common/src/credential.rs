@@ -0,0 +1,36 @@
+use async_trait::async_trait;
+
+/// Manage the credentials used to authenticate a user.
+#[async_trait]
+pub trait Authenticable<'m, B>: Sync + Send {
+    type Id;
+
+    type User;
+
+    /// Find the user owning `login`, without checking any password.
+    async fn identify(
+        &self,
+        login: String,
+        backend: &'m B,
+    ) -> Result<Option<Self::User>, Box<dyn crate::error::Error>>;
+    async fn authenticate(
+        &self,
+        login: String,
+        password: String,
+        backend: &'m B,
+    ) -> Result<Self::User, Box<dyn crate::error::Error>>;
+    async fn set_password(
+        &self,
+        id: Self::Id,
+        password: String,
+        backend: &'m B,
+    ) -> Result<(), Box<dyn crate::error::Error>>;
+    /// Replace the password of `id`, `current_password` must match when a password is already set.
+    async fn change_password(
+        &self,
+        id: Self::Id,
+        current_password: String,
+        new_password: String,
+        backend: &'m B,
+    ) -> Result<(), Box<dyn crate::error::Error>>;
+}
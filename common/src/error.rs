@@ -14,6 +14,51 @@ pub enum CoreError {
     OperationForbiden(String),
     #[error("{0}")]
     UnkownError(String),
+    #[error("Invalid fields: {}", .0.iter().map(|e| e.get_field()).collect::<Vec<_>>().join(", "))]
+    ValidationError(Vec<FieldError>),
+    /// The resource changed since the version the operation was meant for
+    #[error("{0}")]
+    PreconditionFailed(String),
+    /// A unique field holds a value already used by another resource
+    #[error("{}", .0.get_message())]
+    Conflict(FieldError),
+    /// None of the representations the client accepts can be produced
+    #[error("{0}")]
+    NotAcceptable(String),
+    /// The media type of the sent content can not be read
+    #[error("{0}")]
+    UnsupportedMediaType(String),
+}
+
+/// Rule broken by the value of a field, `code` is stable and meant for machines
+/// while `message` is meant for humans.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct FieldError {
+    field: String,
+    code: String,
+    message: String,
+}
+
+impl FieldError {
+    pub fn new(field: &str, code: &str, message: &str) -> Self {
+        Self {
+            field: String::from(field),
+            code: String::from(code),
+            message: String::from(message),
+        }
+    }
+
+    pub fn get_field(&self) -> &str {
+        self.field.as_str()
+    }
+
+    pub fn get_code(&self) -> &str {
+        self.code.as_str()
+    }
+
+    pub fn get_message(&self) -> &str {
+        self.message.as_str()
+    }
 }
 
 /// This trait is used to convert Error to CoreError
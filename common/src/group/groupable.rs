@@ -0,0 +1,16 @@
+use chrono::{DateTime, Utc};
+use erased_serde::serialize_trait_object;
+use std::fmt::Display;
+pub trait Groupable: erased_serde::Serialize + Sync + Send {
+    fn get_id(&self) -> Box<dyn Display + Sync + Send>;
+    fn get_name(&self) -> String;
+    fn get_description(&self) -> Option<String>;
+    /// Incremented on every change of the group
+    fn get_version(&self) -> i32;
+    /// Date of the last update, or of the creation, unknown until the group is saved
+    fn get_last_modified(&self) -> Option<DateTime<Utc>>;
+    /// Set while the group is deleted and can still be restored
+    fn get_deleted_at(&self) -> Option<DateTime<Utc>>;
+}
+
+serialize_trait_object!(Groupable);
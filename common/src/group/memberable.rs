@@ -0,0 +1,23 @@
+use async_trait::async_trait;
+
+/// Manage the users belonging to the groups.
+#[async_trait]
+pub trait Memberable<'m, B>: Sync + Send {
+    type Id;
+
+    /// Add the user `member` to `group`, adding a member twice does nothing.
+    /// Fail with `CoreError::ResourceNotFound` when the group or the user does not exist or is deleted.
+    async fn add_member(
+        &self,
+        group: Self::Id,
+        member: Self::Id,
+        backend: &'m B,
+    ) -> Result<(), Box<dyn crate::error::Error>>;
+    /// Fail with `CoreError::ResourceNotFound` when `member` does not belong to `group`.
+    async fn remove_member(
+        &self,
+        group: Self::Id,
+        member: Self::Id,
+        backend: &'m B,
+    ) -> Result<(), Box<dyn crate::error::Error>>;
+}
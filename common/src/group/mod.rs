@@ -0,0 +1,3 @@
+pub mod groupable;
+pub mod memberable;
+pub mod patchable;
@@ -0,0 +1,6 @@
+/// Changes to apply to a group, a field is left unchanged when its getter returns `None`.
+pub trait Patchable: Sync + Send {
+    fn get_name(&self) -> Option<String>;
+    /// `Some(None)` removes the description
+    fn get_description(&self) -> Option<Option<String>>;
+}
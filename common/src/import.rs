@@ -0,0 +1,143 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImportStatus {
+    Running,
+    Completed,
+    /// Stopped before the last row, the rows already imported are kept
+    Failed,
+}
+
+impl ImportStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Running => "running",
+            Self::Completed => "completed",
+            Self::Failed => "failed",
+        }
+    }
+
+    /// Status named `name` by `as_str`, an unknown name is a failure.
+    pub fn from_name(name: &str) -> Self {
+        match name {
+            "running" => Self::Running,
+            "completed" => Self::Completed,
+            _ => Self::Failed,
+        }
+    }
+}
+
+/// Progress of an import, counted row by row.
+pub trait ImportProgress: Sync + Send {
+    fn get_id(&self) -> String;
+    fn get_status(&self) -> ImportStatus;
+    /// Every row is checked and applied, then undone
+    fn get_dry_run(&self) -> bool;
+    /// The rows update the users already owning their email
+    fn get_upsert(&self) -> bool;
+    fn get_total_rows(&self) -> usize;
+    fn get_processed_rows(&self) -> usize;
+    fn get_created(&self) -> usize;
+    fn get_updated(&self) -> usize;
+    fn get_failed(&self) -> usize;
+    fn get_created_at(&self) -> DateTime<Utc>;
+    fn get_finished_at(&self) -> Option<DateTime<Utc>>;
+}
+
+/// Reason a row of an import failed, a row breaking several rules has one per rule.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct RowError {
+    line: u64,
+    status: u16,
+    code: String,
+    field: Option<String>,
+    message: String,
+}
+
+impl RowError {
+    pub fn new(line: u64, status: u16, code: &str, field: Option<&str>, message: &str) -> Self {
+        Self {
+            line,
+            status,
+            code: String::from(code),
+            field: field.map(String::from),
+            message: String::from(message),
+        }
+    }
+
+    /// Line of the file, starting from 1
+    pub fn get_line(&self) -> u64 {
+        self.line
+    }
+
+    /// HTTP status the row would have been answered with
+    pub fn get_status(&self) -> u16 {
+        self.status
+    }
+
+    pub fn get_code(&self) -> &str {
+        self.code.as_str()
+    }
+
+    pub fn get_field(&self) -> Option<&str> {
+        self.field.as_deref()
+    }
+
+    pub fn get_message(&self) -> &str {
+        self.message.as_str()
+    }
+}
+
+/// What became of a row
+pub enum RowOutcome {
+    Created,
+    Updated,
+    Failed(Vec<RowError>),
+}
+
+/// Follow the imports of an organization, kept by the backend so that any instance reports them.
+#[async_trait]
+pub trait Importable<'m, B>: Sync + Send {
+    type Id;
+
+    /// Record the import of `total_rows` rows started by `actor`, running until `finish` is called.
+    /// The imports finished for a day are forgotten.
+    async fn start(
+        &self,
+        actor: String,
+        dry_run: bool,
+        upsert: bool,
+        total_rows: usize,
+        backend: &'m B,
+    ) -> Result<Box<dyn ImportProgress>, Box<dyn crate::error::Error>>;
+    /// Count the `outcome` of the next row of the import `id`.
+    async fn progress(
+        &self,
+        id: Self::Id,
+        outcome: RowOutcome,
+        backend: &'m B,
+    ) -> Result<(), Box<dyn crate::error::Error>>;
+    async fn finish(
+        &self,
+        id: Self::Id,
+        status: ImportStatus,
+        backend: &'m B,
+    ) -> Result<(), Box<dyn crate::error::Error>>;
+    /// Import `id` started by `actor`, failed when it stopped counting rows while running.
+    /// Fail with `CoreError::ResourceNotFound` when it does not exist, is forgotten or was started by another user.
+    async fn find(
+        &self,
+        id: Self::Id,
+        actor: String,
+        backend: &'m B,
+    ) -> Result<Box<dyn ImportProgress>, Box<dyn crate::error::Error>>;
+    /// Failures of the rows of the import `id` started by `actor`, by line.
+    /// Fail with `CoreError::ResourceNotFound` like `find`.
+    async fn errors(
+        &self,
+        id: Self::Id,
+        actor: String,
+        backend: &'m B,
+    ) -> Result<Vec<RowError>, Box<dyn crate::error::Error>>;
+}
@@ -1,3 +1,10 @@
+pub mod audit;
+pub mod authorization;
+pub mod credential;
 pub mod error;
+pub mod group;
+pub mod import;
 pub mod management;
+pub mod organization;
+pub mod token;
 pub mod user;
@@ -1,7 +1,27 @@
 use async_trait::async_trait;
 use chrono::{DateTime, Utc};
 
+use crate::error::CoreError;
 use std::fmt::Display;
+use std::str::FromStr;
+
+/// Versions of a resource a change is meant for, read from an `If-Match` header.
+/// `Any` applies the change whatever the current version.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub enum Precondition {
+    #[default]
+    Any,
+    Versions(Vec<i32>),
+}
+
+impl Precondition {
+    pub fn is_met(&self, version: i32) -> bool {
+        match self {
+            Self::Any => true,
+            Self::Versions(versions) => versions.contains(&version),
+        }
+    }
+}
 
 #[async_trait]
 pub trait Manageable<'m, B>: Sync + Send {
@@ -13,6 +33,8 @@ pub trait Manageable<'m, B>: Sync + Send {
 
     type Result;
 
+    type Patch;
+
     async fn create(
         &self,
         data: Self::Data,
@@ -23,17 +45,303 @@ pub trait Manageable<'m, B>: Sync + Send {
         search_opt: Self::Search,
         backend: &'m B,
     ) -> Result<Self::Result, Box<dyn crate::error::Error>>;
+    /// Fail with `CoreError::ResourceNotFound` when no record has this id,
+    /// deleted records are only found with `include_deleted`.
+    async fn find(
+        &self,
+        id: Self::Id,
+        include_deleted: bool,
+        backend: &'m B,
+    ) -> Result<Self::Data, Box<dyn crate::error::Error>>;
+    /// Fail with `CoreError::PreconditionFailed` when `precondition` is not met.
     async fn update(
         &self,
         id: Self::Id,
         data: Self::Data,
+        precondition: Precondition,
         backend: &'m B,
     ) -> Result<Self::Data, Box<dyn crate::error::Error>>;
+    /// Only change the fields given by `patch`, the others keep their value.
+    /// Fail with `CoreError::PreconditionFailed` when `precondition` is not met.
+    async fn patch(
+        &self,
+        id: Self::Id,
+        patch: Self::Patch,
+        precondition: Precondition,
+        backend: &'m B,
+    ) -> Result<Self::Data, Box<dyn crate::error::Error>>;
+    /// Mark the record as deleted, it is left out of `read` and `find` until restored.
+    /// Fail with `CoreError::PreconditionFailed` when `precondition` is not met.
     async fn delete(
         &self,
         id: Self::Id,
+        precondition: Precondition,
         backend: &'m B,
     ) -> Result<(), Box<dyn crate::error::Error>>;
+    /// Bring back a deleted record, a record which is not deleted is left as it is.
+    async fn restore(
+        &self,
+        id: Self::Id,
+        backend: &'m B,
+    ) -> Result<Self::Data, Box<dyn crate::error::Error>>;
+    /// Remove a deleted record for good.
+    /// Fail with `CoreError::DataError` when the record is not deleted first.
+    async fn purge(&self, id: Self::Id, backend: &'m B)
+        -> Result<(), Box<dyn crate::error::Error>>;
+}
+
+/// Date a `DateRange` applies to
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum DateField {
+    #[default]
+    CreatedAt,
+    UpdatedAt,
+}
+
+impl FromStr for DateField {
+    type Err = CoreError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "created_at" => Ok(Self::CreatedAt),
+            "updated_at" => Ok(Self::UpdatedAt),
+            other => Err(CoreError::DataError(format!(
+                "{} is not a supported date field. Use either `created_at` or `updated_at`.",
+                other
+            ))),
+        }
+    }
+}
+
+/// Inclusive range of dates, the start is never after the end.
+/// Example:
+/// ```
+/// use chrono::{Duration, Utc};
+/// use common::management::{DateField, DateRange};
+/// # fn main() {
+/// let now = Utc::now();
+/// assert!(DateRange::new(DateField::CreatedAt, now - Duration::days(1), now).is_ok());
+/// assert!(DateRange::new(DateField::CreatedAt, now, now - Duration::days(1)).is_err());
+/// # }
+/// ```
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct DateRange {
+    field: DateField,
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+}
+
+impl DateRange {
+    pub fn new(
+        field: DateField,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> Result<Self, CoreError> {
+        if start > end {
+            return Err(CoreError::DataError(String::from(
+                "start_date must not be after end_date",
+            )));
+        }
+        Ok(Self { field, start, end })
+    }
+
+    pub fn get_field(&self) -> DateField {
+        self.field
+    }
+
+    pub fn get_start(&self) -> DateTime<Utc> {
+        self.start
+    }
+
+    pub fn get_end(&self) -> DateTime<Utc> {
+        self.end
+    }
+}
+
+/// How a search pattern is compared, always ignoring case
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum MatchMode {
+    Exact,
+    Prefix,
+    #[default]
+    Contains,
+}
+
+impl FromStr for MatchMode {
+    type Err = CoreError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "exact" => Ok(Self::Exact),
+            "prefix" => Ok(Self::Prefix),
+            "contains" => Ok(Self::Contains),
+            other => Err(CoreError::DataError(format!(
+                "{} is not a supported match mode. Use either `exact`, `prefix` or `contains`.",
+                other
+            ))),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum SortDirection {
+    #[default]
+    Ascending,
+    Descending,
+}
+
+/// Field to sort on, written `field` to sort in ascending order and `-field` in descending order.
+/// Example:
+/// ```
+/// use common::management::{Sort, SortDirection};
+/// use std::str::FromStr;
+/// # fn main() {
+/// let sort = Sort::from_str("-created_at").unwrap();
+/// assert_eq!(sort.get_field(), "created_at");
+/// assert_eq!(sort.get_direction(), SortDirection::Descending);
+/// assert!(Sort::from_str("-").is_err());
+/// # }
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Sort {
+    field: String,
+    direction: SortDirection,
+}
+
+impl Sort {
+    pub fn get_field(&self) -> &str {
+        self.field.as_str()
+    }
+
+    pub fn get_direction(&self) -> SortDirection {
+        self.direction
+    }
+}
+
+impl FromStr for Sort {
+    type Err = CoreError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let (field, direction) = match s.strip_prefix('-') {
+            Some(field) => (field, SortDirection::Descending),
+            None => (s.strip_prefix('+').unwrap_or(s), SortDirection::Ascending),
+        };
+        if field.is_empty() {
+            return Err(CoreError::DataError(String::from(
+                "A sort field must not be empty",
+            )));
+        }
+        Ok(Self {
+            field: String::from(field),
+            direction,
+        })
+    }
+}
+
+/// Condition on a single field, compared like a search pattern.
+/// Example:
+/// ```
+/// use common::management::{Filter, MatchMode};
+/// # fn main() {
+/// let filter = Filter::new("email", MatchMode::Exact, "jane@example.com");
+/// assert_eq!(filter.get_field(), "email");
+/// assert_eq!(filter.get_match_mode(), MatchMode::Exact);
+/// # }
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Filter {
+    field: String,
+    match_mode: MatchMode,
+    value: String,
+}
+
+impl Filter {
+    pub fn new(field: &str, match_mode: MatchMode, value: &str) -> Self {
+        Self {
+            field: String::from(field),
+            match_mode,
+            value: String::from(value),
+        }
+    }
+
+    pub fn get_field(&self) -> &str {
+        self.field.as_str()
+    }
+
+    pub fn get_match_mode(&self) -> MatchMode {
+        self.match_mode
+    }
+
+    pub fn get_value(&self) -> &str {
+        self.value.as_str()
+    }
+}
+
+/// Page of a list, numbered from 1, holding between 1 and a maximum number of items.
+/// Example:
+/// ```
+/// use common::management::Pagination;
+/// # fn main() {
+/// let pagination = Pagination::new(None, None, 25, 100).unwrap();
+/// assert_eq!((pagination.get_page(), pagination.get_per_page()), (1, 25));
+/// assert!(Pagination::new(Some(0), None, 25, 100).is_err());
+/// assert!(Pagination::new(None, Some(101), 25, 100).is_err());
+/// # }
+/// ```
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Pagination {
+    page: usize,
+    per_page: usize,
+}
+
+impl Pagination {
+    /// `default_per_page` is used when `per_page` is not given.
+    pub fn new(
+        page: Option<usize>,
+        per_page: Option<usize>,
+        default_per_page: usize,
+        max_per_page: usize,
+    ) -> Result<Self, CoreError> {
+        let page = page.unwrap_or(1);
+        let per_page = per_page.unwrap_or(default_per_page);
+        if page == 0 {
+            return Err(CoreError::DataError(String::from(
+                "page must be greater than or equal to 1",
+            )));
+        }
+        if per_page == 0 || per_page > max_per_page {
+            return Err(CoreError::DataError(format!(
+                "per_page must be between 1 and {}",
+                max_per_page
+            )));
+        }
+        if page
+            .checked_mul(per_page)
+            .and_then(|end| u64::try_from(end).ok())
+            .is_none()
+        {
+            return Err(CoreError::DataError(String::from("page is too large")));
+        }
+        Ok(Self { page, per_page })
+    }
+
+    pub fn get_page(&self) -> usize {
+        self.page
+    }
+
+    pub fn get_per_page(&self) -> usize {
+        self.per_page
+    }
+}
+
+/// Position in a sorted list from which a page is read instead of a page number.
+/// The token is opaque, it comes from `SearchResult::get_next_cursor` or `SearchResult::get_prev_cursor`,
+/// an empty token starts from the first (`After`) or the last (`Before`) item.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Cursor {
+    After(String),
+    Before(String),
 }
 
 #[async_trait]
@@ -41,16 +349,45 @@ pub trait Searchable: Sync + Send {
     type Id;
     fn get_id(&self) -> Option<Self::Id>;
     fn get_pattern(&self) -> Option<Box<dyn Display + Sync + Send>>;
-    fn get_date_range(&self) -> Option<(DateTime<Utc>, DateTime<Utc>)>;
-    fn get_page(&self) -> usize;
-    fn get_per_page(&self) -> usize;
+    /// Fail with `CoreError::DataError` when the match mode is unknown.
+    fn get_match_mode(&self) -> Result<MatchMode, CoreError>;
+    /// Conditions on single fields every record meets, on top of the pattern.
+    fn get_filters(&self) -> Vec<Filter>;
+    /// Fail with `CoreError::DataError` when the range is incomplete or invalid.
+    fn get_date_range(&self) -> Result<Option<DateRange>, CoreError>;
+    /// Fields to sort on, by decreasing priority.
+    /// Fail with `CoreError::DataError` when a field is malformed.
+    fn get_sort(&self) -> Result<Vec<Sort>, CoreError>;
+    /// Fail with `CoreError::DataError` when both a cursor and a page are given.
+    fn get_cursor(&self) -> Result<Option<Cursor>, CoreError>;
+    /// Fail with `CoreError::DataError` when the page or its size is out of bounds.
+    fn get_pagination(&self) -> Result<Pagination, CoreError>;
+    /// Also return the deleted records
+    fn get_include_deleted(&self) -> bool;
 }
 
 #[async_trait]
 pub trait SearchResult: Sync + Send {
     type Result;
 
-    fn get_num_pages(&self) -> usize;
+    /// Not counted when the page is read from a cursor.
+    fn get_num_pages(&self) -> Option<usize>;
+
+    /// Not counted when the page is read from a cursor.
+    fn get_total_items(&self) -> Option<usize>;
+
+    /// Page number, starting from 1, absent when the page is read from a cursor.
+    fn get_page(&self) -> Option<usize>;
+
+    fn get_per_page(&self) -> usize;
+
+    fn get_next_cursor(&self) -> Option<String>;
+
+    fn get_prev_cursor(&self) -> Option<String>;
+
+    /// Date the page can not have changed since, creations, updates and deletions included.
+    /// Absent when a change can leave no date behind, e.g. a purge.
+    fn get_last_modified(&self) -> Option<DateTime<Utc>>;
 
     fn get_result(&self) -> Box<dyn Iterator<Item = Self::Result>>;
 }
@@ -0,0 +1,23 @@
+use async_trait::async_trait;
+
+/// Manage the organizations, each one isolating its own users, groups and audit events.
+#[async_trait]
+pub trait Organizable<'m, B>: Sync + Send {
+    type Id;
+
+    /// Create the organization `name`, named `slug` in the `X-Tenant` header and in the subdomains.
+    /// Fail with `CoreError::DataError` when the slug is not made of lowercase letters, digits and dashes.
+    async fn create(
+        &self,
+        name: String,
+        slug: String,
+        backend: &'m B,
+    ) -> Result<Self::Id, Box<dyn crate::error::Error>>;
+    /// Organization named `slug`.
+    /// Fail with `CoreError::ResourceNotFound` when no organization is named `slug`.
+    async fn resolve(
+        &self,
+        slug: String,
+        backend: &'m B,
+    ) -> Result<Self::Id, Box<dyn crate::error::Error>>;
+}
@@ -0,0 +1,30 @@
+use async_trait::async_trait;
+
+/// Manage the refresh tokens exchanged for new access tokens.
+/// Tokens obtained by rotating each other belong to the same family.
+#[async_trait]
+pub trait Refreshable<'m, B>: Sync + Send {
+    type Id;
+
+    /// Issue a token opening a new family for the user `id`, valid for `lifetime` seconds.
+    async fn issue(
+        &self,
+        id: Self::Id,
+        lifetime: u64,
+        backend: &'m B,
+    ) -> Result<String, Box<dyn crate::error::Error>>;
+    /// Exchange `token` for a new token of the same family and return it with its owner.
+    /// Presenting a token already exchanged revokes its whole family.
+    async fn rotate(
+        &self,
+        token: String,
+        lifetime: u64,
+        backend: &'m B,
+    ) -> Result<(Self::Id, String), Box<dyn crate::error::Error>>;
+    /// Revoke the whole family of `token`.
+    async fn revoke(
+        &self,
+        token: String,
+        backend: &'m B,
+    ) -> Result<(), Box<dyn crate::error::Error>>;
+}
@@ -1 +1,2 @@
+pub mod patchable;
 pub mod userable;
@@ -0,0 +1,6 @@
+/// Changes to apply to a user, a field is left unchanged when its getter returns `None`.
+pub trait Patchable: Sync + Send {
+    fn get_first_name(&self) -> Option<String>;
+    fn get_lastname(&self) -> Option<String>;
+    fn get_email(&self) -> Option<String>;
+}
@@ -1,3 +1,4 @@
+use chrono::{DateTime, Utc};
 use erased_serde::serialize_trait_object;
 use std::fmt::Display;
 pub trait Userable: erased_serde::Serialize + Sync + Send {
@@ -5,6 +6,12 @@ pub trait Userable: erased_serde::Serialize + Sync + Send {
     fn get_first_name(&self) -> String;
     fn get_lastname(&self) -> String;
     fn get_email(&self) -> String;
+    /// Incremented on every change of the user
+    fn get_version(&self) -> i32;
+    /// Date of the last update, or of the creation, unknown until the user is saved
+    fn get_last_modified(&self) -> Option<DateTime<Utc>>;
+    /// Set while the user is deleted and can still be restored
+    fn get_deleted_at(&self) -> Option<DateTime<Utc>>;
 }
 
 serialize_trait_object!(Userable);
@@ -1,4 +1,16 @@
 //! `SeaORM` Entity. Generated by sea-orm-codegen 0.12.10
 
+pub mod audit_event;
+pub mod credential;
+pub mod group;
+pub mod import_job;
+pub mod import_job_error;
+pub mod organization;
+pub mod permission;
 pub mod prelude;
+pub mod refresh_token;
+pub mod role;
+pub mod role_permission;
 pub mod user;
+pub mod user_group;
+pub mod user_role;
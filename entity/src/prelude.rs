@@ -1,3 +1,15 @@
 //! `SeaORM` Entity. Generated by sea-orm-codegen 0.12.10
 
+pub use super::audit_event::Entity as AuditEvent;
+pub use super::credential::Entity as Credential;
+pub use super::group::Entity as Group;
+pub use super::import_job::Entity as ImportJob;
+pub use super::import_job_error::Entity as ImportJobError;
+pub use super::organization::Entity as Organization;
+pub use super::permission::Entity as Permission;
+pub use super::refresh_token::Entity as RefreshToken;
+pub use super::role::Entity as Role;
+pub use super::role_permission::Entity as RolePermission;
 pub use super::user::Entity as User;
+pub use super::user_group::Entity as UserGroup;
+pub use super::user_role::Entity as UserRole;
@@ -17,14 +17,86 @@ pub struct Model {
     pub id: Uuid,
     pub first_name: String,
     pub last_name: String,
-    #[sea_orm(unique)]
+    /// Unique among the users of the same organization
     pub email: String,
     pub created_at: chrono::DateTime<Utc>,
     pub updated_at: Option<chrono::DateTime<Utc>>,
+    pub version: i32,
+    /// Set when the user is deleted, the row is only removed when purged
+    pub deleted_at: Option<chrono::DateTime<Utc>>,
+    /// Organization the user belongs to, the user is never seen from another one
+    pub tenant_id: Uuid,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
-pub enum Relation {}
+pub enum Relation {
+    #[sea_orm(has_one = "super::credential::Entity")]
+    Credential,
+    #[sea_orm(has_many = "super::refresh_token::Entity")]
+    RefreshToken,
+    #[sea_orm(has_many = "super::user_role::Entity")]
+    UserRole,
+    #[sea_orm(has_many = "super::user_group::Entity")]
+    UserGroup,
+    #[sea_orm(
+        belongs_to = "super::organization::Entity",
+        from = "Column::TenantId",
+        to = "super::organization::Column::Id",
+        on_update = "NoAction",
+        on_delete = "Cascade"
+    )]
+    Organization,
+}
+
+impl Related<super::credential::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Credential.def()
+    }
+}
+
+impl Related<super::refresh_token::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::RefreshToken.def()
+    }
+}
+
+impl Related<super::user_role::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::UserRole.def()
+    }
+}
+
+impl Related<super::role::Entity> for Entity {
+    fn to() -> RelationDef {
+        super::user_role::Relation::Role.def()
+    }
+
+    fn via() -> Option<RelationDef> {
+        Some(super::user_role::Relation::User.def().rev())
+    }
+}
+
+impl Related<super::organization::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Organization.def()
+    }
+}
+
+impl Related<super::user_group::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::UserGroup.def()
+    }
+}
+
+impl Related<super::group::Entity> for Entity {
+    fn to() -> RelationDef {
+        super::user_group::Relation::Group.def()
+    }
+
+    fn via() -> Option<RelationDef> {
+        Some(super::user_group::Relation::User.def().rev())
+    }
+}
 
 #[async_trait]
 impl ActiveModelBehavior for ActiveModel {
@@ -36,6 +108,8 @@ impl ActiveModelBehavior for ActiveModel {
         if insert {
             model.created_at = sea_orm::ActiveValue::Set(Utc::now());
             model.updated_at = sea_orm::ActiveValue::Set(None);
+            model.version = sea_orm::ActiveValue::Set(1);
+            model.deleted_at = sea_orm::ActiveValue::Set(None);
         } else {
             model.created_at = sea_orm::ActiveValue::Unchanged(Utc::now());
             model.updated_at = sea_orm::ActiveValue::Set(Some(Utc::now()));
@@ -60,4 +134,16 @@ impl Userable for Model {
     fn get_email(&self) -> String {
         self.email.to_owned()
     }
+
+    fn get_version(&self) -> i32 {
+        self.version
+    }
+
+    fn get_last_modified(&self) -> Option<chrono::DateTime<Utc>> {
+        Some(self.updated_at.unwrap_or(self.created_at))
+    }
+
+    fn get_deleted_at(&self) -> Option<chrono::DateTime<Utc>> {
+        self.deleted_at
+    }
 }
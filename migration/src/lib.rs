@@ -1,11 +1,31 @@
 pub use sea_orm_migration::prelude::*;
 
 mod m20231029_000001_create_user_table;
+mod m20261018_000002_create_credential_table;
+mod m20261018_000003_create_refresh_token_table;
+mod m20261018_000004_create_role_tables;
+mod m20261018_000005_add_user_version;
+mod m20261018_000006_add_user_deleted_at;
+mod m20261018_000007_create_audit_event_table;
+mod m20261018_000008_create_group_tables;
+mod m20261018_000009_create_organization_table;
+mod m20261018_000010_create_import_job_tables;
 pub struct Migrator;
 
 #[async_trait::async_trait]
 impl MigratorTrait for Migrator {
     fn migrations() -> Vec<Box<dyn MigrationTrait>> {
-        vec![Box::new(m20231029_000001_create_user_table::Migration)]
+        vec![
+            Box::new(m20231029_000001_create_user_table::Migration),
+            Box::new(m20261018_000002_create_credential_table::Migration),
+            Box::new(m20261018_000003_create_refresh_token_table::Migration),
+            Box::new(m20261018_000004_create_role_tables::Migration),
+            Box::new(m20261018_000005_add_user_version::Migration),
+            Box::new(m20261018_000006_add_user_deleted_at::Migration),
+            Box::new(m20261018_000007_create_audit_event_table::Migration),
+            Box::new(m20261018_000008_create_group_tables::Migration),
+            Box::new(m20261018_000009_create_organization_table::Migration),
+            Box::new(m20261018_000010_create_import_job_tables::Migration),
+        ]
     }
 }
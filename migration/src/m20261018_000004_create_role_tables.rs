@@ -0,0 +1,245 @@
+use sea_orm_migration::prelude::*;
+use uuid::Uuid;
+
+use crate::m20231029_000001_create_user_table::User;
+
+pub const ADMIN_ROLE: &str = "admin";
+pub const USER_ROLE: &str = "user";
+
+/// Permissions granted to the `admin` role.
+const ADMIN_PERMISSIONS: [&str; 7] = [
+    "user:create",
+    "user:read",
+    "user:update",
+    "user:delete",
+    "role:read",
+    "role:update",
+    "role:delete",
+];
+
+/// Permissions granted to the `user` role, `:own` ones apply only to the user himself.
+const USER_PERMISSIONS: [&str; 3] = ["user:read", "user:update:own", "role:read:own"];
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(Role::Table)
+                    .if_not_exists()
+                    .col(ColumnDef::new(Role::Id).uuid().not_null().primary_key())
+                    .col(ColumnDef::new(Role::Name).string().not_null().unique_key())
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .create_table(
+                Table::create()
+                    .table(Permission::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(Permission::Id)
+                            .uuid()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(Permission::Name)
+                            .string()
+                            .not_null()
+                            .unique_key(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .create_table(
+                Table::create()
+                    .table(RolePermission::Table)
+                    .if_not_exists()
+                    .col(ColumnDef::new(RolePermission::RoleId).uuid().not_null())
+                    .col(
+                        ColumnDef::new(RolePermission::PermissionId)
+                            .uuid()
+                            .not_null(),
+                    )
+                    .primary_key(
+                        Index::create()
+                            .col(RolePermission::RoleId)
+                            .col(RolePermission::PermissionId),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_role_permission_role_id")
+                            .from(RolePermission::Table, RolePermission::RoleId)
+                            .to(Role::Table, Role::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_role_permission_permission_id")
+                            .from(RolePermission::Table, RolePermission::PermissionId)
+                            .to(Permission::Table, Permission::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .create_table(
+                Table::create()
+                    .table(UserRole::Table)
+                    .if_not_exists()
+                    .col(ColumnDef::new(UserRole::UserId).uuid().not_null())
+                    .col(ColumnDef::new(UserRole::RoleId).uuid().not_null())
+                    .primary_key(Index::create().col(UserRole::UserId).col(UserRole::RoleId))
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_user_role_user_id")
+                            .from(UserRole::Table, UserRole::UserId)
+                            .to(User::Table, User::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_user_role_role_id")
+                            .from(UserRole::Table, UserRole::RoleId)
+                            .to(Role::Table, Role::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        let admin_role_id = insert_role(manager, ADMIN_ROLE).await?;
+        let user_role_id = insert_role(manager, USER_ROLE).await?;
+        for permission in ADMIN_PERMISSIONS {
+            let permission_id = insert_permission(manager, permission).await?;
+            grant(manager, admin_role_id, permission_id).await?;
+            if USER_PERMISSIONS.contains(&permission) {
+                grant(manager, user_role_id, permission_id).await?;
+            }
+        }
+        for permission in USER_PERMISSIONS
+            .into_iter()
+            .filter(|permission| !ADMIN_PERMISSIONS.contains(permission))
+        {
+            let permission_id = insert_permission(manager, permission).await?;
+            grant(manager, user_role_id, permission_id).await?;
+        }
+
+        // Users created before roles existed keep the access they had to their own record.
+        manager
+            .exec_stmt(
+                Query::insert()
+                    .into_table(UserRole::Table)
+                    .columns([UserRole::UserId, UserRole::RoleId])
+                    .select_from(
+                        Query::select()
+                            .column(User::Id)
+                            .expr(Expr::val(user_role_id))
+                            .from(User::Table)
+                            .to_owned(),
+                    )
+                    .map_err(|e| DbErr::Migration(e.to_string()))?
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(UserRole::Table).to_owned())
+            .await?;
+        manager
+            .drop_table(Table::drop().table(RolePermission::Table).to_owned())
+            .await?;
+        manager
+            .drop_table(Table::drop().table(Permission::Table).to_owned())
+            .await?;
+        manager
+            .drop_table(Table::drop().table(Role::Table).to_owned())
+            .await
+    }
+}
+
+async fn insert_role(manager: &SchemaManager<'_>, name: &str) -> Result<Uuid, DbErr> {
+    let id = Uuid::new_v4();
+    manager
+        .exec_stmt(
+            Query::insert()
+                .into_table(Role::Table)
+                .columns([Role::Id, Role::Name])
+                .values_panic([id.into(), name.into()])
+                .to_owned(),
+        )
+        .await?;
+    Ok(id)
+}
+
+pub(crate) async fn insert_permission(
+    manager: &SchemaManager<'_>,
+    name: &str,
+) -> Result<Uuid, DbErr> {
+    let id = Uuid::new_v4();
+    manager
+        .exec_stmt(
+            Query::insert()
+                .into_table(Permission::Table)
+                .columns([Permission::Id, Permission::Name])
+                .values_panic([id.into(), name.into()])
+                .to_owned(),
+        )
+        .await?;
+    Ok(id)
+}
+
+pub(crate) async fn grant(
+    manager: &SchemaManager<'_>,
+    role_id: Uuid,
+    permission_id: Uuid,
+) -> Result<(), DbErr> {
+    manager
+        .exec_stmt(
+            Query::insert()
+                .into_table(RolePermission::Table)
+                .columns([RolePermission::RoleId, RolePermission::PermissionId])
+                .values_panic([role_id.into(), permission_id.into()])
+                .to_owned(),
+        )
+        .await
+}
+
+#[derive(DeriveIden)]
+pub enum Role {
+    Table,
+    Id,
+    Name,
+}
+
+#[derive(DeriveIden)]
+pub enum Permission {
+    Table,
+    Id,
+    Name,
+}
+
+#[derive(DeriveIden)]
+pub enum RolePermission {
+    Table,
+    RoleId,
+    PermissionId,
+}
+
+#[derive(DeriveIden)]
+pub enum UserRole {
+    Table,
+    UserId,
+    RoleId,
+}
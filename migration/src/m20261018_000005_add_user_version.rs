@@ -0,0 +1,42 @@
+use sea_orm_migration::prelude::*;
+
+use crate::m20231029_000001_create_user_table::User;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(User::Table)
+                    .add_column(
+                        ColumnDef::new(UserVersion::Version)
+                            .integer()
+                            .not_null()
+                            .default(1),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(User::Table)
+                    .drop_column(UserVersion::Version)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+/// Incremented on every change of a user, used by optimistic concurrency control
+#[derive(DeriveIden)]
+enum UserVersion {
+    Version,
+}
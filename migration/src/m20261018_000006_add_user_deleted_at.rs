@@ -0,0 +1,85 @@
+use sea_orm_migration::prelude::*;
+
+use crate::m20231029_000001_create_user_table::User;
+use crate::m20261018_000004_create_role_tables::{
+    insert_permission, Permission, Role, RolePermission, ADMIN_ROLE,
+};
+
+/// Permissions on deleted users, granted to the `admin` role.
+const DELETED_USER_PERMISSIONS: [&str; 2] = ["user:restore", "user:purge"];
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(User::Table)
+                    .add_column(ColumnDef::new(UserDeletedAt::DeletedAt).timestamp_with_time_zone())
+                    .to_owned(),
+            )
+            .await?;
+        for permission in DELETED_USER_PERMISSIONS {
+            let permission_id = insert_permission(manager, permission).await?;
+            manager
+                .exec_stmt(
+                    Query::insert()
+                        .into_table(RolePermission::Table)
+                        .columns([RolePermission::RoleId, RolePermission::PermissionId])
+                        .select_from(
+                            Query::select()
+                                .column(Role::Id)
+                                .expr(Expr::val(permission_id))
+                                .from(Role::Table)
+                                .and_where(Expr::col(Role::Name).eq(ADMIN_ROLE))
+                                .to_owned(),
+                        )
+                        .map_err(|e| DbErr::Migration(e.to_string()))?
+                        .to_owned(),
+                )
+                .await?;
+        }
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let permission_ids = Query::select()
+            .column(Permission::Id)
+            .from(Permission::Table)
+            .and_where(Expr::col(Permission::Name).is_in(DELETED_USER_PERMISSIONS))
+            .to_owned();
+        manager
+            .exec_stmt(
+                Query::delete()
+                    .from_table(RolePermission::Table)
+                    .and_where(Expr::col(RolePermission::PermissionId).in_subquery(permission_ids))
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .exec_stmt(
+                Query::delete()
+                    .from_table(Permission::Table)
+                    .and_where(Expr::col(Permission::Name).is_in(DELETED_USER_PERMISSIONS))
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(User::Table)
+                    .drop_column(UserDeletedAt::DeletedAt)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+/// Date a user was deleted, a deleted user can be restored until it is purged
+#[derive(DeriveIden)]
+enum UserDeletedAt {
+    DeletedAt,
+}
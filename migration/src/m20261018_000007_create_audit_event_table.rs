@@ -0,0 +1,114 @@
+use sea_orm_migration::prelude::*;
+
+use crate::m20261018_000004_create_role_tables::{
+    insert_permission, Permission, Role, RolePermission, ADMIN_ROLE,
+};
+
+const AUDIT_PERMISSION: &str = "audit:read";
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Events outlive the records they describe, there is no foreign key to them.
+        manager
+            .create_table(
+                Table::create()
+                    .table(AuditEvent::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(AuditEvent::Id)
+                            .uuid()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(AuditEvent::Actor).string().not_null())
+                    .col(ColumnDef::new(AuditEvent::Action).string().not_null())
+                    .col(ColumnDef::new(AuditEvent::Resource).string().not_null())
+                    .col(ColumnDef::new(AuditEvent::EntityId).string().not_null())
+                    .col(ColumnDef::new(AuditEvent::Changes).json().not_null())
+                    .col(ColumnDef::new(AuditEvent::RequestId).string())
+                    .col(
+                        ColumnDef::new(AuditEvent::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_audit_event_resource_entity_id")
+                    .table(AuditEvent::Table)
+                    .col(AuditEvent::Resource)
+                    .col(AuditEvent::EntityId)
+                    .to_owned(),
+            )
+            .await?;
+
+        let permission_id = insert_permission(manager, AUDIT_PERMISSION).await?;
+        manager
+            .exec_stmt(
+                Query::insert()
+                    .into_table(RolePermission::Table)
+                    .columns([RolePermission::RoleId, RolePermission::PermissionId])
+                    .select_from(
+                        Query::select()
+                            .column(Role::Id)
+                            .expr(Expr::val(permission_id))
+                            .from(Role::Table)
+                            .and_where(Expr::col(Role::Name).eq(ADMIN_ROLE))
+                            .to_owned(),
+                    )
+                    .map_err(|e| DbErr::Migration(e.to_string()))?
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .exec_stmt(
+                Query::delete()
+                    .from_table(RolePermission::Table)
+                    .and_where(
+                        Expr::col(RolePermission::PermissionId).in_subquery(
+                            Query::select()
+                                .column(Permission::Id)
+                                .from(Permission::Table)
+                                .and_where(Expr::col(Permission::Name).eq(AUDIT_PERMISSION))
+                                .to_owned(),
+                        ),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .exec_stmt(
+                Query::delete()
+                    .from_table(Permission::Table)
+                    .and_where(Expr::col(Permission::Name).eq(AUDIT_PERMISSION))
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .drop_table(Table::drop().table(AuditEvent::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum AuditEvent {
+    Table,
+    Id,
+    Actor,
+    Action,
+    Resource,
+    EntityId,
+    Changes,
+    RequestId,
+    CreatedAt,
+}
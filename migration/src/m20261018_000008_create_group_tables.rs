@@ -0,0 +1,169 @@
+use sea_orm_migration::prelude::*;
+
+use crate::m20231029_000001_create_user_table::User;
+use crate::m20261018_000004_create_role_tables::{
+    insert_permission, Permission, Role, RolePermission, ADMIN_ROLE, USER_ROLE,
+};
+
+/// Permissions on groups granted to the `admin` role, `group:update` also manages the members.
+const ADMIN_PERMISSIONS: [&str; 6] = [
+    "group:create",
+    "group:read",
+    "group:update",
+    "group:delete",
+    "group:restore",
+    "group:purge",
+];
+
+/// Permissions on groups granted to the `user` role.
+const USER_PERMISSIONS: [&str; 1] = ["group:read"];
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(Group::Table)
+                    .if_not_exists()
+                    .col(ColumnDef::new(Group::Id).uuid().not_null().primary_key())
+                    .col(ColumnDef::new(Group::Name).string().not_null().unique_key())
+                    .col(ColumnDef::new(Group::Description).string())
+                    .col(
+                        ColumnDef::new(Group::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(Group::UpdatedAt).timestamp_with_time_zone())
+                    .col(
+                        ColumnDef::new(Group::Version)
+                            .integer()
+                            .not_null()
+                            .default(1),
+                    )
+                    .col(ColumnDef::new(Group::DeletedAt).timestamp_with_time_zone())
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .create_table(
+                Table::create()
+                    .table(UserGroup::Table)
+                    .if_not_exists()
+                    .col(ColumnDef::new(UserGroup::UserId).uuid().not_null())
+                    .col(ColumnDef::new(UserGroup::GroupId).uuid().not_null())
+                    .primary_key(
+                        Index::create()
+                            .col(UserGroup::UserId)
+                            .col(UserGroup::GroupId),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_user_group_user_id")
+                            .from(UserGroup::Table, UserGroup::UserId)
+                            .to(User::Table, User::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_user_group_group_id")
+                            .from(UserGroup::Table, UserGroup::GroupId)
+                            .to(Group::Table, Group::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+        // Members are listed by group, the primary key only serves the lookups by user.
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_user_group_group_id")
+                    .table(UserGroup::Table)
+                    .col(UserGroup::GroupId)
+                    .to_owned(),
+            )
+            .await?;
+
+        for permission in ADMIN_PERMISSIONS {
+            let permission_id = insert_permission(manager, permission).await?;
+            let mut roles = vec![ADMIN_ROLE];
+            if USER_PERMISSIONS.contains(&permission) {
+                roles.push(USER_ROLE);
+            }
+            manager
+                .exec_stmt(
+                    Query::insert()
+                        .into_table(RolePermission::Table)
+                        .columns([RolePermission::RoleId, RolePermission::PermissionId])
+                        .select_from(
+                            Query::select()
+                                .column(Role::Id)
+                                .expr(Expr::val(permission_id))
+                                .from(Role::Table)
+                                .and_where(Expr::col(Role::Name).is_in(roles))
+                                .to_owned(),
+                        )
+                        .map_err(|e| DbErr::Migration(e.to_string()))?
+                        .to_owned(),
+                )
+                .await?;
+        }
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .exec_stmt(
+                Query::delete()
+                    .from_table(RolePermission::Table)
+                    .and_where(
+                        Expr::col(RolePermission::PermissionId).in_subquery(
+                            Query::select()
+                                .column(Permission::Id)
+                                .from(Permission::Table)
+                                .and_where(Expr::col(Permission::Name).is_in(ADMIN_PERMISSIONS))
+                                .to_owned(),
+                        ),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .exec_stmt(
+                Query::delete()
+                    .from_table(Permission::Table)
+                    .and_where(Expr::col(Permission::Name).is_in(ADMIN_PERMISSIONS))
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .drop_table(Table::drop().table(UserGroup::Table).to_owned())
+            .await?;
+        manager
+            .drop_table(Table::drop().table(Group::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Group {
+    Table,
+    Id,
+    Name,
+    Description,
+    CreatedAt,
+    UpdatedAt,
+    Version,
+    DeletedAt,
+}
+
+#[derive(DeriveIden)]
+enum UserGroup {
+    Table,
+    UserId,
+    GroupId,
+}
@@ -0,0 +1,561 @@
+use entity::organization::DEFAULT_ORGANIZATION_ID;
+use sea_orm_migration::prelude::*;
+use sea_orm_migration::sea_orm::{ConnectionTrait, DbBackend};
+
+/// Tables referencing `user`, their rows are deleted in cascade when it is dropped
+const USER_CHILDREN: [&str; 4] = ["credential", "refresh_token", "user_role", "user_group"];
+
+/// Tables referencing `group`
+const GROUP_CHILDREN: [&str; 1] = ["user_group"];
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(Organization::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(Organization::Id)
+                            .uuid()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(Organization::Name).string().not_null())
+                    .col(
+                        ColumnDef::new(Organization::Slug)
+                            .string()
+                            .not_null()
+                            .unique_key(),
+                    )
+                    .col(
+                        ColumnDef::new(Organization::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(Organization::UpdatedAt).timestamp_with_time_zone())
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .exec_stmt(
+                Query::insert()
+                    .into_table(Organization::Table)
+                    .columns([
+                        Organization::Id,
+                        Organization::Name,
+                        Organization::Slug,
+                        Organization::CreatedAt,
+                    ])
+                    .values_panic([
+                        DEFAULT_ORGANIZATION_ID.into(),
+                        "Default".into(),
+                        "default".into(),
+                        chrono::Utc::now().into(),
+                    ])
+                    .to_owned(),
+            )
+            .await?;
+
+        // Every existing record belongs to the default organization.
+        if manager.get_database_backend() == DbBackend::Sqlite {
+            rebuild(
+                manager,
+                "user",
+                user_table(true),
+                &USER_COLUMNS,
+                &USER_CHILDREN,
+            )
+            .await?;
+            rebuild(
+                manager,
+                "group",
+                group_table(true),
+                &GROUP_COLUMNS,
+                &GROUP_CHILDREN,
+            )
+            .await?;
+            rebuild(
+                manager,
+                "audit_event",
+                audit_event_table(true),
+                &AUDIT_EVENT_COLUMNS,
+                &[],
+            )
+            .await?;
+            create_audit_event_index(manager).await?;
+        } else {
+            add_tenant(manager, "user", Some("fk_user_tenant_id")).await?;
+            drop_unique(manager, "user", "email").await?;
+            add_tenant(manager, "group", Some("fk_group_tenant_id")).await?;
+            drop_unique(manager, "group", "name").await?;
+            add_tenant(manager, "audit_event", None).await?;
+        }
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_user_tenant_id_email")
+                    .table(User::Table)
+                    .col(User::TenantId)
+                    .col(User::Email)
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_group_tenant_id_name")
+                    .table(Group::Table)
+                    .col(Group::TenantId)
+                    .col(Group::Name)
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_audit_event_tenant_id")
+                    .table(AuditEvent::Table)
+                    .col(AuditEvent::TenantId)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Only the default organization is kept, emails and names of the others could collide with its own.
+        for table in ["user", "group", "audit_event"] {
+            manager
+                .exec_stmt(
+                    Query::delete()
+                        .from_table(Alias::new(table))
+                        .and_where(Expr::col(User::TenantId).ne(DEFAULT_ORGANIZATION_ID))
+                        .to_owned(),
+                )
+                .await?;
+        }
+        if manager.get_database_backend() == DbBackend::Sqlite {
+            rebuild(
+                manager,
+                "user",
+                user_table(false),
+                &USER_COLUMNS,
+                &USER_CHILDREN,
+            )
+            .await?;
+            rebuild(
+                manager,
+                "group",
+                group_table(false),
+                &GROUP_COLUMNS,
+                &GROUP_CHILDREN,
+            )
+            .await?;
+            rebuild(
+                manager,
+                "audit_event",
+                audit_event_table(false),
+                &AUDIT_EVENT_COLUMNS,
+                &[],
+            )
+            .await?;
+            create_audit_event_index(manager).await?;
+        } else {
+            for (table, index) in [
+                ("user", "idx_user_tenant_id_email"),
+                ("group", "idx_group_tenant_id_name"),
+                ("audit_event", "idx_audit_event_tenant_id"),
+            ] {
+                manager
+                    .drop_index(
+                        Index::drop()
+                            .name(index)
+                            .table(Alias::new(table))
+                            .to_owned(),
+                    )
+                    .await?;
+            }
+            remove_tenant(manager, "user", Some("fk_user_tenant_id")).await?;
+            add_unique(manager, "user", "email").await?;
+            remove_tenant(manager, "group", Some("fk_group_tenant_id")).await?;
+            add_unique(manager, "group", "name").await?;
+            remove_tenant(manager, "audit_event", None).await?;
+        }
+        manager
+            .drop_table(Table::drop().table(Organization::Table).to_owned())
+            .await
+    }
+}
+
+/// Add the `tenant_id` of `table`, set to the default organization on the existing rows.
+async fn add_tenant(
+    manager: &SchemaManager<'_>,
+    table: &str,
+    foreign_key: Option<&str>,
+) -> Result<(), DbErr> {
+    manager
+        .alter_table(
+            Table::alter()
+                .table(Alias::new(table))
+                .add_column(ColumnDef::new(User::TenantId).uuid())
+                .to_owned(),
+        )
+        .await?;
+    manager
+        .exec_stmt(
+            Query::update()
+                .table(Alias::new(table))
+                .value(User::TenantId, DEFAULT_ORGANIZATION_ID)
+                .to_owned(),
+        )
+        .await?;
+    manager
+        .alter_table(
+            Table::alter()
+                .table(Alias::new(table))
+                .modify_column(ColumnDef::new(User::TenantId).uuid().not_null())
+                .to_owned(),
+        )
+        .await?;
+    if let Some(foreign_key) = foreign_key {
+        manager
+            .create_foreign_key(
+                ForeignKey::create()
+                    .name(foreign_key)
+                    .from(Alias::new(table), User::TenantId)
+                    .to(Organization::Table, Organization::Id)
+                    .on_delete(ForeignKeyAction::Cascade)
+                    .to_owned(),
+            )
+            .await?;
+    }
+    Ok(())
+}
+
+async fn remove_tenant(
+    manager: &SchemaManager<'_>,
+    table: &str,
+    foreign_key: Option<&str>,
+) -> Result<(), DbErr> {
+    if let Some(foreign_key) = foreign_key {
+        manager
+            .drop_foreign_key(
+                ForeignKey::drop()
+                    .name(foreign_key)
+                    .table(Alias::new(table))
+                    .to_owned(),
+            )
+            .await?;
+    }
+    manager
+        .alter_table(
+            Table::alter()
+                .table(Alias::new(table))
+                .drop_column(User::TenantId)
+                .to_owned(),
+        )
+        .await
+}
+
+/// Drop the constraint `unique_key()` gave to `column`, named after the column by MySQL
+/// and after the table and the column by PostgreSQL.
+async fn drop_unique(manager: &SchemaManager<'_>, table: &str, column: &str) -> Result<(), DbErr> {
+    let sql = match manager.get_database_backend() {
+        DbBackend::MySql => format!("ALTER TABLE `{}` DROP INDEX `{}`", table, column),
+        _ => format!(
+            "ALTER TABLE \"{}\" DROP CONSTRAINT \"{}_{}_key\"",
+            table, table, column
+        ),
+    };
+    manager.get_connection().execute_unprepared(&sql).await?;
+    Ok(())
+}
+
+/// Give back to `column` the constraint `drop_unique` removed.
+async fn add_unique(manager: &SchemaManager<'_>, table: &str, column: &str) -> Result<(), DbErr> {
+    let sql = match manager.get_database_backend() {
+        DbBackend::MySql => format!(
+            "ALTER TABLE `{}` ADD UNIQUE INDEX `{}` (`{}`)",
+            table, column, column
+        ),
+        _ => format!(
+            "ALTER TABLE \"{}\" ADD CONSTRAINT \"{}_{}_key\" UNIQUE (\"{}\")",
+            table, table, column, column
+        ),
+    };
+    manager.get_connection().execute_unprepared(&sql).await?;
+    Ok(())
+}
+
+/// Replace the SQLite `table` by the `{table}_rebuilt` table `create` defines, filled with its `columns`
+/// and, when `create` adds it, a `tenant_id` set to the default organization.
+/// SQLite can neither drop a unique constraint nor a referenced column, so the table is rebuilt.
+/// Foreign keys can not be disabled inside the transaction of the migrations: the rows of the
+/// `children` tables, deleted in cascade with the old table, are kept aside and put back afterwards.
+async fn rebuild(
+    manager: &SchemaManager<'_>,
+    table: &str,
+    create: TableCreateStatement,
+    columns: &[&str],
+    children: &[&str],
+) -> Result<(), DbErr> {
+    let connection = manager.get_connection();
+    let rebuilt = Alias::new(format!("{}_rebuilt", table));
+    let tenant_id = create
+        .get_columns()
+        .iter()
+        .any(|column| column.get_column_name() == "tenant_id");
+    for child in children {
+        connection
+            .execute_unprepared(&format!(
+                "CREATE TEMP TABLE \"{}_kept\" AS SELECT * FROM \"{}\"",
+                child, child
+            ))
+            .await?;
+    }
+    manager.create_table(create).await?;
+    let mut rows = Query::select();
+    rows.columns(columns.iter().map(|column| Alias::new(*column)))
+        .from(Alias::new(table));
+    let mut targets: Vec<Alias> = columns.iter().map(|column| Alias::new(*column)).collect();
+    if tenant_id {
+        rows.expr(Expr::val(DEFAULT_ORGANIZATION_ID));
+        targets.push(Alias::new("tenant_id"));
+    }
+    manager
+        .exec_stmt(
+            Query::insert()
+                .into_table(rebuilt.clone())
+                .columns(targets)
+                .select_from(rows)
+                .map_err(|e| DbErr::Migration(e.to_string()))?
+                .to_owned(),
+        )
+        .await?;
+    manager
+        .drop_table(Table::drop().table(Alias::new(table)).to_owned())
+        .await?;
+    manager
+        .rename_table(Table::rename().table(rebuilt, Alias::new(table)).to_owned())
+        .await?;
+    for child in children {
+        connection
+            .execute_unprepared(&format!(
+                "INSERT INTO \"{}\" SELECT * FROM \"{}_kept\"",
+                child, child
+            ))
+            .await?;
+        connection
+            .execute_unprepared(&format!("DROP TABLE \"{}_kept\"", child))
+            .await?;
+    }
+    Ok(())
+}
+
+/// Tenant of the rebuilt tables, a reference to the organization
+fn tenant(create: &mut TableCreateStatement, table: &str) {
+    create
+        .col(ColumnDef::new(User::TenantId).uuid().not_null())
+        .foreign_key(
+            ForeignKey::create()
+                .name(format!("fk_{}_tenant_id", table))
+                .from(Alias::new(format!("{}_rebuilt", table)), User::TenantId)
+                .to(Organization::Table, Organization::Id)
+                .on_delete(ForeignKeyAction::Cascade),
+        );
+}
+
+const USER_COLUMNS: [&str; 8] = [
+    "id",
+    "first_name",
+    "last_name",
+    "email",
+    "created_at",
+    "updated_at",
+    "version",
+    "deleted_at",
+];
+
+/// `user` as defined by the previous migrations, the email is only unique per tenant once `tenant` is added
+fn user_table(tenant_id: bool) -> TableCreateStatement {
+    let mut email = ColumnDef::new(User::Email);
+    email.string().not_null();
+    if !tenant_id {
+        email.unique_key();
+    }
+    let mut create = Table::create()
+        .table(Alias::new("user_rebuilt"))
+        .col(ColumnDef::new(User::Id).uuid().not_null().primary_key())
+        .col(ColumnDef::new(User::FirstName).string().not_null())
+        .col(ColumnDef::new(User::LastName).string().not_null())
+        .col(&mut email)
+        .col(
+            ColumnDef::new(User::CreatedAt)
+                .timestamp_with_time_zone()
+                .not_null(),
+        )
+        .col(ColumnDef::new(User::UpdatedAt).timestamp_with_time_zone())
+        .col(
+            ColumnDef::new(User::Version)
+                .integer()
+                .not_null()
+                .default(1),
+        )
+        .col(ColumnDef::new(User::DeletedAt).timestamp_with_time_zone())
+        .to_owned();
+    if tenant_id {
+        tenant(&mut create, "user");
+    }
+    create
+}
+
+const GROUP_COLUMNS: [&str; 7] = [
+    "id",
+    "name",
+    "description",
+    "created_at",
+    "updated_at",
+    "version",
+    "deleted_at",
+];
+
+fn group_table(tenant_id: bool) -> TableCreateStatement {
+    let mut name = ColumnDef::new(Group::Name);
+    name.string().not_null();
+    if !tenant_id {
+        name.unique_key();
+    }
+    let mut create = Table::create()
+        .table(Alias::new("group_rebuilt"))
+        .col(ColumnDef::new(Group::Id).uuid().not_null().primary_key())
+        .col(&mut name)
+        .col(ColumnDef::new(Group::Description).string())
+        .col(
+            ColumnDef::new(Group::CreatedAt)
+                .timestamp_with_time_zone()
+                .not_null(),
+        )
+        .col(ColumnDef::new(Group::UpdatedAt).timestamp_with_time_zone())
+        .col(
+            ColumnDef::new(Group::Version)
+                .integer()
+                .not_null()
+                .default(1),
+        )
+        .col(ColumnDef::new(Group::DeletedAt).timestamp_with_time_zone())
+        .to_owned();
+    if tenant_id {
+        tenant(&mut create, "group");
+    }
+    create
+}
+
+const AUDIT_EVENT_COLUMNS: [&str; 8] = [
+    "id",
+    "actor",
+    "action",
+    "resource",
+    "entity_id",
+    "changes",
+    "request_id",
+    "created_at",
+];
+
+/// Events outlive the records and the organizations they describe, `tenant_id` is not a foreign key.
+fn audit_event_table(tenant_id: bool) -> TableCreateStatement {
+    let mut create = Table::create()
+        .table(Alias::new("audit_event_rebuilt"))
+        .col(
+            ColumnDef::new(AuditEvent::Id)
+                .uuid()
+                .not_null()
+                .primary_key(),
+        )
+        .col(ColumnDef::new(AuditEvent::Actor).string().not_null())
+        .col(ColumnDef::new(AuditEvent::Action).string().not_null())
+        .col(ColumnDef::new(AuditEvent::Resource).string().not_null())
+        .col(ColumnDef::new(AuditEvent::EntityId).string().not_null())
+        .col(ColumnDef::new(AuditEvent::Changes).json().not_null())
+        .col(ColumnDef::new(AuditEvent::RequestId).string())
+        .col(
+            ColumnDef::new(AuditEvent::CreatedAt)
+                .timestamp_with_time_zone()
+                .not_null(),
+        )
+        .to_owned();
+    if tenant_id {
+        create.col(ColumnDef::new(AuditEvent::TenantId).uuid().not_null());
+    }
+    create
+}
+
+/// Index created with `audit_event` and dropped by its rebuild
+async fn create_audit_event_index(manager: &SchemaManager<'_>) -> Result<(), DbErr> {
+    manager
+        .create_index(
+            Index::create()
+                .name("idx_audit_event_resource_entity_id")
+                .table(AuditEvent::Table)
+                .col(AuditEvent::Resource)
+                .col(AuditEvent::EntityId)
+                .to_owned(),
+        )
+        .await
+}
+
+#[derive(DeriveIden)]
+pub enum Organization {
+    Table,
+    Id,
+    Name,
+    Slug,
+    CreatedAt,
+    UpdatedAt,
+}
+
+#[derive(DeriveIden)]
+enum User {
+    Table,
+    Id,
+    FirstName,
+    LastName,
+    Email,
+    CreatedAt,
+    UpdatedAt,
+    Version,
+    DeletedAt,
+    TenantId,
+}
+
+#[derive(DeriveIden)]
+enum Group {
+    Table,
+    Id,
+    Name,
+    Description,
+    CreatedAt,
+    UpdatedAt,
+    Version,
+    DeletedAt,
+    TenantId,
+}
+
+#[derive(DeriveIden)]
+enum AuditEvent {
+    Table,
+    Id,
+    Actor,
+    Action,
+    Resource,
+    EntityId,
+    Changes,
+    RequestId,
+    CreatedAt,
+    TenantId,
+}
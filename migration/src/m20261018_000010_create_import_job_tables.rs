@@ -0,0 +1,157 @@
+use sea_orm_migration::prelude::*;
+
+use crate::m20261018_000009_create_organization_table::Organization;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(ImportJob::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(ImportJob::Id)
+                            .uuid()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(ImportJob::TenantId).uuid().not_null())
+                    .col(ColumnDef::new(ImportJob::Actor).string().not_null())
+                    .col(ColumnDef::new(ImportJob::Status).string().not_null())
+                    .col(ColumnDef::new(ImportJob::DryRun).boolean().not_null())
+                    .col(ColumnDef::new(ImportJob::Upsert).boolean().not_null())
+                    .col(ColumnDef::new(ImportJob::TotalRows).integer().not_null())
+                    .col(
+                        ColumnDef::new(ImportJob::ProcessedRows)
+                            .integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .col(
+                        ColumnDef::new(ImportJob::Created)
+                            .integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .col(
+                        ColumnDef::new(ImportJob::Updated)
+                            .integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .col(
+                        ColumnDef::new(ImportJob::Failed)
+                            .integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .col(
+                        ColumnDef::new(ImportJob::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(ImportJob::UpdatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(ImportJob::FinishedAt).timestamp_with_time_zone())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_import_job_tenant_id")
+                            .from(ImportJob::Table, ImportJob::TenantId)
+                            .to(Organization::Table, Organization::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .create_table(
+                Table::create()
+                    .table(ImportJobError::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(ImportJobError::Id)
+                            .uuid()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(ImportJobError::JobId).uuid().not_null())
+                    .col(
+                        ColumnDef::new(ImportJobError::Line)
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(ImportJobError::Status).integer().not_null())
+                    .col(ColumnDef::new(ImportJobError::Code).string().not_null())
+                    .col(ColumnDef::new(ImportJobError::Field).string())
+                    .col(ColumnDef::new(ImportJobError::Message).string().not_null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_import_job_error_job_id")
+                            .from(ImportJobError::Table, ImportJobError::JobId)
+                            .to(ImportJob::Table, ImportJob::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+        // The report lists the errors of a single job.
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_import_job_error_job_id")
+                    .table(ImportJobError::Table)
+                    .col(ImportJobError::JobId)
+                    .col(ImportJobError::Line)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(ImportJobError::Table).to_owned())
+            .await?;
+        manager
+            .drop_table(Table::drop().table(ImportJob::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum ImportJob {
+    Table,
+    Id,
+    TenantId,
+    Actor,
+    Status,
+    DryRun,
+    Upsert,
+    TotalRows,
+    ProcessedRows,
+    Created,
+    Updated,
+    Failed,
+    CreatedAt,
+    /// Touched by every processed row, a running job left untouched was interrupted
+    UpdatedAt,
+    FinishedAt,
+}
+
+#[derive(DeriveIden)]
+enum ImportJobError {
+    Table,
+    Id,
+    JobId,
+    Line,
+    Status,
+    Code,
+    Field,
+    Message,
+}
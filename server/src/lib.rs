@@ -1,8 +1,8 @@
 use actix_web::dev::Server;
 use actix_web::middleware::Logger;
 use actix_web::web::Data;
-use actix_web::{web, App, HttpServer};
-use api::{init, AppState};
+use actix_web::{App, HttpServer};
+use api::{init, init_administrator, AppState};
 use migration::{Migrator, MigratorTrait};
 use sea_orm::TransactionTrait;
 use sea_orm::{ConnectOptions, Database, DatabaseConnection};
@@ -29,7 +29,15 @@ pub async fn start() -> std::io::Result<Server> {
         .await
         .expect("Error occurs when trying to connect to database");
 
-    let state = AppState { db_connection };
+    let address = format!(
+        "{}:{}",
+        settings.application.host.clone(),
+        settings.application.port.clone()
+    );
+    let state = AppState {
+        db_connection,
+        settings,
+    };
     let state = Arc::new(state);
     let state_1 = Arc::clone(&state);
 
@@ -41,6 +49,10 @@ pub async fn start() -> std::io::Result<Server> {
     .await
     .expect("Cannot apply migration !");
 
+    init_administrator(&state)
+        .await
+        .expect("Cannot create administrators !");
+
     let server = HttpServer::new(move || {
         let state = Arc::clone(&state);
         App::new()
@@ -48,11 +60,7 @@ pub async fn start() -> std::io::Result<Server> {
             .wrap(Logger::default())
             .configure(init)
     })
-    .bind(format!(
-        "{}:{}",
-        settings.application.host.clone(),
-        settings.application.port.clone()
-    ))?
+    .bind(address)?
     .run();
     Ok(server)
 }
@@ -74,7 +82,7 @@ mod tests {
             if counter == 5 {
                 panic!("Connection to server failed");
             }
-            let _ = tokio::time::sleep(tokio::time::Duration::from_secs(5));
+            tokio::time::sleep(tokio::time::Duration::from_secs(5)).await;
             counter += 1;
         }
     }
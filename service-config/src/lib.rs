@@ -14,6 +14,12 @@ pub const APP_NAME: &str = env!("APP_NAME");
 pub struct Settings {
     pub application: ApplicationSettings,
     pub database: DatabaseSettings,
+    pub authentication: AuthenticationSettings,
+    pub pagination: PaginationSettings,
+    #[serde(default)]
+    pub administrator: Option<AdministratorSettings>,
+    #[serde(default)]
+    pub organizations: Vec<OrganizationSettings>,
 }
 
 #[derive(Deserialize, Serialize)]
@@ -22,6 +28,20 @@ pub struct ApplicationSettings {
     #[serde(deserialize_with = "deserialize_number_from_string")]
     pub port: u16,
     pub loglevel: Level,
+    /// Seconds a client may reuse a read response before revalidating it, 0 to always revalidate
+    pub cache_max_age: u32,
+    /// Largest number of operations accepted by one bulk request
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub bulk_max_operations: usize,
+    /// Largest file accepted by an import, in bytes
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub import_max_size: usize,
+    /// Imports of more rows run as a background job
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub import_max_sync_rows: usize,
+    /// Domain whose subdomains name the organizations, e.g. `acme.example.com` for `example.com`
+    #[serde(default)]
+    pub tenant_domain: Option<String>,
 }
 
 #[derive(Deserialize, Serialize)]
@@ -40,6 +60,51 @@ pub struct DatabaseSettings {
     pub path: Option<String>,
 }
 
+/// Keys used to sign and verify the JWT access tokens.
+/// `secret` is used by `HS256` while `private_key` and `public_key` (PEM encoded) are used by `RS256`.
+#[derive(Deserialize, Serialize)]
+pub struct AuthenticationSettings {
+    pub algorithm: String,
+    pub secret: Option<String>,
+    pub private_key: Option<String>,
+    pub public_key: Option<String>,
+    /// Lifetime of an access token in seconds
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub access_token_lifetime: u64,
+    /// Lifetime of a refresh token in seconds
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub refresh_token_lifetime: u64,
+}
+
+/// Number of items in a page of a list
+#[derive(Deserialize, Serialize)]
+pub struct PaginationSettings {
+    /// Used when the page size is not given
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub default_page_size: usize,
+    /// Larger page sizes are rejected
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub max_page_size: usize,
+}
+
+/// Account created at startup when no user owns `email` yet.
+#[derive(Deserialize, Serialize)]
+pub struct AdministratorSettings {
+    pub first_name: String,
+    pub last_name: String,
+    pub email: String,
+    pub password: String,
+}
+
+/// Organization created at startup when no organization is named `slug` yet,
+/// `administrator` being its first account.
+#[derive(Deserialize, Serialize)]
+pub struct OrganizationSettings {
+    pub name: String,
+    pub slug: String,
+    pub administrator: AdministratorSettings,
+}
+
 #[derive(Debug, Copy, Clone)]
 pub enum Environment {
     Local,
@@ -53,6 +118,11 @@ impl Default for ApplicationSettings {
             host: "127.0.0.1".to_string(),
             port: 8000,
             loglevel: Level::Info,
+            cache_max_age: 0,
+            bulk_max_operations: 1000,
+            import_max_size: 10485760,
+            import_max_sync_rows: 100,
+            tenant_domain: None,
         }
     }
 }
@@ -72,6 +142,28 @@ impl Default for DatabaseSettings {
     }
 }
 
+impl Default for AuthenticationSettings {
+    fn default() -> Self {
+        Self {
+            algorithm: "HS256".to_string(),
+            secret: None,
+            private_key: None,
+            public_key: None,
+            access_token_lifetime: 900,
+            refresh_token_lifetime: 2_592_000,
+        }
+    }
+}
+
+impl Default for PaginationSettings {
+    fn default() -> Self {
+        Self {
+            default_page_size: 25,
+            max_page_size: 100,
+        }
+    }
+}
+
 impl Environment {
     pub fn as_str(&self) -> &'static str {
         match self {
@@ -154,6 +246,20 @@ impl Settings {
                     serde_yaml::to_value(DatabaseSettings::default()).unwrap(),
                 )
                 .unwrap(),
+            )?
+            .set_default(
+                "authentication",
+                serde_yaml::from_value::<Map<String, Value>>(
+                    serde_yaml::to_value(AuthenticationSettings::default()).unwrap(),
+                )
+                .unwrap(),
+            )?
+            .set_default(
+                "pagination",
+                serde_yaml::from_value::<Map<String, Value>>(
+                    serde_yaml::to_value(PaginationSettings::default()).unwrap(),
+                )
+                .unwrap(),
             )?;
         if !environment_filename.is_empty() {
             let configuration_file: PathBuf =
@@ -207,6 +313,35 @@ impl Settings {
             std::fs::File::create(Path::new(&settings.database.path.clone().unwrap()))
                 .expect("Cannot create sqlite database file");
         }
+        match settings.authentication.algorithm.as_str() {
+            "HS256" if settings.authentication.secret.is_none() => {
+                return Err(config::ConfigError::Message(String::from(
+                    "authentication.secret is required by HS256",
+                )));
+            }
+            "RS256"
+                if settings.authentication.public_key.is_none()
+                    || settings.authentication.private_key.is_none() =>
+            {
+                return Err(config::ConfigError::Message(String::from(
+                    "authentication.private_key and authentication.public_key are required by RS256",
+                )));
+            }
+            "HS256" | "RS256" => {}
+            other => {
+                return Err(config::ConfigError::Message(format!(
+                    "{} is not a supported algorithm. Use either `HS256` or `RS256`.",
+                    other
+                )));
+            }
+        }
+        if settings.pagination.default_page_size == 0
+            || settings.pagination.default_page_size > settings.pagination.max_page_size
+        {
+            return Err(config::ConfigError::Message(String::from(
+                "pagination.default_page_size must be between 1 and pagination.max_page_size",
+            )));
+        }
         Ok(settings)
     }
 }
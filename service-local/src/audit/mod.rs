@@ -0,0 +1,392 @@
+use std::fmt::Display;
+
+use crate::error::AsCoreError;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use common::audit::{AuditEvent, AuditSearch, Auditable};
+use common::error::CoreError;
+use common::management::{Manageable, Precondition, SearchResult};
+use entity::audit_event::{ActiveModel, Column, Entity, Model};
+use sea_orm::ActiveValue::Set;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, ConnectionTrait, EntityTrait, Paginator, PaginatorTrait,
+    QueryFilter, QueryOrder, QuerySelect, SelectModel,
+};
+use serde::Serialize;
+use serde_json::{json, Map, Value};
+use uuid::Uuid;
+
+/// Audit events of a single organization.
+pub struct AuditManagement {
+    tenant: Uuid,
+}
+
+impl AuditManagement {
+    pub fn new(tenant: Uuid) -> Self {
+        Self { tenant }
+    }
+}
+
+pub struct AuditSearchResult {
+    num_pages: usize,
+    total_items: usize,
+    page: usize,
+    per_page: usize,
+    result: Vec<Model>,
+}
+
+/// Record in `audit_event` every change made by `actor` through `management`, in the organization `tenant`.
+/// Events are written with the backend of the change, give a transaction to keep both together.
+pub struct Audited<M> {
+    actor: Box<dyn Display + Sync + Send>,
+    tenant: Uuid,
+    request_id: Option<String>,
+    resource: &'static str,
+    management: M,
+}
+
+impl<M> Audited<M> {
+    pub fn new(
+        actor: Box<dyn Display + Sync + Send>,
+        tenant: Uuid,
+        request_id: Option<String>,
+        resource: &'static str,
+        management: M,
+    ) -> Self {
+        Self {
+            actor,
+            tenant,
+            request_id,
+            resource,
+            management,
+        }
+    }
+
+    async fn record<C: ConnectionTrait>(
+        &self,
+        action: &str,
+        before: Value,
+        after: Value,
+        backend: &C,
+    ) -> Result<(), Box<dyn common::error::Error>> {
+        let entity_id = match after.get("id").or_else(|| before.get("id")) {
+            Some(Value::String(id)) => id.to_owned(),
+            Some(id) => id.to_string(),
+            None => String::new(),
+        };
+        ActiveModel {
+            id: Set(Uuid::new_v4()),
+            tenant_id: Set(self.tenant),
+            actor: Set(self.actor.to_string()),
+            action: Set(String::from(action)),
+            resource: Set(String::from(self.resource)),
+            entity_id: Set(entity_id),
+            changes: Set(diff(&before, &after)),
+            request_id: Set(self.request_id.to_owned()),
+            ..Default::default()
+        }
+        .insert(backend)
+        .await
+        .map(|_| ())
+        .map_err(|e| Box::new(AsCoreError::from(e)) as Box<dyn common::error::Error>)
+    }
+}
+
+fn snapshot<T: Serialize>(record: &T) -> Result<Value, Box<dyn common::error::Error>> {
+    serde_json::to_value(record).map_err(|e| {
+        Box::new(AsCoreError::from(CoreError::UnkownError(e.to_string())))
+            as Box<dyn common::error::Error>
+    })
+}
+
+/// Fields whose value differs between `before` and `after`, each with both values.
+/// A field missing on one side is `null` there.
+fn diff(before: &Value, after: &Value) -> Value {
+    let empty = Map::new();
+    let before = before.as_object().unwrap_or(&empty);
+    let after = after.as_object().unwrap_or(&empty);
+    Value::Object(
+        before
+            .keys()
+            .chain(after.keys())
+            .filter(|field| before.get(*field) != after.get(*field))
+            .map(|field| {
+                (
+                    field.to_owned(),
+                    json!({
+                        "before": before.get(field).unwrap_or(&Value::Null),
+                        "after": after.get(field).unwrap_or(&Value::Null),
+                    }),
+                )
+            })
+            .collect(),
+    )
+}
+
+/// Date of the latest purge of a `resource` of the organization `tenant`, the purged rows
+/// being gone it is only kept by their events.
+pub(crate) async fn purged_at<C: ConnectionTrait>(
+    tenant: Uuid,
+    resource: &str,
+    backend: &C,
+) -> Result<Option<DateTime<Utc>>, Box<dyn common::error::Error>> {
+    Entity::find()
+        .select_only()
+        .column_as(Column::CreatedAt.max(), "created_at")
+        .filter(Column::TenantId.eq(tenant))
+        .filter(Column::Resource.eq(resource))
+        .filter(Column::Action.eq("purge"))
+        .into_tuple::<Option<DateTime<Utc>>>()
+        .one(backend)
+        .await
+        .map(Option::flatten)
+        .map_err(|e| Box::new(AsCoreError::from(e)) as Box<dyn common::error::Error>)
+}
+
+fn copy(id: &(dyn Display + Sync + Send)) -> Box<dyn Display + Sync + Send> {
+    Box::new(id.to_string())
+}
+
+#[async_trait]
+impl<'a, B, M, D, S, R, P> Manageable<'a, B> for Audited<M>
+where
+    B: ConnectionTrait,
+    M: Manageable<
+        'a,
+        B,
+        Id = Box<dyn Display + Sync + Send>,
+        Data = D,
+        Search = S,
+        Result = R,
+        Patch = P,
+    >,
+    D: Serialize + Send + 'static,
+    S: Send + 'static,
+    R: 'static,
+    P: Send + 'static,
+{
+    type Id = Box<dyn Display + Sync + Send>;
+    type Data = D;
+    type Search = S;
+    type Result = R;
+    type Patch = P;
+
+    async fn create(
+        &self,
+        data: Self::Data,
+        backend: &'a B,
+    ) -> Result<Self::Data, Box<dyn common::error::Error>> {
+        let created = self.management.create(data, backend).await?;
+        self.record("create", Value::Null, snapshot(&created)?, backend)
+            .await?;
+        Ok(created)
+    }
+
+    async fn read(
+        &self,
+        search_opt: Self::Search,
+        backend: &'a B,
+    ) -> Result<Self::Result, Box<dyn common::error::Error>> {
+        self.management.read(search_opt, backend).await
+    }
+
+    async fn find(
+        &self,
+        id: Self::Id,
+        include_deleted: bool,
+        backend: &'a B,
+    ) -> Result<Self::Data, Box<dyn common::error::Error>> {
+        self.management.find(id, include_deleted, backend).await
+    }
+
+    async fn update(
+        &self,
+        id: Self::Id,
+        data: Self::Data,
+        precondition: Precondition,
+        backend: &'a B,
+    ) -> Result<Self::Data, Box<dyn common::error::Error>> {
+        let before = snapshot(
+            &self
+                .management
+                .find(copy(id.as_ref()), true, backend)
+                .await?,
+        )?;
+        let updated = self
+            .management
+            .update(id, data, precondition, backend)
+            .await?;
+        self.record("update", before, snapshot(&updated)?, backend)
+            .await?;
+        Ok(updated)
+    }
+
+    async fn patch(
+        &self,
+        id: Self::Id,
+        patch: Self::Patch,
+        precondition: Precondition,
+        backend: &'a B,
+    ) -> Result<Self::Data, Box<dyn common::error::Error>> {
+        let before = snapshot(
+            &self
+                .management
+                .find(copy(id.as_ref()), true, backend)
+                .await?,
+        )?;
+        let patched = self
+            .management
+            .patch(id, patch, precondition, backend)
+            .await?;
+        self.record("patch", before, snapshot(&patched)?, backend)
+            .await?;
+        Ok(patched)
+    }
+
+    async fn delete(
+        &self,
+        id: Self::Id,
+        precondition: Precondition,
+        backend: &'a B,
+    ) -> Result<(), Box<dyn common::error::Error>> {
+        let before = snapshot(
+            &self
+                .management
+                .find(copy(id.as_ref()), true, backend)
+                .await?,
+        )?;
+        self.management
+            .delete(copy(id.as_ref()), precondition, backend)
+            .await?;
+        let after = snapshot(&self.management.find(id, true, backend).await?)?;
+        self.record("delete", before, after, backend).await
+    }
+
+    async fn restore(
+        &self,
+        id: Self::Id,
+        backend: &'a B,
+    ) -> Result<Self::Data, Box<dyn common::error::Error>> {
+        let before = snapshot(
+            &self
+                .management
+                .find(copy(id.as_ref()), true, backend)
+                .await?,
+        )?;
+        let restored = self.management.restore(id, backend).await?;
+        self.record("restore", before, snapshot(&restored)?, backend)
+            .await?;
+        Ok(restored)
+    }
+
+    async fn purge(
+        &self,
+        id: Self::Id,
+        backend: &'a B,
+    ) -> Result<(), Box<dyn common::error::Error>> {
+        let before = snapshot(
+            &self
+                .management
+                .find(copy(id.as_ref()), true, backend)
+                .await?,
+        )?;
+        self.management.purge(id, backend).await?;
+        self.record("purge", before, Value::Null, backend).await
+    }
+}
+
+#[async_trait]
+impl<'a, B: ConnectionTrait> Auditable<'a, B> for AuditManagement {
+    type Search = Box<dyn AuditSearch>;
+    type Result = Box<dyn SearchResult<Result = Box<dyn AuditEvent>>>;
+
+    async fn search(
+        &self,
+        search: Self::Search,
+        backend: &'a B,
+    ) -> Result<Self::Result, Box<dyn common::error::Error>> {
+        let mut select_events = Entity::find().filter(Column::TenantId.eq(self.tenant));
+        for (column, value) in [
+            (Column::Actor, search.get_actor()),
+            (Column::Action, search.get_action()),
+            (Column::Resource, search.get_resource()),
+            (Column::EntityId, search.get_entity_id()),
+        ] {
+            if let Some(value) = value {
+                select_events = select_events.filter(column.eq(value));
+            }
+        }
+        if let Some(date_range) = search
+            .get_date_range()
+            .map_err(|e| Box::new(AsCoreError::from(e)) as Box<dyn common::error::Error>)?
+        {
+            select_events = select_events
+                .filter(Column::CreatedAt.between(date_range.get_start(), date_range.get_end()));
+        }
+        let pagination = search
+            .get_pagination()
+            .map_err(|e| Box::new(AsCoreError::from(e)) as Box<dyn common::error::Error>)?;
+        let per_page = pagination.get_per_page();
+        let paginator: Paginator<_, SelectModel<Model>> = select_events
+            .order_by_desc(Column::CreatedAt)
+            .order_by_desc(Column::Id)
+            .paginate(backend, TryInto::<u64>::try_into(per_page).unwrap());
+        let count = paginator
+            .num_items_and_pages()
+            .await
+            .map_err(|e| Box::new(AsCoreError::from(e)) as Box<dyn common::error::Error>)?;
+        let result = Box::new(AuditSearchResult {
+            num_pages: count.number_of_pages as usize,
+            total_items: count.number_of_items as usize,
+            page: pagination.get_page(),
+            per_page,
+            result: paginator
+                .fetch_page(TryInto::<u64>::try_into(pagination.get_page() - 1).unwrap())
+                .await
+                .map_err(|e| Box::new(AsCoreError::from(e)) as Box<dyn common::error::Error>)?,
+        });
+        Ok(result as Self::Result)
+    }
+}
+
+impl SearchResult for AuditSearchResult {
+    type Result = Box<dyn AuditEvent>;
+
+    fn get_num_pages(&self) -> Option<usize> {
+        Some(self.num_pages)
+    }
+
+    fn get_total_items(&self) -> Option<usize> {
+        Some(self.total_items)
+    }
+
+    fn get_page(&self) -> Option<usize> {
+        Some(self.page)
+    }
+
+    fn get_per_page(&self) -> usize {
+        self.per_page
+    }
+
+    fn get_next_cursor(&self) -> Option<String> {
+        None
+    }
+
+    fn get_prev_cursor(&self) -> Option<String> {
+        None
+    }
+
+    /// Events are never changed once recorded
+    fn get_last_modified(&self) -> Option<DateTime<Utc>> {
+        self.result.iter().map(|event| event.created_at).max()
+    }
+
+    fn get_result(&self) -> Box<dyn Iterator<Item = Self::Result>> {
+        Box::new(
+            self.result
+                .clone()
+                .into_iter()
+                .map(|v| Box::new(v) as Box<dyn AuditEvent>),
+        )
+    }
+}
@@ -0,0 +1,406 @@
+use std::fmt::Display;
+
+use crate::error::AsCoreError;
+use async_trait::async_trait;
+use common::authorization::Authorizable;
+use common::error::CoreError;
+use common::management::{Manageable, Precondition};
+use entity::{permission, role, role_permission, user, user_role};
+use sea_orm::sea_query::{Expr, Query};
+use sea_orm::ActiveValue::Set;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, ConnectionTrait, EntityTrait, JoinType, QueryFilter,
+    QuerySelect, RelationTrait,
+};
+use uuid::Uuid;
+
+/// Roles of the users, permissions are only granted to the live users of the organization `tenant`
+pub struct RoleManagement {
+    tenant: Uuid,
+}
+
+impl RoleManagement {
+    pub fn new(tenant: Uuid) -> Self {
+        Self { tenant }
+    }
+}
+
+/// Check the permissions of `actor`, a live user of the organization `tenant`, on `resource`
+/// before delegating to `management`.
+/// The record targeted by `update` and `delete` is owned by the actor when its id is the actor's id.
+pub struct Authorized<M> {
+    actor: Box<dyn Display + Sync + Send>,
+    tenant: Uuid,
+    resource: &'static str,
+    management: M,
+}
+
+impl<M> Authorized<M> {
+    pub fn new(
+        actor: Box<dyn Display + Sync + Send>,
+        tenant: Uuid,
+        resource: &'static str,
+        management: M,
+    ) -> Self {
+        Self {
+            actor,
+            tenant,
+            resource,
+            management,
+        }
+    }
+}
+
+fn parse_id(id: &(dyn Display + Sync + Send)) -> Result<Uuid, Box<dyn common::error::Error>> {
+    id.to_string()
+        .as_str()
+        .parse::<Uuid>()
+        .map_err(|e| Box::new(AsCoreError::from(e)) as Box<dyn common::error::Error>)
+}
+
+/// Roles of a deleted actor, or of an actor from another organization than `tenant`, grant nothing.
+async fn check_permission<C: ConnectionTrait>(
+    actor: &(dyn Display + Sync + Send),
+    tenant: Uuid,
+    permission: String,
+    owner: Option<&(dyn Display + Sync + Send)>,
+    backend: &C,
+) -> Result<(), Box<dyn common::error::Error>> {
+    let forbidden = || {
+        Box::new(AsCoreError::from(CoreError::OperationForbiden(format!(
+            "Permission {} required",
+            permission
+        )))) as Box<dyn common::error::Error>
+    };
+    let Ok(actor_id) = actor.to_string().as_str().parse::<Uuid>() else {
+        return Err(forbidden());
+    };
+    let mut accepted = vec![permission.to_owned()];
+    if owner.is_some_and(|owner| owner.to_string() == actor_id.to_string()) {
+        accepted.push(format!("{}:own", permission));
+    }
+    let granted = permission::Entity::find()
+        .join(
+            JoinType::InnerJoin,
+            permission::Relation::RolePermission.def(),
+        )
+        .filter(permission::Column::Name.is_in(accepted))
+        .filter(
+            role_permission::Column::RoleId.in_subquery(
+                Query::select()
+                    .column(user_role::Column::RoleId)
+                    .from(user_role::Entity)
+                    .inner_join(
+                        user::Entity,
+                        Expr::col((user::Entity, user::Column::Id))
+                            .equals((user_role::Entity, user_role::Column::UserId)),
+                    )
+                    .and_where(user_role::Column::UserId.eq(actor_id))
+                    .and_where(user::Column::TenantId.eq(tenant))
+                    .and_where(user::Column::DeletedAt.is_null())
+                    .to_owned(),
+            ),
+        )
+        .one(backend)
+        .await
+        .map_err(|e| Box::new(AsCoreError::from(e)) as Box<dyn common::error::Error>)?;
+    match granted {
+        Some(_) => Ok(()),
+        None => Err(forbidden()),
+    }
+}
+
+async fn find_role<C: ConnectionTrait>(
+    name: String,
+    backend: &C,
+) -> Result<role::Model, Box<dyn common::error::Error>> {
+    role::Entity::find()
+        .filter(role::Column::Name.eq(name))
+        .one(backend)
+        .await
+        .map_err(|e| Box::new(AsCoreError::from(e)) as Box<dyn common::error::Error>)?
+        .ok_or_else(|| {
+            Box::new(AsCoreError::from(CoreError::ResourceNotFound(
+                String::from("Role not found !"),
+            ))) as Box<dyn common::error::Error>
+        })
+}
+
+/// Id of `id` when it is a live user of the organization `tenant`, the roles of the others are
+/// out of reach.
+async fn find_user<C: ConnectionTrait>(
+    tenant: Uuid,
+    id: &(dyn Display + Sync + Send),
+    backend: &C,
+) -> Result<Uuid, Box<dyn common::error::Error>> {
+    let user_id = parse_id(id)?;
+    user::Entity::find_by_id(user_id)
+        .filter(user::Column::TenantId.eq(tenant))
+        .filter(user::Column::DeletedAt.is_null())
+        .one(backend)
+        .await
+        .map_err(|e| Box::new(AsCoreError::from(e)) as Box<dyn common::error::Error>)?
+        .map(|user| user.id)
+        .ok_or_else(|| {
+            Box::new(AsCoreError::from(CoreError::ResourceNotFound(
+                String::from("Record not found !"),
+            ))) as Box<dyn common::error::Error>
+        })
+}
+
+#[async_trait]
+impl<'a, B: ConnectionTrait> Authorizable<'a, B> for RoleManagement {
+    type Id = Box<dyn Display + Sync + Send>;
+
+    async fn authorize(
+        &self,
+        actor: Self::Id,
+        permission: String,
+        owner: Option<Self::Id>,
+        backend: &'a B,
+    ) -> Result<(), Box<dyn common::error::Error>> {
+        check_permission(
+            actor.as_ref(),
+            self.tenant,
+            permission,
+            owner.as_deref(),
+            backend,
+        )
+        .await
+    }
+
+    async fn get_roles(
+        &self,
+        id: Self::Id,
+        backend: &'a B,
+    ) -> Result<Vec<String>, Box<dyn common::error::Error>> {
+        let user_id = find_user(self.tenant, id.as_ref(), backend).await?;
+        role::Entity::find()
+            .join(JoinType::InnerJoin, role::Relation::UserRole.def())
+            .filter(user_role::Column::UserId.eq(user_id))
+            .all(backend)
+            .await
+            .map(|roles| roles.into_iter().map(|role| role.name).collect())
+            .map_err(|e| Box::new(AsCoreError::from(e)) as Box<dyn common::error::Error>)
+    }
+
+    async fn assign(
+        &self,
+        id: Self::Id,
+        role: String,
+        backend: &'a B,
+    ) -> Result<(), Box<dyn common::error::Error>> {
+        let user_id = find_user(self.tenant, id.as_ref(), backend).await?;
+        let role = find_role(role, backend).await?;
+        if user_role::Entity::find_by_id((user_id, role.id))
+            .one(backend)
+            .await
+            .map_err(|e| Box::new(AsCoreError::from(e)) as Box<dyn common::error::Error>)?
+            .is_some()
+        {
+            return Ok(());
+        }
+        user_role::ActiveModel {
+            user_id: Set(user_id),
+            role_id: Set(role.id),
+        }
+        .insert(backend)
+        .await
+        .map(|_| ())
+        .map_err(|e| Box::new(AsCoreError::from(e)) as Box<dyn common::error::Error>)
+    }
+
+    async fn unassign(
+        &self,
+        id: Self::Id,
+        role: String,
+        backend: &'a B,
+    ) -> Result<(), Box<dyn common::error::Error>> {
+        let user_id = find_user(self.tenant, id.as_ref(), backend).await?;
+        let role = find_role(role, backend).await?;
+        user_role::Entity::delete_by_id((user_id, role.id))
+            .exec(backend)
+            .await
+            .map_err(|e| Box::new(AsCoreError::from(e)) as Box<dyn common::error::Error>)
+            .and_then(|result| match result.rows_affected {
+                0 => Err(Box::new(AsCoreError::from(CoreError::ResourceNotFound(
+                    String::from("Record not found !"),
+                ))) as Box<dyn common::error::Error>),
+                _ => Ok(()),
+            })
+    }
+}
+
+#[async_trait]
+impl<'a, B, M, D, S, R, P> Manageable<'a, B> for Authorized<M>
+where
+    B: ConnectionTrait,
+    M: Manageable<
+        'a,
+        B,
+        Id = Box<dyn Display + Sync + Send>,
+        Data = D,
+        Search = S,
+        Result = R,
+        Patch = P,
+    >,
+    D: Send + 'static,
+    S: Send + 'static,
+    R: 'static,
+    P: Send + 'static,
+{
+    type Id = Box<dyn Display + Sync + Send>;
+    type Data = D;
+    type Search = S;
+    type Result = R;
+    type Patch = P;
+
+    async fn create(
+        &self,
+        data: Self::Data,
+        backend: &'a B,
+    ) -> Result<Self::Data, Box<dyn common::error::Error>> {
+        check_permission(
+            self.actor.as_ref(),
+            self.tenant,
+            format!("{}:create", self.resource),
+            None,
+            backend,
+        )
+        .await?;
+        self.management.create(data, backend).await
+    }
+
+    async fn read(
+        &self,
+        search_opt: Self::Search,
+        backend: &'a B,
+    ) -> Result<Self::Result, Box<dyn common::error::Error>> {
+        check_permission(
+            self.actor.as_ref(),
+            self.tenant,
+            format!("{}:read", self.resource),
+            None,
+            backend,
+        )
+        .await?;
+        self.management.read(search_opt, backend).await
+    }
+
+    /// Deleted records are only found with the `{resource}:restore` permission.
+    async fn find(
+        &self,
+        id: Self::Id,
+        include_deleted: bool,
+        backend: &'a B,
+    ) -> Result<Self::Data, Box<dyn common::error::Error>> {
+        check_permission(
+            self.actor.as_ref(),
+            self.tenant,
+            format!("{}:read", self.resource),
+            Some(id.as_ref()),
+            backend,
+        )
+        .await?;
+        if include_deleted {
+            check_permission(
+                self.actor.as_ref(),
+                self.tenant,
+                format!("{}:restore", self.resource),
+                None,
+                backend,
+            )
+            .await?;
+        }
+        self.management.find(id, include_deleted, backend).await
+    }
+
+    async fn update(
+        &self,
+        id: Self::Id,
+        data: Self::Data,
+        precondition: Precondition,
+        backend: &'a B,
+    ) -> Result<Self::Data, Box<dyn common::error::Error>> {
+        check_permission(
+            self.actor.as_ref(),
+            self.tenant,
+            format!("{}:update", self.resource),
+            Some(id.as_ref()),
+            backend,
+        )
+        .await?;
+        self.management
+            .update(id, data, precondition, backend)
+            .await
+    }
+
+    async fn patch(
+        &self,
+        id: Self::Id,
+        patch: Self::Patch,
+        precondition: Precondition,
+        backend: &'a B,
+    ) -> Result<Self::Data, Box<dyn common::error::Error>> {
+        check_permission(
+            self.actor.as_ref(),
+            self.tenant,
+            format!("{}:update", self.resource),
+            Some(id.as_ref()),
+            backend,
+        )
+        .await?;
+        self.management
+            .patch(id, patch, precondition, backend)
+            .await
+    }
+
+    async fn delete(
+        &self,
+        id: Self::Id,
+        precondition: Precondition,
+        backend: &'a B,
+    ) -> Result<(), Box<dyn common::error::Error>> {
+        check_permission(
+            self.actor.as_ref(),
+            self.tenant,
+            format!("{}:delete", self.resource),
+            Some(id.as_ref()),
+            backend,
+        )
+        .await?;
+        self.management.delete(id, precondition, backend).await
+    }
+
+    async fn restore(
+        &self,
+        id: Self::Id,
+        backend: &'a B,
+    ) -> Result<Self::Data, Box<dyn common::error::Error>> {
+        check_permission(
+            self.actor.as_ref(),
+            self.tenant,
+            format!("{}:restore", self.resource),
+            None,
+            backend,
+        )
+        .await?;
+        self.management.restore(id, backend).await
+    }
+
+    async fn purge(
+        &self,
+        id: Self::Id,
+        backend: &'a B,
+    ) -> Result<(), Box<dyn common::error::Error>> {
+        check_permission(
+            self.actor.as_ref(),
+            self.tenant,
+            format!("{}:purge", self.resource),
+            None,
+            backend,
+        )
+        .await?;
+        self.management.purge(id, backend).await
+    }
+}
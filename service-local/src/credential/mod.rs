@@ -0,0 +1,191 @@
+use std::fmt::Display;
+
+use crate::error::AsCoreError;
+use argon2::password_hash::rand_core::OsRng;
+use argon2::password_hash::SaltString;
+use argon2::{Argon2, PasswordHash, PasswordHasher, PasswordVerifier};
+use async_trait::async_trait;
+use common::credential::Authenticable;
+use common::error::CoreError;
+use common::user::userable::Userable;
+use entity::credential::{ActiveModel, Entity as CredentialEntity};
+use entity::user::{Column, Entity as UserEntity};
+use sea_orm::ActiveValue::Set;
+use sea_orm::{ActiveModelTrait, ColumnTrait, ConnectionTrait, EntityTrait, QueryFilter};
+use uuid::Uuid;
+
+const PASSWORD_MIN_LENGTH: usize = 8;
+
+/// Credentials of the users of a single organization, an email is only looked up in it.
+pub struct CredentialManagement {
+    tenant: Uuid,
+}
+
+impl CredentialManagement {
+    pub fn new(tenant: Uuid) -> Self {
+        Self { tenant }
+    }
+
+    async fn ensure_user<C: ConnectionTrait>(
+        &self,
+        user_id: Uuid,
+        backend: &C,
+    ) -> Result<(), Box<dyn common::error::Error>> {
+        match UserEntity::find_by_id(user_id)
+            .filter(Column::TenantId.eq(self.tenant))
+            .one(backend)
+            .await
+            .map_err(|e| Box::new(AsCoreError::from(e)) as Box<dyn common::error::Error>)?
+        {
+            Some(_) => Ok(()),
+            None => Err(Box::new(AsCoreError::from(CoreError::ResourceNotFound(
+                String::from("Record not found !"),
+            )))),
+        }
+    }
+}
+
+fn hash_password(password: &str) -> Result<String, Box<dyn common::error::Error>> {
+    Argon2::default()
+        .hash_password(password.as_bytes(), &SaltString::generate(&mut OsRng))
+        .map(|hash| hash.to_string())
+        .map_err(|e| {
+            Box::new(AsCoreError::from(CoreError::UnkownError(e.to_string())))
+                as Box<dyn common::error::Error>
+        })
+}
+
+fn verify_password(password: &str, password_hash: &str) -> bool {
+    PasswordHash::new(password_hash).is_ok_and(|hash| {
+        Argon2::default()
+            .verify_password(password.as_bytes(), &hash)
+            .is_ok()
+    })
+}
+
+fn invalid_credentials() -> Box<dyn common::error::Error> {
+    Box::new(AsCoreError::from(CoreError::OperationNotAuthorized(
+        String::from("Invalid email or password"),
+    )))
+}
+
+fn parse_id(id: &(dyn Display + Sync + Send)) -> Result<Uuid, Box<dyn common::error::Error>> {
+    id.to_string()
+        .as_str()
+        .parse::<Uuid>()
+        .map_err(|e| Box::new(AsCoreError::from(e)) as Box<dyn common::error::Error>)
+}
+
+#[async_trait]
+impl<'a, B: ConnectionTrait> Authenticable<'a, B> for CredentialManagement {
+    type Id = Box<dyn Display + Sync + Send>;
+    type User = Box<dyn Userable>;
+
+    async fn identify(
+        &self,
+        login: String,
+        backend: &'a B,
+    ) -> Result<Option<Self::User>, Box<dyn common::error::Error>> {
+        Ok(UserEntity::find()
+            .filter(Column::TenantId.eq(self.tenant))
+            .filter(Column::Email.eq(login))
+            .one(backend)
+            .await
+            .map_err(|e| Box::new(AsCoreError::from(e)) as Box<dyn common::error::Error>)?
+            .map(|user| Box::new(user) as Self::User))
+    }
+
+    async fn authenticate(
+        &self,
+        login: String,
+        password: String,
+        backend: &'a B,
+    ) -> Result<Self::User, Box<dyn common::error::Error>> {
+        let found = UserEntity::find()
+            .filter(Column::TenantId.eq(self.tenant))
+            .filter(Column::Email.eq(login))
+            .filter(Column::DeletedAt.is_null())
+            .find_also_related(CredentialEntity)
+            .one(backend)
+            .await
+            .map_err(|e| Box::new(AsCoreError::from(e)) as Box<dyn common::error::Error>)?;
+        match found {
+            Some((user, Some(credential)))
+                if verify_password(password.as_str(), credential.password_hash.as_str()) =>
+            {
+                Ok(Box::new(user))
+            }
+            Some((_, Some(_))) => Err(invalid_credentials()),
+            _ => {
+                // Hash anyway so that unknown emails take as long as wrong passwords.
+                hash_password(password.as_str())?;
+                Err(invalid_credentials())
+            }
+        }
+    }
+
+    async fn set_password(
+        &self,
+        id: Self::Id,
+        password: String,
+        backend: &'a B,
+    ) -> Result<(), Box<dyn common::error::Error>> {
+        let user_id = parse_id(id.as_ref())?;
+        if password.chars().count() < PASSWORD_MIN_LENGTH {
+            return Err(Box::new(AsCoreError::from(CoreError::DataError(format!(
+                "Password must contain at least {} characters",
+                PASSWORD_MIN_LENGTH
+            )))));
+        }
+        self.ensure_user(user_id, backend).await?;
+        let password_hash = hash_password(password.as_str())?;
+        let credential = CredentialEntity::find_by_id(user_id)
+            .one(backend)
+            .await
+            .map_err(|e| Box::new(AsCoreError::from(e)) as Box<dyn common::error::Error>)?;
+        match credential {
+            Some(credential) => {
+                let mut active_model: ActiveModel = credential.into();
+                active_model.password_hash = Set(password_hash);
+                active_model.update(backend).await
+            }
+            None => {
+                ActiveModel {
+                    user_id: Set(user_id),
+                    password_hash: Set(password_hash),
+                    ..Default::default()
+                }
+                .insert(backend)
+                .await
+            }
+        }
+        .map(|_| ())
+        .map_err(|e| Box::new(AsCoreError::from(e)) as Box<dyn common::error::Error>)
+    }
+
+    async fn change_password(
+        &self,
+        id: Self::Id,
+        current_password: String,
+        new_password: String,
+        backend: &'a B,
+    ) -> Result<(), Box<dyn common::error::Error>> {
+        let user_id = parse_id(id.as_ref())?;
+        self.ensure_user(user_id, backend).await?;
+        if let Some(credential) = CredentialEntity::find_by_id(user_id)
+            .one(backend)
+            .await
+            .map_err(|e| Box::new(AsCoreError::from(e)) as Box<dyn common::error::Error>)?
+        {
+            if !verify_password(current_password.as_str(), credential.password_hash.as_str()) {
+                return Err(Box::new(AsCoreError::from(
+                    CoreError::OperationNotAuthorized(String::from(
+                        "Current password is incorrect",
+                    )),
+                )));
+            }
+        }
+        self.set_password(Box::new(user_id), new_password, backend)
+            .await
+    }
+}
@@ -1,6 +1,7 @@
-use common::error::CoreError;
-use common::error::CoreError::{DataError, ResourceNotFound, UnkownError};
-use sea_orm::DbErr;
+use common::error::CoreError::{Conflict, DataError, ResourceNotFound, UnkownError};
+use common::error::{CoreError, FieldError};
+use log::{error, warn};
+use sea_orm::{DbErr, SqlErr};
 use std::convert::From;
 use uuid::Error;
 
@@ -13,14 +14,42 @@ impl common::error::Error for AsCoreError {
     }
 }
 
+impl AsCoreError {
+    /// Report a unique constraint violation as a conflict on the first of `fields`
+    /// named by the database message, the first one when none is named.
+    pub(crate) fn from_unique(value: DbErr, fields: &[&str]) -> Self {
+        match value.sql_err() {
+            Some(SqlErr::UniqueConstraintViolation(message)) if !fields.is_empty() => {
+                warn!("Unique constraint violated: {}", message);
+                let message = message.to_lowercase();
+                let field = fields
+                    .iter()
+                    .find(|field| message.contains(&field.to_lowercase()))
+                    .unwrap_or(&fields[0]);
+                AsCoreError(Conflict(FieldError::new(
+                    field,
+                    "already_exists",
+                    format!("{} is already used", field).as_str(),
+                )))
+            }
+            _ => AsCoreError::from(value),
+        }
+    }
+}
+
 impl From<DbErr> for AsCoreError {
     fn from(value: DbErr) -> Self {
-        if let Some(sql_error) = value.sql_err() {
-            AsCoreError(DataError(sql_error.to_string()))
+        // Database messages reveal the schema, they are logged and never sent back.
+        if value.sql_err().is_some() {
+            warn!("Data rejected by the database: {}", value);
+            AsCoreError(DataError(String::from("Data rejected by the database")))
         } else {
             match value {
                 DbErr::RecordNotFound(s) => AsCoreError(ResourceNotFound(s)),
-                unknown => AsCoreError(UnkownError(unknown.to_string())),
+                unknown => {
+                    error!("Database error: {}", unknown);
+                    AsCoreError(UnkownError(String::from("Database error")))
+                }
             }
         }
     }
@@ -31,3 +60,9 @@ impl From<uuid::Error> for AsCoreError {
         AsCoreError(DataError(value.to_string()))
     }
 }
+
+impl From<CoreError> for AsCoreError {
+    fn from(value: CoreError) -> Self {
+        AsCoreError(value)
+    }
+}
@@ -0,0 +1,515 @@
+use std::fmt::Display;
+
+use crate::error::AsCoreError;
+use crate::user::matches;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use common::{
+    error::CoreError,
+    group::{groupable::Groupable, memberable::Memberable, patchable::Patchable},
+    management::{DateField, Manageable, Precondition, SearchResult, Searchable, SortDirection},
+};
+use entity::group::{ActiveModel, Column, Entity, Model};
+use entity::{user, user_group};
+use sea_orm::sea_query::Expr;
+use sea_orm::ActiveValue::Set;
+use sea_orm::{
+    ActiveModelBehavior, ActiveModelTrait, Condition, DbErr, EntityTrait, IntoActiveModel, Order,
+    Paginator, PaginatorTrait, QueryFilter, QueryOrder, QuerySelect, SelectModel,
+};
+use sea_orm::{ColumnTrait, ConnectionTrait};
+use uuid::Uuid;
+
+/// Groups of a single organization, only the users of the same organization can join them.
+pub struct GroupManagement {
+    tenant: Uuid,
+}
+
+impl GroupManagement {
+    pub fn new(tenant: Uuid) -> Self {
+        Self { tenant }
+    }
+}
+
+pub struct GroupSearchResult {
+    num_pages: usize,
+    total_items: usize,
+    page: usize,
+    per_page: usize,
+    result: Vec<Model>,
+    last_modified: Option<DateTime<Utc>>,
+}
+
+/// Fields no two groups of the same organization can share
+const UNIQUE_FIELDS: &[&str] = &["name"];
+
+fn precondition_failed() -> Box<dyn common::error::Error> {
+    Box::new(AsCoreError::from(CoreError::PreconditionFailed(
+        String::from("Group changed since the version given by If-Match"),
+    )))
+}
+
+fn not_deleted() -> Box<dyn common::error::Error> {
+    Box::new(AsCoreError::from(CoreError::DataError(String::from(
+        "A group must be deleted before being purged",
+    ))))
+}
+
+fn not_found() -> Box<dyn common::error::Error> {
+    Box::new(AsCoreError::from(CoreError::ResourceNotFound(
+        String::from("Record not found !"),
+    )))
+}
+
+fn parse_id(id: &(dyn Display + Sync + Send)) -> Result<Uuid, Box<dyn common::error::Error>> {
+    id.to_string()
+        .as_str()
+        .parse::<Uuid>()
+        .map_err(|e| Box::new(AsCoreError::from(e)) as Box<dyn common::error::Error>)
+}
+
+/// Group of `id` in the organization `tenant`, shared by every operation on a single group.
+/// Deleted groups are only found with `include_deleted`.
+async fn find_group<C: ConnectionTrait>(
+    tenant: Uuid,
+    id: &(dyn Display + Sync + Send),
+    include_deleted: bool,
+    backend: &C,
+) -> Result<Model, Box<dyn common::error::Error>> {
+    let mut select_group = Entity::find_by_id(parse_id(id)?).filter(Column::TenantId.eq(tenant));
+    if !include_deleted {
+        select_group = select_group.filter(Column::DeletedAt.is_null());
+    }
+    select_group
+        .one(backend)
+        .await
+        .map_err(|e| Box::new(AsCoreError::from(e)) as Box<dyn common::error::Error>)?
+        .ok_or_else(not_found)
+}
+
+/// Save the changes of `active_model` if its version still meets `precondition`,
+/// the version is incremented by the same statement so that concurrent changes can not both win.
+async fn save<C: ConnectionTrait>(
+    tenant: Uuid,
+    active_model: ActiveModel,
+    precondition: &Precondition,
+    backend: &C,
+) -> Result<Model, Box<dyn common::error::Error>> {
+    let id = active_model.id.clone().unwrap();
+    let active_model = active_model
+        .before_save(backend, false)
+        .await
+        .map_err(|e| Box::new(AsCoreError::from(e)) as Box<dyn common::error::Error>)?;
+    let mut update = Entity::update_many()
+        .set(active_model)
+        .col_expr(Column::Version, Expr::col(Column::Version).add(1))
+        .filter(Column::Id.eq(id))
+        .filter(Column::TenantId.eq(tenant));
+    if let Precondition::Versions(versions) = precondition {
+        update = update.filter(Column::Version.is_in(versions.to_owned()));
+    }
+    let updated = update.exec(backend).await.map_err(|e| {
+        Box::new(AsCoreError::from_unique(e, UNIQUE_FIELDS)) as Box<dyn common::error::Error>
+    })?;
+    if updated.rows_affected == 0 {
+        return Err(precondition_failed());
+    }
+    Entity::find_by_id(id)
+        .one(backend)
+        .await
+        .map_err(|e| Box::new(AsCoreError::from(e)) as Box<dyn common::error::Error>)?
+        .ok_or_else(|| {
+            Box::new(AsCoreError::from(DbErr::RecordNotFound(String::from(
+                "Record not found !",
+            )))) as Box<dyn common::error::Error>
+        })
+}
+
+/// Latest creation, update, deletion or purge among the groups of the organization `tenant`.
+/// The deleted groups are counted since a deletion takes a group out of the lists.
+async fn last_modified<C: ConnectionTrait>(
+    tenant: Uuid,
+    backend: &C,
+) -> Result<Option<DateTime<Utc>>, Box<dyn common::error::Error>> {
+    let (created_at, updated_at, deleted_at) = Entity::find()
+        .select_only()
+        .column_as(Column::CreatedAt.max(), "created_at")
+        .column_as(Column::UpdatedAt.max(), "updated_at")
+        .column_as(Column::DeletedAt.max(), "deleted_at")
+        .filter(Column::TenantId.eq(tenant))
+        .into_tuple::<(
+            Option<DateTime<Utc>>,
+            Option<DateTime<Utc>>,
+            Option<DateTime<Utc>>,
+        )>()
+        .one(backend)
+        .await
+        .map_err(|e| Box::new(AsCoreError::from(e)) as Box<dyn common::error::Error>)?
+        .unwrap_or_default();
+    let purged_at = crate::audit::purged_at(tenant, "group", backend).await?;
+    Ok([created_at, updated_at, deleted_at, purged_at]
+        .into_iter()
+        .flatten()
+        .max())
+}
+
+/// Columns the groups can be sorted on
+fn sortable_column(field: &str) -> Result<Column, Box<dyn common::error::Error>> {
+    match field {
+        "id" => Ok(Column::Id),
+        "name" => Ok(Column::Name),
+        "created_at" => Ok(Column::CreatedAt),
+        "updated_at" => Ok(Column::UpdatedAt),
+        other => Err(Box::new(AsCoreError::from(CoreError::DataError(format!(
+            "Groups can not be sorted on {}",
+            other
+        ))))),
+    }
+}
+
+fn filterable_column(field: &str) -> Result<Column, Box<dyn common::error::Error>> {
+    match field {
+        "name" => Ok(Column::Name),
+        "description" => Ok(Column::Description),
+        other => Err(Box::new(AsCoreError::from(CoreError::DataError(format!(
+            "Groups can not be filtered on {}",
+            other
+        ))))),
+    }
+}
+
+#[async_trait]
+impl<'a, B: ConnectionTrait> Manageable<'a, B> for GroupManagement {
+    type Id = Box<dyn Display + Sync + Send>;
+    type Data = Box<dyn Groupable>;
+    type Search = Box<dyn Searchable<Id = Box<dyn Display + Sync + Send>>>;
+    type Result = Box<dyn SearchResult<Result = Box<dyn Groupable>>>;
+    type Patch = Box<dyn Patchable>;
+
+    async fn create(
+        &self,
+        data: Self::Data,
+        backend: &'a B,
+    ) -> Result<Self::Data, Box<dyn common::error::Error>> {
+        Ok(Box::new(
+            ActiveModel {
+                id: Set(Uuid::new_v4()),
+                tenant_id: Set(self.tenant),
+                name: Set(data.get_name()),
+                description: Set(data.get_description()),
+                ..Default::default()
+            }
+            .insert(backend)
+            .await
+            .map_err(|e| {
+                Box::new(AsCoreError::from_unique(e, UNIQUE_FIELDS))
+                    as Box<dyn common::error::Error>
+            })?,
+        ))
+    }
+
+    /// Groups are only read by page, a cursor is refused.
+    async fn read(
+        &self,
+        search_opt: Self::Search,
+        backend: &'a B,
+    ) -> Result<Self::Result, Box<dyn common::error::Error>> {
+        let mut select_groups = match search_opt.get_id() {
+            Some(id) => Entity::find_by_id(parse_id(id.as_ref())?),
+            None => Entity::find(),
+        }
+        .filter(Column::TenantId.eq(self.tenant));
+        if !search_opt.get_include_deleted() {
+            select_groups = select_groups.filter(Column::DeletedAt.is_null());
+        }
+        if let Some(pattern) = search_opt.get_pattern() {
+            let match_mode = search_opt
+                .get_match_mode()
+                .map_err(|e| Box::new(AsCoreError::from(e)) as Box<dyn common::error::Error>)?;
+            let pattern = pattern.to_string();
+            select_groups = select_groups.filter(
+                Condition::any()
+                    .add(matches(Column::Name, pattern.as_str(), match_mode))
+                    .add(matches(Column::Description, pattern.as_str(), match_mode)),
+            );
+        }
+        for filter in search_opt.get_filters() {
+            select_groups = select_groups.filter(matches(
+                filterable_column(filter.get_field())?,
+                filter.get_value(),
+                filter.get_match_mode(),
+            ));
+        }
+        if let Some(date_range) = search_opt
+            .get_date_range()
+            .map_err(|e| Box::new(AsCoreError::from(e)) as Box<dyn common::error::Error>)?
+        {
+            let column = match date_range.get_field() {
+                DateField::CreatedAt => Column::CreatedAt,
+                DateField::UpdatedAt => Column::UpdatedAt,
+            };
+            select_groups =
+                select_groups.filter(column.between(date_range.get_start(), date_range.get_end()));
+        }
+        if search_opt
+            .get_cursor()
+            .map_err(|e| Box::new(AsCoreError::from(e)) as Box<dyn common::error::Error>)?
+            .is_some()
+        {
+            return Err(Box::new(AsCoreError::from(CoreError::DataError(
+                String::from("Groups are read by page, not by cursor"),
+            ))));
+        }
+        for sort in search_opt
+            .get_sort()
+            .map_err(|e| Box::new(AsCoreError::from(e)) as Box<dyn common::error::Error>)?
+        {
+            select_groups = select_groups.order_by(
+                sortable_column(sort.get_field())?,
+                match sort.get_direction() {
+                    SortDirection::Ascending => Order::Asc,
+                    SortDirection::Descending => Order::Desc,
+                },
+            );
+        }
+        // The primary key breaks ties so that pages never overlap.
+        select_groups = select_groups.order_by(Column::Id, Order::Asc);
+        let pagination = search_opt
+            .get_pagination()
+            .map_err(|e| Box::new(AsCoreError::from(e)) as Box<dyn common::error::Error>)?;
+        let per_page = pagination.get_per_page();
+        let paginator: Paginator<_, SelectModel<Model>> =
+            select_groups.paginate(backend, TryInto::<u64>::try_into(per_page).unwrap());
+        let count = paginator
+            .num_items_and_pages()
+            .await
+            .map_err(|e| Box::new(AsCoreError::from(e)) as Box<dyn common::error::Error>)?;
+        let result = Box::new(GroupSearchResult {
+            num_pages: count.number_of_pages as usize,
+            total_items: count.number_of_items as usize,
+            page: pagination.get_page(),
+            per_page,
+            result: paginator
+                .fetch_page(TryInto::<u64>::try_into(pagination.get_page() - 1).unwrap())
+                .await
+                .map_err(|e| Box::new(AsCoreError::from(e)) as Box<dyn common::error::Error>)?,
+            last_modified: last_modified(self.tenant, backend).await?,
+        });
+        Ok(result as Self::Result)
+    }
+
+    async fn find(
+        &self,
+        id: Self::Id,
+        include_deleted: bool,
+        backend: &'a B,
+    ) -> Result<Self::Data, Box<dyn common::error::Error>> {
+        Ok(
+            Box::new(find_group(self.tenant, id.as_ref(), include_deleted, backend).await?)
+                as Self::Data,
+        )
+    }
+
+    async fn update(
+        &self,
+        id: Self::Id,
+        data: Self::Data,
+        precondition: Precondition,
+        backend: &'a B,
+    ) -> Result<Self::Data, Box<dyn common::error::Error>> {
+        let selected_model = find_group(self.tenant, id.as_ref(), false, backend).await?;
+        if !precondition.is_met(selected_model.version) {
+            return Err(precondition_failed());
+        }
+        let mut active_model = selected_model.into_active_model();
+        active_model.name = Set(data.get_name());
+        active_model.description = Set(data.get_description());
+        Ok(Box::new(save(self.tenant, active_model, &precondition, backend).await?) as Self::Data)
+    }
+
+    async fn patch(
+        &self,
+        id: Self::Id,
+        patch: Self::Patch,
+        precondition: Precondition,
+        backend: &'a B,
+    ) -> Result<Self::Data, Box<dyn common::error::Error>> {
+        let selected_model = find_group(self.tenant, id.as_ref(), false, backend).await?;
+        if !precondition.is_met(selected_model.version) {
+            return Err(precondition_failed());
+        }
+        let mut active_model = selected_model.clone().into_active_model();
+        if let Some(name) = patch.get_name() {
+            active_model.name = Set(name);
+        }
+        if let Some(description) = patch.get_description() {
+            active_model.description = Set(description);
+        }
+        if !active_model.is_changed() {
+            return Ok(Box::new(selected_model) as Self::Data);
+        }
+        Ok(Box::new(save(self.tenant, active_model, &precondition, backend).await?) as Self::Data)
+    }
+
+    /// The members stay in a deleted group, they are removed when it is purged.
+    async fn delete(
+        &self,
+        id: Self::Id,
+        precondition: Precondition,
+        backend: &'a B,
+    ) -> Result<(), Box<dyn common::error::Error>> {
+        let selected_model = find_group(self.tenant, id.as_ref(), false, backend).await?;
+        if !precondition.is_met(selected_model.version) {
+            return Err(precondition_failed());
+        }
+        let mut active_model = selected_model.into_active_model();
+        active_model.deleted_at = Set(Some(Utc::now()));
+        save(self.tenant, active_model, &precondition, backend).await?;
+        Ok(())
+    }
+
+    async fn restore(
+        &self,
+        id: Self::Id,
+        backend: &'a B,
+    ) -> Result<Self::Data, Box<dyn common::error::Error>> {
+        let selected_model = find_group(self.tenant, id.as_ref(), true, backend).await?;
+        if selected_model.deleted_at.is_none() {
+            return Ok(Box::new(selected_model) as Self::Data);
+        }
+        let mut active_model = selected_model.into_active_model();
+        active_model.deleted_at = Set(None);
+        Ok(
+            Box::new(save(self.tenant, active_model, &Precondition::Any, backend).await?)
+                as Self::Data,
+        )
+    }
+
+    async fn purge(
+        &self,
+        id: Self::Id,
+        backend: &'a B,
+    ) -> Result<(), Box<dyn common::error::Error>> {
+        let selected_model = find_group(self.tenant, id.as_ref(), true, backend).await?;
+        if selected_model.deleted_at.is_none() {
+            return Err(not_deleted());
+        }
+        // Only a group still deleted is purged, in case it was restored meanwhile.
+        match Entity::delete_many()
+            .filter(Column::Id.eq(selected_model.id))
+            .filter(Column::TenantId.eq(self.tenant))
+            .filter(Column::DeletedAt.is_not_null())
+            .exec(backend)
+            .await
+            .map_err(|e| Box::new(AsCoreError::from(e)) as Box<dyn common::error::Error>)?
+            .rows_affected
+        {
+            0 => Err(not_deleted()),
+            _ => Ok(()),
+        }
+    }
+}
+
+#[async_trait]
+impl<'a, B: ConnectionTrait> Memberable<'a, B> for GroupManagement {
+    type Id = Box<dyn Display + Sync + Send>;
+
+    async fn add_member(
+        &self,
+        group: Self::Id,
+        member: Self::Id,
+        backend: &'a B,
+    ) -> Result<(), Box<dyn common::error::Error>> {
+        let group_id = find_group(self.tenant, group.as_ref(), false, backend)
+            .await?
+            .id;
+        let user_id = parse_id(member.as_ref())?;
+        if user::Entity::find_by_id(user_id)
+            .filter(user::Column::TenantId.eq(self.tenant))
+            .filter(user::Column::DeletedAt.is_null())
+            .one(backend)
+            .await
+            .map_err(|e| Box::new(AsCoreError::from(e)) as Box<dyn common::error::Error>)?
+            .is_none()
+        {
+            return Err(not_found());
+        }
+        if user_group::Entity::find_by_id((user_id, group_id))
+            .one(backend)
+            .await
+            .map_err(|e| Box::new(AsCoreError::from(e)) as Box<dyn common::error::Error>)?
+            .is_some()
+        {
+            return Ok(());
+        }
+        user_group::ActiveModel {
+            user_id: Set(user_id),
+            group_id: Set(group_id),
+        }
+        .insert(backend)
+        .await
+        .map(|_| ())
+        .map_err(|e| Box::new(AsCoreError::from(e)) as Box<dyn common::error::Error>)
+    }
+
+    async fn remove_member(
+        &self,
+        group: Self::Id,
+        member: Self::Id,
+        backend: &'a B,
+    ) -> Result<(), Box<dyn common::error::Error>> {
+        let group_id = find_group(self.tenant, group.as_ref(), false, backend)
+            .await?
+            .id;
+        let user_id = parse_id(member.as_ref())?;
+        user_group::Entity::delete_by_id((user_id, group_id))
+            .exec(backend)
+            .await
+            .map_err(|e| Box::new(AsCoreError::from(e)) as Box<dyn common::error::Error>)
+            .and_then(|result| match result.rows_affected {
+                0 => Err(not_found()),
+                _ => Ok(()),
+            })
+    }
+}
+
+impl SearchResult for GroupSearchResult {
+    type Result = Box<dyn Groupable>;
+
+    fn get_num_pages(&self) -> Option<usize> {
+        Some(self.num_pages)
+    }
+
+    fn get_total_items(&self) -> Option<usize> {
+        Some(self.total_items)
+    }
+
+    fn get_page(&self) -> Option<usize> {
+        Some(self.page)
+    }
+
+    fn get_per_page(&self) -> usize {
+        self.per_page
+    }
+
+    fn get_next_cursor(&self) -> Option<String> {
+        None
+    }
+
+    fn get_prev_cursor(&self) -> Option<String> {
+        None
+    }
+
+    fn get_last_modified(&self) -> Option<DateTime<Utc>> {
+        self.last_modified
+    }
+
+    fn get_result(&self) -> Box<dyn Iterator<Item = Self::Result>> {
+        Box::new(
+            self.result
+                .clone()
+                .into_iter()
+                .map(|v| Box::new(v) as Box<dyn Groupable>),
+        )
+    }
+}
@@ -0,0 +1,211 @@
+use crate::error::AsCoreError;
+use async_trait::async_trait;
+use chrono::{Duration, Utc};
+use common::error::CoreError;
+use common::import::{ImportProgress, ImportStatus, Importable, RowError, RowOutcome};
+use entity::import_job::{ActiveModel, Column, Entity, Model};
+use entity::import_job_error;
+use sea_orm::sea_query::Expr;
+use sea_orm::ActiveValue::Set;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, ConnectionTrait, EntityTrait, QueryFilter, QueryOrder,
+};
+use uuid::Uuid;
+
+/// Hours a finished import is kept for its status and its report
+const RETENTION_HOURS: i64 = 24;
+
+/// Minutes a running import can go without counting a row before it is deemed interrupted
+const STALE_MINUTES: i64 = 5;
+
+/// Imports of a single organization.
+pub struct ImportManagement {
+    tenant: Uuid,
+}
+
+impl ImportManagement {
+    pub fn new(tenant: Uuid) -> Self {
+        Self { tenant }
+    }
+
+    async fn find_model<C: ConnectionTrait>(
+        &self,
+        id: Uuid,
+        actor: String,
+        backend: &C,
+    ) -> Result<Model, Box<dyn common::error::Error>> {
+        Entity::find_by_id(id)
+            .filter(Column::TenantId.eq(self.tenant))
+            .filter(Column::Actor.eq(actor))
+            .filter(
+                Column::FinishedAt
+                    .is_null()
+                    .or(Column::FinishedAt.gt(expired())),
+            )
+            .one(backend)
+            .await
+            .map_err(|e| Box::new(AsCoreError::from(e)) as Box<dyn common::error::Error>)?
+            .ok_or_else(|| {
+                Box::new(AsCoreError::from(CoreError::ResourceNotFound(format!(
+                    "Import {} not found",
+                    id
+                )))) as Box<dyn common::error::Error>
+            })
+    }
+}
+
+/// Finishing date of the imports no longer kept
+fn expired() -> chrono::DateTime<Utc> {
+    Utc::now() - Duration::hours(RETENTION_HOURS)
+}
+
+#[async_trait]
+impl<'a, B: ConnectionTrait> Importable<'a, B> for ImportManagement {
+    type Id = Uuid;
+
+    async fn start(
+        &self,
+        actor: String,
+        dry_run: bool,
+        upsert: bool,
+        total_rows: usize,
+        backend: &'a B,
+    ) -> Result<Box<dyn ImportProgress>, Box<dyn common::error::Error>> {
+        // Their errors are deleted in cascade.
+        Entity::delete_many()
+            .filter(Column::TenantId.eq(self.tenant))
+            .filter(Column::FinishedAt.lte(expired()))
+            .exec(backend)
+            .await
+            .map_err(|e| Box::new(AsCoreError::from(e)) as Box<dyn common::error::Error>)?;
+        ActiveModel {
+            id: Set(Uuid::new_v4()),
+            tenant_id: Set(self.tenant),
+            actor: Set(actor),
+            status: Set(String::from(ImportStatus::Running.as_str())),
+            dry_run: Set(dry_run),
+            upsert: Set(upsert),
+            total_rows: Set(total_rows as i32),
+            processed_rows: Set(0),
+            created: Set(0),
+            updated: Set(0),
+            failed: Set(0),
+            finished_at: Set(None),
+            ..Default::default()
+        }
+        .insert(backend)
+        .await
+        .map(|job| Box::new(job) as Box<dyn ImportProgress>)
+        .map_err(|e| Box::new(AsCoreError::from(e)) as Box<dyn common::error::Error>)
+    }
+
+    async fn progress(
+        &self,
+        id: Self::Id,
+        outcome: RowOutcome,
+        backend: &'a B,
+    ) -> Result<(), Box<dyn common::error::Error>> {
+        let counter = match &outcome {
+            RowOutcome::Created => Column::Created,
+            RowOutcome::Updated => Column::Updated,
+            RowOutcome::Failed(_) => Column::Failed,
+        };
+        // Counters are incremented by the statement itself, the job is never read back.
+        Entity::update_many()
+            .col_expr(
+                Column::ProcessedRows,
+                Expr::col(Column::ProcessedRows).add(1),
+            )
+            .col_expr(counter, Expr::col(counter).add(1))
+            .col_expr(Column::UpdatedAt, Expr::value(Utc::now()))
+            .filter(Column::Id.eq(id))
+            .filter(Column::TenantId.eq(self.tenant))
+            .exec(backend)
+            .await
+            .map_err(|e| Box::new(AsCoreError::from(e)) as Box<dyn common::error::Error>)?;
+        let RowOutcome::Failed(errors) = outcome else {
+            return Ok(());
+        };
+        if errors.is_empty() {
+            return Ok(());
+        }
+        import_job_error::Entity::insert_many(errors.into_iter().map(|error| {
+            import_job_error::ActiveModel {
+                id: Set(Uuid::new_v4()),
+                job_id: Set(id),
+                line: Set(error.get_line() as i64),
+                status: Set(error.get_status() as i32),
+                code: Set(error.get_code().to_owned()),
+                field: Set(error.get_field().map(String::from)),
+                message: Set(error.get_message().to_owned()),
+            }
+        }))
+        .exec(backend)
+        .await
+        .map(|_| ())
+        .map_err(|e| Box::new(AsCoreError::from(e)) as Box<dyn common::error::Error>)
+    }
+
+    async fn finish(
+        &self,
+        id: Self::Id,
+        status: ImportStatus,
+        backend: &'a B,
+    ) -> Result<(), Box<dyn common::error::Error>> {
+        let now = Utc::now();
+        Entity::update_many()
+            .col_expr(Column::Status, Expr::value(status.as_str()))
+            .col_expr(Column::UpdatedAt, Expr::value(now))
+            .col_expr(Column::FinishedAt, Expr::value(now))
+            .filter(Column::Id.eq(id))
+            .filter(Column::TenantId.eq(self.tenant))
+            .exec(backend)
+            .await
+            .map(|_| ())
+            .map_err(|e| Box::new(AsCoreError::from(e)) as Box<dyn common::error::Error>)
+    }
+
+    async fn find(
+        &self,
+        id: Self::Id,
+        actor: String,
+        backend: &'a B,
+    ) -> Result<Box<dyn ImportProgress>, Box<dyn common::error::Error>> {
+        let job = self.find_model(id, actor.to_owned(), backend).await?;
+        let stale = Utc::now() - Duration::minutes(STALE_MINUTES);
+        if job.get_status() != ImportStatus::Running || job.updated_at > stale {
+            return Ok(Box::new(job));
+        }
+        // The instance running the import stopped, only a job still untouched is failed
+        // so that a row counted meanwhile keeps it running.
+        Entity::update_many()
+            .col_expr(Column::Status, Expr::value(ImportStatus::Failed.as_str()))
+            .col_expr(Column::FinishedAt, Expr::value(Utc::now()))
+            .filter(Column::Id.eq(id))
+            .filter(Column::TenantId.eq(self.tenant))
+            .filter(Column::Status.eq(ImportStatus::Running.as_str()))
+            .filter(Column::UpdatedAt.lte(stale))
+            .exec(backend)
+            .await
+            .map_err(|e| Box::new(AsCoreError::from(e)) as Box<dyn common::error::Error>)?;
+        self.find_model(id, actor, backend)
+            .await
+            .map(|job| Box::new(job) as Box<dyn ImportProgress>)
+    }
+
+    async fn errors(
+        &self,
+        id: Self::Id,
+        actor: String,
+        backend: &'a B,
+    ) -> Result<Vec<RowError>, Box<dyn common::error::Error>> {
+        let job = self.find_model(id, actor, backend).await?;
+        import_job_error::Entity::find()
+            .filter(import_job_error::Column::JobId.eq(job.id))
+            .order_by_asc(import_job_error::Column::Line)
+            .all(backend)
+            .await
+            .map(|errors| errors.into_iter().map(RowError::from).collect())
+            .map_err(|e| Box::new(AsCoreError::from(e)) as Box<dyn common::error::Error>)
+    }
+}
@@ -1,3 +1,10 @@
+pub mod audit;
+pub mod authorization;
+pub mod credential;
+pub mod group;
+pub mod import;
+pub mod organization;
+pub mod refresh_token;
 pub mod user;
 
 mod error;
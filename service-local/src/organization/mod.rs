@@ -0,0 +1,74 @@
+use crate::error::AsCoreError;
+use async_trait::async_trait;
+use common::error::CoreError;
+use common::organization::Organizable;
+use entity::organization::{ActiveModel, Column, Entity};
+use sea_orm::ActiveValue::Set;
+use sea_orm::{ActiveModelTrait, ColumnTrait, ConnectionTrait, EntityTrait, QueryFilter};
+use uuid::Uuid;
+
+pub use entity::organization::DEFAULT_ORGANIZATION_ID;
+
+pub struct OrganizationManagement;
+
+/// Fields no two organizations can share
+const UNIQUE_FIELDS: &[&str] = &["slug"];
+
+/// A slug is a single DNS label so that it can name a subdomain.
+fn is_slug(slug: &str) -> bool {
+    (1..=63).contains(&slug.len())
+        && slug
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-')
+        && !slug.starts_with('-')
+        && !slug.ends_with('-')
+}
+
+#[async_trait]
+impl<'a, B: ConnectionTrait> Organizable<'a, B> for OrganizationManagement {
+    type Id = Uuid;
+
+    async fn create(
+        &self,
+        name: String,
+        slug: String,
+        backend: &'a B,
+    ) -> Result<Self::Id, Box<dyn common::error::Error>> {
+        if !is_slug(slug.as_str()) {
+            return Err(Box::new(AsCoreError::from(CoreError::DataError(format!(
+                "{} is not a valid slug, use lowercase letters, digits and dashes",
+                slug
+            )))));
+        }
+        ActiveModel {
+            id: Set(Uuid::new_v4()),
+            name: Set(name),
+            slug: Set(slug),
+            ..Default::default()
+        }
+        .insert(backend)
+        .await
+        .map(|organization| organization.id)
+        .map_err(|e| {
+            Box::new(AsCoreError::from_unique(e, UNIQUE_FIELDS)) as Box<dyn common::error::Error>
+        })
+    }
+
+    async fn resolve(
+        &self,
+        slug: String,
+        backend: &'a B,
+    ) -> Result<Self::Id, Box<dyn common::error::Error>> {
+        Entity::find()
+            .filter(Column::Slug.eq(slug))
+            .one(backend)
+            .await
+            .map_err(|e| Box::new(AsCoreError::from(e)) as Box<dyn common::error::Error>)?
+            .map(|organization| organization.id)
+            .ok_or_else(|| {
+                Box::new(AsCoreError::from(CoreError::ResourceNotFound(
+                    String::from("Organization not found !"),
+                ))) as Box<dyn common::error::Error>
+            })
+    }
+}
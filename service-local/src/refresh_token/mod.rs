@@ -0,0 +1,190 @@
+use std::fmt::Display;
+
+use crate::error::AsCoreError;
+use async_trait::async_trait;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use chrono::{Duration, Utc};
+use common::error::CoreError;
+use common::token::Refreshable;
+use entity::refresh_token::{ActiveModel, Column, Entity, Model};
+use entity::user;
+use rand::rngs::OsRng;
+use rand::RngCore;
+use sea_orm::sea_query::{Expr, Query};
+use sea_orm::ActiveValue::Set;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, ConnectionTrait, EntityTrait, QueryFilter, TransactionTrait,
+};
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
+
+/// Refresh tokens of the users of a single organization, the token of another one is unknown.
+pub struct RefreshTokenManagement {
+    tenant: Uuid,
+}
+
+impl RefreshTokenManagement {
+    pub fn new(tenant: Uuid) -> Self {
+        Self { tenant }
+    }
+}
+
+fn generate_token() -> String {
+    let mut bytes = [0u8; 32];
+    OsRng.fill_bytes(&mut bytes);
+    URL_SAFE_NO_PAD.encode(bytes)
+}
+
+/// Only the digest of a token is stored so that a database leak does not leak usable tokens.
+fn hash_token(token: &str) -> String {
+    URL_SAFE_NO_PAD.encode(Sha256::digest(token.as_bytes()))
+}
+
+fn invalid_token(message: &str) -> Box<dyn common::error::Error> {
+    Box::new(AsCoreError::from(CoreError::OperationNotAuthorized(
+        String::from(message),
+    )))
+}
+
+async fn insert_token<C: ConnectionTrait>(
+    user_id: Uuid,
+    family_id: Uuid,
+    lifetime: u64,
+    backend: &C,
+) -> Result<(Model, String), Box<dyn common::error::Error>> {
+    let token = generate_token();
+    let model = ActiveModel {
+        id: Set(Uuid::new_v4()),
+        user_id: Set(user_id),
+        family_id: Set(family_id),
+        token_hash: Set(hash_token(token.as_str())),
+        expires_at: Set(Utc::now() + Duration::seconds(lifetime as i64)),
+        ..Default::default()
+    }
+    .insert(backend)
+    .await
+    .map_err(|e| Box::new(AsCoreError::from(e)) as Box<dyn common::error::Error>)?;
+    Ok((model, token))
+}
+
+async fn revoke_family<C: ConnectionTrait>(
+    family_id: Uuid,
+    backend: &C,
+) -> Result<(), Box<dyn common::error::Error>> {
+    Entity::update_many()
+        .col_expr(Column::RevokedAt, Expr::value(Utc::now()))
+        .filter(Column::FamilyId.eq(family_id))
+        .filter(Column::RevokedAt.is_null())
+        .exec(backend)
+        .await
+        .map(|_| ())
+        .map_err(|e| Box::new(AsCoreError::from(e)) as Box<dyn common::error::Error>)
+}
+
+#[async_trait]
+impl<'a, B: ConnectionTrait + TransactionTrait> Refreshable<'a, B> for RefreshTokenManagement {
+    type Id = Box<dyn Display + Sync + Send>;
+
+    async fn issue(
+        &self,
+        id: Self::Id,
+        lifetime: u64,
+        backend: &'a B,
+    ) -> Result<String, Box<dyn common::error::Error>> {
+        let user_id = id
+            .to_string()
+            .as_str()
+            .parse::<Uuid>()
+            .map_err(|e| Box::new(AsCoreError::from(e)) as Box<dyn common::error::Error>)?;
+        insert_token(user_id, Uuid::new_v4(), lifetime, backend)
+            .await
+            .map(|(_, token)| token)
+    }
+
+    async fn rotate(
+        &self,
+        token: String,
+        lifetime: u64,
+        backend: &'a B,
+    ) -> Result<(Self::Id, String), Box<dyn common::error::Error>> {
+        let Some(current) = Entity::find()
+            .filter(Column::TokenHash.eq(hash_token(token.as_str())))
+            // Deleted users can not obtain new access tokens.
+            .filter(
+                Column::UserId.in_subquery(
+                    Query::select()
+                        .column(user::Column::Id)
+                        .from(user::Entity)
+                        .and_where(user::Column::TenantId.eq(self.tenant))
+                        .and_where(user::Column::DeletedAt.is_null())
+                        .to_owned(),
+                ),
+            )
+            .one(backend)
+            .await
+            .map_err(|e| Box::new(AsCoreError::from(e)) as Box<dyn common::error::Error>)?
+        else {
+            return Err(invalid_token("Invalid refresh token"));
+        };
+        if current.expires_at < Utc::now() {
+            return Err(invalid_token("Refresh token expired"));
+        }
+
+        let transaction = backend
+            .begin()
+            .await
+            .map_err(|e| Box::new(AsCoreError::from(e)) as Box<dyn common::error::Error>)?;
+        let (next, next_token) =
+            insert_token(current.user_id, current.family_id, lifetime, &transaction).await?;
+        // Only one exchange of a token can win, any other one is a replay.
+        let exchanged = Entity::update_many()
+            .col_expr(Column::RevokedAt, Expr::value(Utc::now()))
+            .col_expr(Column::ReplacedBy, Expr::value(next.id))
+            .filter(Column::Id.eq(current.id))
+            .filter(Column::RevokedAt.is_null())
+            .exec(&transaction)
+            .await
+            .map_err(|e| Box::new(AsCoreError::from(e)) as Box<dyn common::error::Error>)?
+            .rows_affected
+            == 1;
+        if !exchanged {
+            transaction
+                .rollback()
+                .await
+                .map_err(|e| Box::new(AsCoreError::from(e)) as Box<dyn common::error::Error>)?;
+            revoke_family(current.family_id, backend).await?;
+            return Err(invalid_token("Refresh token already used"));
+        }
+        transaction
+            .commit()
+            .await
+            .map_err(|e| Box::new(AsCoreError::from(e)) as Box<dyn common::error::Error>)?;
+        Ok((Box::new(current.user_id), next_token))
+    }
+
+    async fn revoke(
+        &self,
+        token: String,
+        backend: &'a B,
+    ) -> Result<(), Box<dyn common::error::Error>> {
+        if let Some(current) = Entity::find()
+            .filter(Column::TokenHash.eq(hash_token(token.as_str())))
+            .filter(
+                Column::UserId.in_subquery(
+                    Query::select()
+                        .column(user::Column::Id)
+                        .from(user::Entity)
+                        .and_where(user::Column::TenantId.eq(self.tenant))
+                        .to_owned(),
+                ),
+            )
+            .one(backend)
+            .await
+            .map_err(|e| Box::new(AsCoreError::from(e)) as Box<dyn common::error::Error>)?
+        {
+            revoke_family(current.family_id, backend).await?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,196 @@
+use crate::error::AsCoreError;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use chrono::{DateTime, Utc};
+use common::error::CoreError;
+use common::management::SortDirection;
+use entity::user::{Column, Model};
+use sea_orm::sea_query::Iden;
+use sea_orm::{ColumnTrait, Condition, Value};
+use uuid::Uuid;
+
+/// Sort keys of a page, the primary key is always the last one.
+pub(super) type Keys = Vec<(Column, SortDirection)>;
+
+fn invalid_cursor() -> Box<dyn common::error::Error> {
+    Box::new(AsCoreError::from(CoreError::DataError(String::from(
+        "Invalid cursor, it was issued for another sort or is malformed",
+    ))))
+}
+
+/// Canonical form of the sort, e.g. `last_name,-created_at,id`
+fn describe(keys: &Keys) -> String {
+    keys.iter()
+        .map(|(column, direction)| match direction {
+            SortDirection::Ascending => column.to_string(),
+            SortDirection::Descending => format!("-{}", column.to_string()),
+        })
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+fn key_of(model: &Model, column: Column) -> String {
+    match column {
+        Column::Id => model.id.to_string(),
+        Column::TenantId => model.tenant_id.to_string(),
+        Column::FirstName => model.first_name.to_owned(),
+        Column::LastName => model.last_name.to_owned(),
+        Column::Email => model.email.to_owned(),
+        Column::Version => model.version.to_string(),
+        Column::CreatedAt => model.created_at.to_rfc3339(),
+        Column::UpdatedAt => model
+            .updated_at
+            .map(|updated_at| updated_at.to_rfc3339())
+            .unwrap_or_default(),
+        Column::DeletedAt => model
+            .deleted_at
+            .map(|deleted_at| deleted_at.to_rfc3339())
+            .unwrap_or_default(),
+    }
+}
+
+fn parse_key(column: Column, key: String) -> Result<Value, Box<dyn common::error::Error>> {
+    match column {
+        Column::Id | Column::TenantId => key
+            .parse::<Uuid>()
+            .map(Value::from)
+            .map_err(|_| invalid_cursor()),
+        Column::FirstName | Column::LastName | Column::Email => Ok(Value::from(key)),
+        Column::Version => key
+            .parse::<i32>()
+            .map(Value::from)
+            .map_err(|_| invalid_cursor()),
+        Column::CreatedAt | Column::UpdatedAt | Column::DeletedAt => {
+            DateTime::parse_from_rfc3339(key.as_str())
+                .map(|date| Value::from(date.with_timezone(&Utc)))
+                .map_err(|_| invalid_cursor())
+        }
+    }
+}
+
+/// Encode the sort and the keys of `model` as dot separated base64url segments.
+pub(super) fn encode(keys: &Keys, model: &Model) -> String {
+    std::iter::once(describe(keys))
+        .chain(keys.iter().map(|(column, _)| key_of(model, *column)))
+        .map(|segment| URL_SAFE_NO_PAD.encode(segment))
+        .collect::<Vec<_>>()
+        .join(".")
+}
+
+/// Decode the keys of a cursor, it must have been issued for the same sort.
+pub(super) fn decode(
+    keys: &Keys,
+    token: &str,
+) -> Result<Vec<Value>, Box<dyn common::error::Error>> {
+    let mut segments = token.split('.').map(|segment| {
+        URL_SAFE_NO_PAD
+            .decode(segment)
+            .ok()
+            .and_then(|bytes| String::from_utf8(bytes).ok())
+            .ok_or_else(invalid_cursor)
+    });
+    if segments.next().transpose()? != Some(describe(keys)) {
+        return Err(invalid_cursor());
+    }
+    let values = keys
+        .iter()
+        .zip(segments.by_ref())
+        .map(|((column, _), segment)| parse_key(*column, segment?))
+        .collect::<Result<Vec<_>, _>>()?;
+    if values.len() != keys.len() || segments.next().is_some() {
+        return Err(invalid_cursor());
+    }
+    Ok(values)
+}
+
+/// Rows strictly after (`forward`) or before the cursor keys in the order of `keys`.
+pub(super) fn seek(keys: &Keys, values: Vec<Value>, forward: bool) -> Condition {
+    (0..keys.len()).fold(Condition::any(), |seek, i| {
+        let (column, direction) = keys[i];
+        let equal = keys[..i]
+            .iter()
+            .zip(values.iter())
+            .fold(Condition::all(), |equal, ((column, _), value)| {
+                equal.add(column.eq(value.clone()))
+            });
+        let value = values[i].clone();
+        let past = if (direction == SortDirection::Ascending) == forward {
+            column.gt(value)
+        } else {
+            column.lt(value)
+        };
+        seek.add(equal.add(past))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{decode, encode, seek, Keys};
+    use common::management::SortDirection;
+    use entity::user::{Column, Entity, Model};
+    use sea_orm::{DbBackend, EntityTrait, QueryFilter, QueryTrait, Value};
+    use uuid::Uuid;
+
+    fn keys() -> Keys {
+        vec![
+            (Column::LastName, SortDirection::Descending),
+            (Column::Id, SortDirection::Ascending),
+        ]
+    }
+
+    #[test]
+    fn test_cursor_round_trip() {
+        let model = Model {
+            id: Uuid::new_v4(),
+            last_name: String::from("Dupont.Durand"),
+            ..Default::default()
+        };
+        let token = encode(&keys(), &model);
+        assert_eq!(token.split('.').count(), 3);
+        let values = decode(&keys(), token.as_str()).unwrap();
+        assert_eq!(
+            values,
+            vec![Value::from(model.last_name), Value::from(model.id)]
+        );
+    }
+
+    #[test]
+    fn test_cursor_refused() {
+        let token = encode(&keys(), &Model::default());
+        // issued for another sort
+        let other_keys = vec![(Column::Id, SortDirection::Ascending)];
+        assert!(decode(&other_keys, token.as_str()).is_err());
+        // a key is missing or is left over
+        let (truncated, _) = token.rsplit_once('.').unwrap();
+        assert!(decode(&keys(), truncated).is_err());
+        assert!(decode(&keys(), format!("{}.e30", token).as_str()).is_err());
+        // malformed
+        assert!(decode(&keys(), "").is_err());
+        assert!(decode(&keys(), "not base64!").is_err());
+        let (sort, _) = token.split_once('.').unwrap();
+        assert!(decode(&keys(), format!("{}.YQ.YQ", sort).as_str()).is_err());
+    }
+
+    #[test]
+    fn test_seek() {
+        let id = Uuid::nil();
+        let sql = |forward| {
+            Entity::find()
+                .filter(seek(
+                    &keys(),
+                    vec![Value::from("Martin"), Value::from(id)],
+                    forward,
+                ))
+                .build(DbBackend::Sqlite)
+                .to_string()
+        };
+        let after = sql(true);
+        assert!(after.ends_with(
+            "WHERE \"user\".\"last_name\" < 'Martin' OR (\"user\".\"last_name\" = 'Martin' AND \"user\".\"id\" > '00000000-0000-0000-0000-000000000000')"
+        ), "{}", after);
+        let before = sql(false);
+        assert!(before.ends_with(
+            "WHERE \"user\".\"last_name\" > 'Martin' OR (\"user\".\"last_name\" = 'Martin' AND \"user\".\"id\" < '00000000-0000-0000-0000-000000000000')"
+        ), "{}", before);
+    }
+}
@@ -2,22 +2,250 @@ use std::fmt::Display;
 
 use crate::error::AsCoreError;
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
 use common::{
-    management::{Manageable, SearchResult, Searchable},
-    user::userable::Userable,
+    error::CoreError,
+    management::{
+        Cursor, DateField, Filter, Manageable, MatchMode, Precondition, SearchResult, Searchable,
+        SortDirection,
+    },
+    user::{patchable::Patchable, userable::Userable},
 };
 use entity::user::{ActiveModel, Column, Entity, Model};
+use entity::user_group;
+use sea_orm::sea_query::{Expr, Func, LikeExpr, Query, SimpleExpr};
 use sea_orm::ActiveValue::Set;
+use sea_orm::Order;
 use sea_orm::{
-    ActiveModelTrait, DbErr, EntityTrait, IntoActiveModel, ModelTrait, Paginator, PaginatorTrait,
-    QueryFilter, SelectModel,
+    ActiveModelBehavior, ActiveModelTrait, Condition, DbErr, EntityTrait, IntoActiveModel,
+    Paginator, PaginatorTrait, QueryFilter, QueryOrder, QuerySelect, SelectModel,
 };
 use sea_orm::{ColumnTrait, ConnectionTrait};
 use uuid::Uuid;
-pub struct UserManagement;
+/// Users of a single organization, no operation ever reaches the users of another one.
+pub struct UserManagement {
+    tenant: Uuid,
+}
+
+impl UserManagement {
+    pub fn new(tenant: Uuid) -> Self {
+        Self { tenant }
+    }
+}
+
 pub struct UserSearchResult {
-    num_pages: usize,
+    num_pages: Option<usize>,
+    total_items: Option<usize>,
+    page: Option<usize>,
+    per_page: usize,
     result: Vec<Model>,
+    next_cursor: Option<String>,
+    prev_cursor: Option<String>,
+    last_modified: Option<DateTime<Utc>>,
+}
+
+mod cursor;
+
+/// Order of a sort key, reversed when reading backward.
+fn order(direction: SortDirection, forward: bool) -> Order {
+    match (direction == SortDirection::Ascending) == forward {
+        true => Order::Asc,
+        false => Order::Desc,
+    }
+}
+
+/// Fields no two users of the same organization can share
+const UNIQUE_FIELDS: &[&str] = &["email"];
+
+fn precondition_failed() -> Box<dyn common::error::Error> {
+    Box::new(AsCoreError::from(CoreError::PreconditionFailed(
+        String::from("User changed since the version given by If-Match"),
+    )))
+}
+
+fn not_deleted() -> Box<dyn common::error::Error> {
+    Box::new(AsCoreError::from(CoreError::DataError(String::from(
+        "A user must be deleted before being purged",
+    ))))
+}
+
+/// User of `id` in the organization `tenant`, shared by every operation on a single user.
+/// Deleted users are only found with `include_deleted`.
+async fn find_user<C: ConnectionTrait>(
+    tenant: Uuid,
+    id: &(dyn Display + Sync + Send),
+    include_deleted: bool,
+    backend: &C,
+) -> Result<Model, Box<dyn common::error::Error>> {
+    let mut select_user = Entity::find_by_id(
+        id.to_string()
+            .as_str()
+            .parse::<Uuid>()
+            .map_err(|e| Box::new(AsCoreError::from(e)) as Box<dyn common::error::Error>)?,
+    )
+    .filter(Column::TenantId.eq(tenant));
+    if !include_deleted {
+        select_user = select_user.filter(Column::DeletedAt.is_null());
+    }
+    select_user
+        .one(backend)
+        .await
+        .map_err(|e| Box::new(AsCoreError::from(e)) as Box<dyn common::error::Error>)?
+        .ok_or_else(|| {
+            Box::new(AsCoreError::from(CoreError::ResourceNotFound(
+                String::from("Record not found !"),
+            ))) as Box<dyn common::error::Error>
+        })
+}
+
+/// Save the changes of `active_model` if its version still meets `precondition`,
+/// the version is incremented by the same statement so that concurrent changes can not both win.
+async fn save<C: ConnectionTrait>(
+    tenant: Uuid,
+    active_model: ActiveModel,
+    precondition: &Precondition,
+    backend: &C,
+) -> Result<Model, Box<dyn common::error::Error>> {
+    let id = active_model.id.clone().unwrap();
+    let active_model = active_model
+        .before_save(backend, false)
+        .await
+        .map_err(|e| Box::new(AsCoreError::from(e)) as Box<dyn common::error::Error>)?;
+    let mut update = Entity::update_many()
+        .set(active_model)
+        .col_expr(Column::Version, Expr::col(Column::Version).add(1))
+        .filter(Column::Id.eq(id))
+        .filter(Column::TenantId.eq(tenant));
+    if let Precondition::Versions(versions) = precondition {
+        update = update.filter(Column::Version.is_in(versions.to_owned()));
+    }
+    let updated = update.exec(backend).await.map_err(|e| {
+        Box::new(AsCoreError::from_unique(e, UNIQUE_FIELDS)) as Box<dyn common::error::Error>
+    })?;
+    if updated.rows_affected == 0 {
+        return Err(precondition_failed());
+    }
+    Entity::find_by_id(id)
+        .one(backend)
+        .await
+        .map_err(|e| Box::new(AsCoreError::from(e)) as Box<dyn common::error::Error>)?
+        .ok_or_else(|| {
+            Box::new(AsCoreError::from(DbErr::RecordNotFound(String::from(
+                "Record not found !",
+            )))) as Box<dyn common::error::Error>
+        })
+}
+
+/// Latest creation, update, deletion or purge among the users of the organization `tenant`.
+/// The deleted users are counted since a deletion takes a user out of the lists.
+async fn last_modified<C: ConnectionTrait>(
+    tenant: Uuid,
+    backend: &C,
+) -> Result<Option<DateTime<Utc>>, Box<dyn common::error::Error>> {
+    let (created_at, updated_at, deleted_at) = Entity::find()
+        .select_only()
+        .column_as(Column::CreatedAt.max(), "created_at")
+        .column_as(Column::UpdatedAt.max(), "updated_at")
+        .column_as(Column::DeletedAt.max(), "deleted_at")
+        .filter(Column::TenantId.eq(tenant))
+        .into_tuple::<(
+            Option<DateTime<Utc>>,
+            Option<DateTime<Utc>>,
+            Option<DateTime<Utc>>,
+        )>()
+        .one(backend)
+        .await
+        .map_err(|e| Box::new(AsCoreError::from(e)) as Box<dyn common::error::Error>)?
+        .unwrap_or_default();
+    let purged_at = crate::audit::purged_at(tenant, "user", backend).await?;
+    Ok([created_at, updated_at, deleted_at, purged_at]
+        .into_iter()
+        .flatten()
+        .max())
+}
+
+/// Columns the users can be sorted on
+fn sortable_column(field: &str) -> Result<Column, Box<dyn common::error::Error>> {
+    match field {
+        "id" => Ok(Column::Id),
+        "first_name" => Ok(Column::FirstName),
+        "last_name" => Ok(Column::LastName),
+        "email" => Ok(Column::Email),
+        "created_at" => Ok(Column::CreatedAt),
+        "updated_at" => Ok(Column::UpdatedAt),
+        other => Err(Box::new(AsCoreError::from(CoreError::DataError(format!(
+            "Users can not be sorted on {}",
+            other
+        ))))),
+    }
+}
+
+fn filterable_column(field: &str) -> Result<Column, Box<dyn common::error::Error>> {
+    match field {
+        "first_name" => Ok(Column::FirstName),
+        "last_name" => Ok(Column::LastName),
+        "email" => Ok(Column::Email),
+        other => Err(Box::new(AsCoreError::from(CoreError::DataError(format!(
+            "Users can not be filtered on {}",
+            other
+        ))))),
+    }
+}
+
+/// Condition of `filter`, the `group` field keeping the members of the group of this id
+fn condition(filter: &Filter) -> Result<SimpleExpr, Box<dyn common::error::Error>> {
+    if filter.get_field() != "group" {
+        return Ok(matches(
+            filterable_column(filter.get_field())?,
+            filter.get_value(),
+            filter.get_match_mode(),
+        ));
+    }
+    if filter.get_match_mode() != MatchMode::Exact {
+        return Err(Box::new(AsCoreError::from(CoreError::DataError(
+            String::from("group is only compared exactly"),
+        ))));
+    }
+    let group_id = filter
+        .get_value()
+        .parse::<Uuid>()
+        .map_err(|e| Box::new(AsCoreError::from(e)) as Box<dyn common::error::Error>)?;
+    Ok(Column::Id.in_subquery(
+        Query::select()
+            .column(user_group::Column::UserId)
+            .from(user_group::Entity)
+            .and_where(user_group::Column::GroupId.eq(group_id))
+            .to_owned(),
+    ))
+}
+
+/// Escape character of `LIKE` patterns, a backslash is not portable because of MySQL string literals.
+const LIKE_ESCAPE: char = '!';
+
+/// Compare `LOWER(column)` to the lowercased pattern so that the search ignores case on every backend.
+pub(crate) fn matches<C: ColumnTrait>(
+    column: C,
+    pattern: &str,
+    match_mode: MatchMode,
+) -> SimpleExpr {
+    let pattern = pattern.to_lowercase();
+    let escaped = pattern
+        .chars()
+        .fold(String::with_capacity(pattern.len()), |mut escaped, c| {
+            if c == LIKE_ESCAPE || c == '%' || c == '_' {
+                escaped.push(LIKE_ESCAPE);
+            }
+            escaped.push(c);
+            escaped
+        });
+    let lower = Expr::expr(Func::lower(Expr::col(column.as_column_ref())));
+    match match_mode {
+        MatchMode::Exact => lower.eq(pattern),
+        MatchMode::Prefix => lower.like(LikeExpr::new(format!("{}%", escaped)).escape(LIKE_ESCAPE)),
+        MatchMode::Contains => {
+            lower.like(LikeExpr::new(format!("%{}%", escaped)).escape(LIKE_ESCAPE))
+        }
+    }
 }
 
 #[async_trait]
@@ -26,6 +254,7 @@ impl<'a, B: ConnectionTrait> Manageable<'a, B> for UserManagement {
     type Data = Box<dyn Userable>;
     type Search = Box<dyn Searchable<Id = Box<dyn Display + Sync + Send>>>;
     type Result = Box<dyn SearchResult<Result = Box<dyn Userable>>>;
+    type Patch = Box<dyn Patchable>;
 
     async fn create(
         &self,
@@ -35,6 +264,7 @@ impl<'a, B: ConnectionTrait> Manageable<'a, B> for UserManagement {
         Ok(Box::new(
             ActiveModel {
                 id: Set(Uuid::new_v4()),
+                tenant_id: Set(self.tenant),
                 first_name: Set((*data).get_first_name()),
                 last_name: Set((*data).get_lastname()),
                 email: Set((*data).get_email()),
@@ -42,7 +272,10 @@ impl<'a, B: ConnectionTrait> Manageable<'a, B> for UserManagement {
             }
             .insert(backend)
             .await
-            .map_err(|e| Box::new(AsCoreError::from(e)) as Box<dyn common::error::Error>)?,
+            .map_err(|e| {
+                Box::new(AsCoreError::from_unique(e, UNIQUE_FIELDS))
+                    as Box<dyn common::error::Error>
+            })?,
         ))
     }
 
@@ -60,89 +293,260 @@ impl<'a, B: ConnectionTrait> Manageable<'a, B> for UserManagement {
         } else {
             select_users = Entity::find();
         };
+        select_users = select_users.filter(Column::TenantId.eq(self.tenant));
+        if !search_opt.get_include_deleted() {
+            select_users = select_users.filter(Column::DeletedAt.is_null());
+        }
         if let Some(pattern) = search_opt.get_pattern() {
+            let match_mode = search_opt
+                .get_match_mode()
+                .map_err(|e| Box::new(AsCoreError::from(e)) as Box<dyn common::error::Error>)?;
+            let pattern = pattern.to_string();
             select_users = select_users.filter(
-                Column::FirstName
-                    .eq(pattern.to_string())
-                    .or(Column::LastName.eq(pattern.to_string()))
-                    .or(Column::Email.eq(pattern.to_string())),
+                Condition::any()
+                    .add(matches(Column::FirstName, pattern.as_str(), match_mode))
+                    .add(matches(Column::LastName, pattern.as_str(), match_mode))
+                    .add(matches(Column::Email, pattern.as_str(), match_mode)),
             );
         };
-        let paginator: Paginator<_, SelectModel<Model>> = select_users.paginate(
-            backend,
-            TryInto::<u64>::try_into(search_opt.get_per_page()).unwrap(),
+        for filter in search_opt.get_filters() {
+            select_users = select_users.filter(condition(&filter)?);
+        }
+        if let Some(date_range) = search_opt
+            .get_date_range()
+            .map_err(|e| Box::new(AsCoreError::from(e)) as Box<dyn common::error::Error>)?
+        {
+            let column = match date_range.get_field() {
+                DateField::CreatedAt => Column::CreatedAt,
+                DateField::UpdatedAt => Column::UpdatedAt,
+            };
+            select_users =
+                select_users.filter(column.between(date_range.get_start(), date_range.get_end()));
+        };
+        let cursor = search_opt
+            .get_cursor()
+            .map_err(|e| Box::new(AsCoreError::from(e)) as Box<dyn common::error::Error>)?;
+        let mut keys: cursor::Keys = Vec::new();
+        for sort in search_opt
+            .get_sort()
+            .map_err(|e| Box::new(AsCoreError::from(e)) as Box<dyn common::error::Error>)?
+        {
+            let column = sortable_column(sort.get_field())?;
+            if cursor.is_some() && matches!(column, Column::UpdatedAt) {
+                return Err(Box::new(AsCoreError::from(CoreError::DataError(
+                    String::from(
+                        "updated_at can be null, users can not be sorted on it with a cursor",
+                    ),
+                ))));
+            }
+            keys.push((column, sort.get_direction()));
+        }
+        // The primary key breaks ties so that pages never overlap.
+        keys.push((Column::Id, SortDirection::Ascending));
+        let pagination = search_opt
+            .get_pagination()
+            .map_err(|e| Box::new(AsCoreError::from(e)) as Box<dyn common::error::Error>)?;
+        let per_page = pagination.get_per_page();
+        // Memberships are not dated, the members of a group are only validated by the page digest.
+        let last_modified = if search_opt
+            .get_filters()
+            .iter()
+            .any(|filter| filter.get_field() == "group")
+        {
+            None
+        } else {
+            last_modified(self.tenant, backend).await?
+        };
+
+        let Some(cursor) = cursor else {
+            select_users = keys
+                .iter()
+                .fold(select_users, |select_users, (column, direction)| {
+                    select_users.order_by(*column, order(*direction, true))
+                });
+            let paginator: Paginator<_, SelectModel<Model>> =
+                select_users.paginate(backend, TryInto::<u64>::try_into(per_page).unwrap());
+            let count = paginator
+                .num_items_and_pages()
+                .await
+                .map_err(|e| Box::new(AsCoreError::from(e)) as Box<dyn common::error::Error>)?;
+            let result = Box::new(UserSearchResult {
+                num_pages: Some(count.number_of_pages as usize),
+                total_items: Some(count.number_of_items as usize),
+                page: Some(pagination.get_page()),
+                per_page,
+                result: paginator
+                    .fetch_page(TryInto::<u64>::try_into(pagination.get_page() - 1).unwrap())
+                    .await
+                    .map_err(|e| Box::new(AsCoreError::from(e)) as Box<dyn common::error::Error>)?,
+                next_cursor: None,
+                prev_cursor: None,
+                last_modified,
+            });
+            return Ok(result as Self::Result);
+        };
+
+        // Pages before a cursor are read in the reverse order, then put back in order.
+        let (token, forward) = match &cursor {
+            Cursor::After(token) => (token.as_str(), true),
+            Cursor::Before(token) => (token.as_str(), false),
+        };
+        if !token.is_empty() {
+            select_users =
+                select_users.filter(cursor::seek(&keys, cursor::decode(&keys, token)?, forward));
+        }
+        let mut users = keys
+            .iter()
+            .fold(select_users, |select_users, (column, direction)| {
+                select_users.order_by(*column, order(*direction, forward))
+            })
+            .limit(TryInto::<u64>::try_into(per_page).unwrap() + 1)
+            .all(backend)
+            .await
+            .map_err(|e| Box::new(AsCoreError::from(e)) as Box<dyn common::error::Error>)?;
+        let has_more = users.len() > per_page;
+        users.truncate(per_page);
+        if !forward {
+            users.reverse();
+        }
+        let (first, last) = (
+            users.first().map(|user| cursor::encode(&keys, user)),
+            users.last().map(|user| cursor::encode(&keys, user)),
         );
+        let (next_cursor, prev_cursor) = match forward {
+            true => (
+                last.filter(|_| has_more),
+                first.filter(|_| !token.is_empty()),
+            ),
+            false => (
+                last.filter(|_| !token.is_empty()),
+                first.filter(|_| has_more),
+            ),
+        };
         let result = Box::new(UserSearchResult {
-            num_pages: paginator
-                .num_pages()
-                .await
-                .map_err(|e| Box::new(AsCoreError::from(e)) as Box<dyn common::error::Error>)?
-                as usize,
-            result: paginator
-                .fetch_page(TryInto::<u64>::try_into(search_opt.get_page()).unwrap() - 1)
-                .await
-                .map_err(|e| Box::new(AsCoreError::from(e)) as Box<dyn common::error::Error>)?,
+            num_pages: None,
+            total_items: None,
+            page: None,
+            per_page,
+            result: users,
+            next_cursor,
+            prev_cursor,
+            last_modified,
         });
         Ok(result as Self::Result)
     }
 
+    async fn find(
+        &self,
+        id: Self::Id,
+        include_deleted: bool,
+        backend: &'a B,
+    ) -> Result<Self::Data, Box<dyn common::error::Error>> {
+        Ok(
+            Box::new(find_user(self.tenant, id.as_ref(), include_deleted, backend).await?)
+                as Self::Data,
+        )
+    }
+
     async fn update(
         &self,
         id: Self::Id,
         data: Self::Data,
+        precondition: Precondition,
         backend: &'a B,
     ) -> Result<Self::Data, Box<dyn common::error::Error>> {
-        if let Some(selected_model) = Entity::find_by_id(
-            id.to_string()
-                .as_str()
-                .parse::<Uuid>()
-                .map_err(|e| Box::new(AsCoreError::from(e)) as Box<dyn common::error::Error>)?,
-        )
-        .one(backend)
-        .await
-        .map_err(|e| Box::new(AsCoreError::from(e)) as Box<dyn common::error::Error>)?
-        {
-            let mut active_model = selected_model.into_active_model();
-            active_model.first_name = Set(data.get_first_name());
-            active_model.last_name = Set(data.get_lastname());
-            active_model.email = Set(data.get_email());
-            return Ok(Box::new(
-                active_model
-                    .update(backend)
-                    .await
-                    .map_err(|e| Box::new(AsCoreError::from(e)) as Box<dyn common::error::Error>)?,
-            ) as Self::Data);
-        } else {
-            return Err(Box::new(AsCoreError::from(DbErr::RecordNotFound(
-                String::from("Record not found !"),
-            ))));
+        let selected_model = find_user(self.tenant, id.as_ref(), false, backend).await?;
+        if !precondition.is_met(selected_model.version) {
+            return Err(precondition_failed());
+        }
+        let mut active_model = selected_model.into_active_model();
+        active_model.first_name = Set(data.get_first_name());
+        active_model.last_name = Set(data.get_lastname());
+        active_model.email = Set(data.get_email());
+        Ok(Box::new(save(self.tenant, active_model, &precondition, backend).await?) as Self::Data)
+    }
+
+    async fn patch(
+        &self,
+        id: Self::Id,
+        patch: Self::Patch,
+        precondition: Precondition,
+        backend: &'a B,
+    ) -> Result<Self::Data, Box<dyn common::error::Error>> {
+        let selected_model = find_user(self.tenant, id.as_ref(), false, backend).await?;
+        if !precondition.is_met(selected_model.version) {
+            return Err(precondition_failed());
+        }
+        let mut active_model = selected_model.clone().into_active_model();
+        if let Some(first_name) = patch.get_first_name() {
+            active_model.first_name = Set(first_name);
+        }
+        if let Some(last_name) = patch.get_lastname() {
+            active_model.last_name = Set(last_name);
+        }
+        if let Some(email) = patch.get_email() {
+            active_model.email = Set(email);
+        }
+        if !active_model.is_changed() {
+            return Ok(Box::new(selected_model) as Self::Data);
         }
+        Ok(Box::new(save(self.tenant, active_model, &precondition, backend).await?) as Self::Data)
     }
 
     async fn delete(
         &self,
         id: Self::Id,
+        precondition: Precondition,
         backend: &'a B,
     ) -> Result<(), Box<dyn common::error::Error>> {
-        if let Some(selected_model) = Entity::find_by_id(
-            id.to_string()
-                .as_str()
-                .parse::<Uuid>()
-                .map_err(|e| Box::new(AsCoreError::from(e)) as Box<dyn common::error::Error>)?,
+        let selected_model = find_user(self.tenant, id.as_ref(), false, backend).await?;
+        if !precondition.is_met(selected_model.version) {
+            return Err(precondition_failed());
+        }
+        let mut active_model = selected_model.into_active_model();
+        active_model.deleted_at = Set(Some(Utc::now()));
+        save(self.tenant, active_model, &precondition, backend).await?;
+        Ok(())
+    }
+
+    async fn restore(
+        &self,
+        id: Self::Id,
+        backend: &'a B,
+    ) -> Result<Self::Data, Box<dyn common::error::Error>> {
+        let selected_model = find_user(self.tenant, id.as_ref(), true, backend).await?;
+        if selected_model.deleted_at.is_none() {
+            return Ok(Box::new(selected_model) as Self::Data);
+        }
+        let mut active_model = selected_model.into_active_model();
+        active_model.deleted_at = Set(None);
+        Ok(
+            Box::new(save(self.tenant, active_model, &Precondition::Any, backend).await?)
+                as Self::Data,
         )
-        .one(backend)
-        .await
-        .map_err(|e| Box::new(AsCoreError::from(e)) as Box<dyn common::error::Error>)?
+    }
+
+    async fn purge(
+        &self,
+        id: Self::Id,
+        backend: &'a B,
+    ) -> Result<(), Box<dyn common::error::Error>> {
+        let selected_model = find_user(self.tenant, id.as_ref(), true, backend).await?;
+        if selected_model.deleted_at.is_none() {
+            return Err(not_deleted());
+        }
+        // Only a user still deleted is purged, in case it was restored meanwhile.
+        match Entity::delete_many()
+            .filter(Column::Id.eq(selected_model.id))
+            .filter(Column::TenantId.eq(self.tenant))
+            .filter(Column::DeletedAt.is_not_null())
+            .exec(backend)
+            .await
+            .map_err(|e| Box::new(AsCoreError::from(e)) as Box<dyn common::error::Error>)?
+            .rows_affected
         {
-            return selected_model
-                .delete(backend)
-                .await
-                .map(|_| ())
-                .map_err(|e| Box::new(AsCoreError::from(e)) as Box<dyn common::error::Error>);
-        } else {
-            return Err(Box::new(AsCoreError::from(DbErr::RecordNotFound(
-                String::from("Record not found !"),
-            ))));
+            0 => Err(not_deleted()),
+            _ => Ok(()),
         }
     }
 }
@@ -150,10 +554,34 @@ impl<'a, B: ConnectionTrait> Manageable<'a, B> for UserManagement {
 impl SearchResult for UserSearchResult {
     type Result = Box<dyn Userable>;
 
-    fn get_num_pages(&self) -> usize {
+    fn get_num_pages(&self) -> Option<usize> {
         self.num_pages
     }
 
+    fn get_total_items(&self) -> Option<usize> {
+        self.total_items
+    }
+
+    fn get_page(&self) -> Option<usize> {
+        self.page
+    }
+
+    fn get_per_page(&self) -> usize {
+        self.per_page
+    }
+
+    fn get_next_cursor(&self) -> Option<String> {
+        self.next_cursor.clone()
+    }
+
+    fn get_prev_cursor(&self) -> Option<String> {
+        self.prev_cursor.clone()
+    }
+
+    fn get_last_modified(&self) -> Option<DateTime<Utc>> {
+        self.last_modified
+    }
+
     fn get_result(&self) -> Box<dyn Iterator<Item = Self::Result>> {
         Box::new(
             self.result